@@ -0,0 +1,194 @@
+//! Simple wall-clock benchmarks, run with `cargo bench`.
+//!
+//! No external harness: each case is timed with `Instant` over a fixed number
+//! of iterations and the mean time per iteration is printed.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use bytes::Bytes;
+use oxidart::{OxidArt, OxidArtN};
+
+fn bench<F: FnMut()>(name: &str, iterations: u32, mut f: F) {
+    // Warm up
+    f();
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    let per_iter = start.elapsed() / iterations;
+    println!("{name:<40} {per_iter:>12.2?}/iter");
+}
+
+/// 10k keys sharing long prefixes, to get a deep tree
+fn deep_tree() -> OxidArt {
+    let mut art = OxidArt::new();
+    for i in 0..10_000u32 {
+        let key = format!(
+            "tenant:0042:region:eu-west:bucket:{:02}:object:{i:08}",
+            i % 16
+        );
+        art.set(Bytes::from(key), Bytes::from_static(b"value"));
+    }
+    art
+}
+
+/// Every node has 30 children: 3 levels over a 30-letter alphabet (27k keys)
+fn dense_keys() -> Vec<Bytes> {
+    let alphabet = b"abcdefghijklmnopqrstuvwxyz0123";
+    let mut keys = Vec::new();
+    for &a in alphabet {
+        for &b in alphabet {
+            for &c in alphabet {
+                keys.push(Bytes::copy_from_slice(&[a, b, c]));
+            }
+        }
+    }
+    keys
+}
+
+/// Every node has 16 children: 4 levels over a 16-letter alphabet (65k keys)
+fn sixteen_keys() -> Vec<Bytes> {
+    let alphabet = b"aeiouybcdfghjklm";
+    let mut keys = Vec::new();
+    for &a in alphabet {
+        for &b in alphabet {
+            for &c in alphabet {
+                for &d in alphabet {
+                    keys.push(Bytes::copy_from_slice(&[a, b, c, d]));
+                }
+            }
+        }
+    }
+    keys
+}
+
+fn bench_dense<const N: usize>(tree: &str, keys: &[Bytes]) {
+    let mut art = OxidArtN::<N>::new();
+    for key in keys {
+        art.set(key.clone(), Bytes::from_static(b"value"));
+    }
+    bench(&format!("get {tree}, inline capacity {N}"), 20, || {
+        for key in keys {
+            black_box(art.get(key.clone()));
+        }
+    });
+}
+
+fn main() {
+    let art = deep_tree();
+
+    bench("getn deep tree (10k entries)", 100, || {
+        black_box(art.getn(Bytes::from_static(b"tenant:0042:")));
+    });
+
+    let mut lookups = deep_tree();
+    let probes: Vec<Bytes> = (0..10_000u32)
+        .step_by(7)
+        .map(|i| {
+            Bytes::from(format!(
+                "tenant:0042:region:eu-west:bucket:{:02}:object:{i:08}",
+                i % 16
+            ))
+        })
+        .collect();
+    bench("get(..).is_some() deep tree", 100, || {
+        for key in &probes {
+            black_box(lookups.get(key.clone()).is_some());
+        }
+    });
+    bench("contains_key deep tree", 100, || {
+        for key in &probes {
+            black_box(lookups.contains_key(key.clone()));
+        }
+    });
+
+    // Fields of one record: long shared prefix, short distinct suffixes
+    let record: Vec<Bytes> = [
+        "name", "email", "phone", "city", "zip", "plan", "created", "seen",
+    ]
+    .iter()
+    .flat_map(|field| {
+        (0..2).map(move |i| {
+            Bytes::from(format!(
+                "tenant:0042:region:eu-west:bucket:07:object:00000007:{field}{i}"
+            ))
+        })
+    })
+    .collect();
+    for key in &record {
+        lookups.set(key.clone(), Bytes::from_static(b"field"));
+    }
+    bench("get x16 record fields", 10_000, || {
+        for key in &record {
+            black_box(lookups.get_slice(key));
+        }
+    });
+    bench("get_many 16 record fields", 10_000, || {
+        black_box(lookups.get_many(&record));
+    });
+
+    let keys = dense_keys();
+    bench_dense::<4>("dense tree", &keys);
+    bench_dense::<32>("dense tree", &keys);
+    // Child lookups in a Node16 then inline, compare with `--features simd`
+    let keys = sixteen_keys();
+    bench_dense::<4>("16-child nodes", &keys);
+    bench_dense::<16>("16-child nodes", &keys);
+
+    // Every node has 256 children, in a Node256
+    let wide: Vec<Bytes> = (0..=255u8)
+        .flat_map(|a| (0..=255u8).map(move |b| Bytes::copy_from_slice(&[b'w', a, b])))
+        .collect();
+    let mut wide_art = OxidArt::new();
+    for key in &wide {
+        wide_art.set(key.clone(), Bytes::from_static(b"value"));
+    }
+    bench("get wide nodes (256 children, 65k keys)", 20, || {
+        for key in &wide {
+            black_box(wide_art.get_slice(key));
+        }
+    });
+
+    let mut sorted: Vec<(Bytes, Bytes)> = (0..100_000u32)
+        .map(|i| {
+            (
+                Bytes::from(format!("user:{i:08}:profile")),
+                Bytes::from_static(b"v"),
+            )
+        })
+        .collect();
+    sorted.sort();
+    bench("set sorted (100k entries)", 10, || {
+        let mut art = OxidArt::new();
+        for (key, val) in &sorted {
+            art.set(key.clone(), val.clone());
+        }
+        black_box(art);
+    });
+    bench("set sorted, with_capacity (100k entries)", 10, || {
+        let mut art = OxidArt::with_capacity(2 * sorted.len());
+        for (key, val) in &sorted {
+            art.set(key.clone(), val.clone());
+        }
+        black_box(art);
+    });
+    for (name, mut art) in [
+        ("new()", OxidArt::new()),
+        (
+            "with_capacity(200k)",
+            OxidArt::with_capacity(2 * sorted.len()),
+        ),
+    ] {
+        let mut reallocations = 0;
+        for (key, val) in &sorted {
+            let capacity = art.capacity();
+            art.set(key.clone(), val.clone());
+            reallocations += usize::from(art.capacity() != capacity);
+        }
+        println!("{name:<40} {reallocations:>12} slab reallocations");
+    }
+    bench("from_sorted (100k entries)", 10, || {
+        black_box(OxidArt::from_sorted(sorted.iter().cloned()));
+    });
+}
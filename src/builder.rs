@@ -0,0 +1,97 @@
+//! Builder gathering the optional configuration of a tree.
+
+use crate::OxidArt;
+use crate::intern::ValuePool;
+use crate::lru::Lru;
+
+/// Configures an [`OxidArt`] before creating it.
+///
+/// `OxidArt::new()` stays the zero-config default; the builder is the place
+/// where new options are added as the crate grows.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use oxidart::OxidArtBuilder;
+///
+/// let tree = OxidArtBuilder::new()
+///     .capacity(100_000)
+///     .max_entries(Some(50_000))
+///     .value_interning(true)
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct OxidArtBuilder {
+    capacity: usize,
+    max_entries: Option<usize>,
+    value_interning: bool,
+    max_key_len: Option<usize>,
+}
+
+impl Default for OxidArtBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OxidArtBuilder {
+    /// Creates a builder with the same settings as `OxidArt::new()`.
+    pub fn new() -> Self {
+        Self {
+            capacity: 1024,
+            max_entries: None,
+            value_interning: false,
+            max_key_len: None,
+        }
+    }
+
+    /// Number of nodes pre-allocated, before the node slab has to grow.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Maximum number of entries, evicting the least recently used ones beyond it
+    /// (see `OxidArt::with_max_entries`). `None` means unbounded.
+    pub fn max_entries(mut self, max_entries: Option<usize>) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Whether equal values share one buffer (see `OxidArt::with_value_interning`).
+    pub fn value_interning(mut self, value_interning: bool) -> Self {
+        self.value_interning = value_interning;
+        self
+    }
+
+    /// Longest key accepted by `OxidArt::try_set`, which returns
+    /// `KeyError::TooLong` beyond it. `None` (the default) means unbounded.
+    pub fn max_key_len(mut self, max_key_len: Option<usize>) -> Self {
+        self.max_key_len = max_key_len;
+        self
+    }
+
+    /// Creates the configured tree.
+    pub fn build(self) -> OxidArt {
+        let mut art = OxidArt::with_node_capacity(self.capacity);
+        art.lru = self.max_entries.map(Lru::new);
+        art.value_pool = self.value_interning.then(ValuePool::new);
+        art.max_key_len = self.max_key_len;
+        art
+    }
+}
+
+impl OxidArt {
+    /// Returns a builder to configure a new tree.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    ///
+    /// let tree = OxidArt::builder().capacity(4096).build();
+    /// ```
+    pub fn builder() -> OxidArtBuilder {
+        OxidArtBuilder::new()
+    }
+}
@@ -0,0 +1,279 @@
+//! Bulk loading from pre-sorted input or any iterator, merging trees, and conversions
+//! from and to `BTreeMap`.
+
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use bytes::Bytes;
+
+use crate::OxidArtN;
+
+impl<const N: usize, V: Clone> OxidArtN<N, V> {
+    /// Builds a tree from key-value pairs given in ascending key order.
+    ///
+    /// Each key only diverges from the previous one at their common prefix, so the
+    /// tree is built by keeping the active root-to-leaf path: nodes past the
+    /// divergence point are popped, at most one node is split, and the new key is
+    /// attached as a single leaf. No lookup is performed from the root.
+    ///
+    /// Duplicate keys are allowed, the last value wins (as with repeated `set`).
+    /// If a key is smaller than the previous one, the input is not sorted: this
+    /// triggers a debug assertion, and in release builds the remaining pairs are
+    /// inserted with `set`, so the result is still correct, just not faster.
+    ///
+    /// # Arguments
+    ///
+    /// * `pairs` - Key-value pairs in ascending key order.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let pairs = vec![
+    ///     (Bytes::from_static(b"user:1"), Bytes::from_static(b"alice")),
+    ///     (Bytes::from_static(b"user:2"), Bytes::from_static(b"bob")),
+    /// ];
+    /// let mut tree = OxidArt::from_sorted(pairs.into_iter());
+    ///
+    /// assert_eq!(tree.get(Bytes::from_static(b"user:2")), Some(Bytes::from_static(b"bob")));
+    /// ```
+    pub fn from_sorted(mut pairs: impl Iterator<Item = (Bytes, V)>) -> Self {
+        let mut art = Self::default();
+        // Active path of the previous key: (node, start, end), the node covering
+        // `prev[..end]` with its compression being `prev[start..end]`
+        let mut path: Vec<(u32, usize, usize)> = vec![(art.root_idx, 0, 0)];
+        let mut prev: Option<Bytes> = None;
+
+        while let Some((key, val)) = pairs.next() {
+            let common_len = match &prev {
+                Some(prev) => {
+                    debug_assert!(key >= *prev, "from_sorted input is not sorted");
+                    if key < *prev {
+                        art.set(key, val);
+                        for (key, val) in pairs {
+                            art.set(key, val);
+                        }
+                        return art;
+                    }
+                    prev.iter()
+                        .zip(key.iter())
+                        .take_while(|(a, b)| a == b)
+                        .count()
+                }
+                None => 0,
+            };
+
+            // Drop the nodes reached through a radix past the divergence point
+            while path.last().is_some_and(|&(_, start, _)| start > common_len) {
+                path.pop();
+            }
+            let top = path.last_mut().expect("root is always on the path");
+            let idx = top.0;
+            if top.2 > common_len {
+                art.split_node(idx, common_len - top.1);
+                top.2 = common_len;
+            }
+
+            if key.len() == common_len {
+                // A duplicate key overwrites the value stored just before
+                art.len += usize::from(art.get_node(idx).val.is_none());
+                #[cfg(feature = "ttl")]
+                art.get_node_mut(idx).set_val(val, crate::NO_EXPIRY);
+                #[cfg(not(feature = "ttl"))]
+                art.get_node_mut(idx).set_val(val);
+            } else {
+                let compression = &key[common_len + 1..];
+                #[cfg(feature = "ttl")]
+                let leaf = art.create_node_with_val(
+                    idx,
+                    key[common_len],
+                    val,
+                    compression,
+                    crate::NO_EXPIRY,
+                );
+                #[cfg(not(feature = "ttl"))]
+                let leaf = art.create_node_with_val(idx, key[common_len], val, compression);
+                path.push((leaf, common_len + 1, key.len()));
+            }
+            prev = Some(key);
+        }
+
+        art
+    }
+
+    /// Moves every entry of `other` into this tree, calling `resolve` on the keys both hold.
+    ///
+    /// `resolve(key, existing, incoming)` returns the value kept for a key
+    /// stored in both trees; the others are inserted as they are. `other` is
+    /// drained, so its values are moved rather than cloned. The empty key is
+    /// merged like any other. With the `ttl` feature, expired entries of either
+    /// tree are ignored, incoming entries keep their expiration, and a resolved
+    /// value keeps the expiration of the existing entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The tree to drain into this one.
+    /// * `resolve` - Picks the value of a key present in both trees.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut shard_a = OxidArt::new();
+    /// shard_a.set(Bytes::from_static(b"hits:home"), Bytes::from_static(b"3"));
+    /// let mut shard_b = OxidArt::new();
+    /// shard_b.set(Bytes::from_static(b"hits:home"), Bytes::from_static(b"4"));
+    /// shard_b.set(Bytes::from_static(b"hits:about"), Bytes::from_static(b"1"));
+    ///
+    /// // Last write wins
+    /// shard_a.merge(shard_b, |_key, _existing, incoming| incoming);
+    /// assert_eq!(shard_a.get(Bytes::from_static(b"hits:home")), Some(Bytes::from_static(b"4")));
+    /// assert_eq!(shard_a.len(), 2);
+    /// ```
+    pub fn merge<F>(&mut self, mut other: Self, mut resolve: F)
+    where
+        F: FnMut(&Bytes, V, V) -> V,
+    {
+        #[cfg(feature = "ttl")]
+        let other_now = other.now;
+        let mut stack = vec![(other.root_idx, Vec::new())];
+        while let Some((idx, mut key)) = stack.pop() {
+            let node = other.get_node_mut(idx);
+            key.extend_from_slice(&node.compression);
+            #[cfg(feature = "ttl")]
+            let incoming = node
+                .val
+                .take()
+                .filter(|&(_, ttl)| ttl == crate::NO_EXPIRY || ttl >= other_now);
+            #[cfg(not(feature = "ttl"))]
+            let incoming = node.val.take();
+
+            for (radix, child_idx) in other.all_children(idx) {
+                let mut child_key = key.clone();
+                child_key.push(radix);
+                stack.push((child_idx, child_key));
+            }
+            if let Some(incoming) = incoming {
+                self.merge_entry(Bytes::from(key), incoming, &mut resolve);
+            }
+        }
+    }
+
+    /// Stores one drained entry of `merge`, resolving it against a live existing value
+    #[cfg(feature = "ttl")]
+    fn merge_entry<F>(&mut self, key: Bytes, (val, ttl): (V, u64), resolve: &mut F)
+    where
+        F: FnMut(&Bytes, V, V) -> V,
+    {
+        let now = self.now;
+        let existing = self.find_key_node(&key).and_then(|idx| {
+            let node = self.get_node_mut(idx);
+            node.get_value(now)?;
+            let (existing, existing_ttl) = node.val.take()?;
+            Some((idx, existing, existing_ttl))
+        });
+        match existing {
+            Some((idx, existing, existing_ttl)) => {
+                let resolved = self.intern_value(resolve(&key, existing, val));
+                self.get_node_mut(idx).set_val(resolved, existing_ttl);
+            }
+            None => {
+                self.set_internal(key.clone(), ttl, val)
+                    .unwrap_or_else(|e| panic!("merge failed: {e}"));
+            }
+        }
+        self.lru_record_write(&key);
+    }
+
+    #[cfg(not(feature = "ttl"))]
+    fn merge_entry<F>(&mut self, key: Bytes, val: V, resolve: &mut F)
+    where
+        F: FnMut(&Bytes, V, V) -> V,
+    {
+        let existing = self
+            .find_key_node(&key)
+            .and_then(|idx| Some((idx, self.get_node_mut(idx).take_stored_value()?)));
+        match existing {
+            Some((idx, existing)) => {
+                let resolved = self.intern_value(resolve(&key, existing, val));
+                self.get_node_mut(idx).set_val(resolved);
+            }
+            None => {
+                self.set_internal(key.clone(), val)
+                    .unwrap_or_else(|e| panic!("merge failed: {e}"));
+            }
+        }
+        self.lru_record_write(&key);
+    }
+
+    /// Copies every entry into a `BTreeMap`, keyed by the full key bytes.
+    ///
+    /// The map iterates in the same ascending byte order as the tree keys.
+    /// With the `ttl` feature, expired entries are skipped and expirations are
+    /// not carried over.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"b"), Bytes::from_static(b"2"));
+    /// tree.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+    ///
+    /// let map = tree.to_btree_map();
+    /// assert_eq!(map.keys().next(), Some(&b"a".to_vec()));
+    /// ```
+    pub fn to_btree_map(&self) -> BTreeMap<Vec<u8>, V> {
+        let mut key = self.get_node(self.root_idx).compression.to_vec();
+        self.fold_subtree(
+            self.root_idx,
+            &mut key,
+            BTreeMap::new(),
+            &mut |mut map, key, val| {
+                map.insert(key.to_vec(), val.clone());
+                map
+            },
+        )
+    }
+}
+
+impl<const N: usize> From<BTreeMap<Vec<u8>, Bytes>> for OxidArtN<N> {
+    /// Builds a tree from the map with `from_sorted`, the map being already sorted.
+    ///
+    /// Only implemented for `Bytes` values, so that `OxidArt::from(map)` infers
+    /// the value type; other maps go through `from_sorted` directly.
+    fn from(map: BTreeMap<Vec<u8>, Bytes>) -> Self {
+        Self::from_sorted(map.into_iter().map(|(key, val)| (Bytes::from(key), val)))
+    }
+}
+
+impl<const N: usize, V: Clone> FromIterator<(Bytes, V)> for OxidArtN<N, V> {
+    /// Builds a tree by inserting every pair with `set`, the last value winning
+    /// on duplicate keys.
+    ///
+    /// Every new key takes at least one node, so the node slab is pre-sized from
+    /// the iterator's lower size hint. Use `from_sorted` when the pairs are
+    /// already in ascending key order.
+    fn from_iter<I: IntoIterator<Item = (Bytes, V)>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut art = Self::with_node_capacity(iter.size_hint().0.max(1024));
+        art.extend(iter);
+        art
+    }
+}
+
+impl<const N: usize, V: Clone> Extend<(Bytes, V)> for OxidArtN<N, V> {
+    /// Inserts every pair with `set`, the last value winning on duplicate keys.
+    fn extend<I: IntoIterator<Item = (Bytes, V)>>(&mut self, iter: I) {
+        for (key, val) in iter {
+            self.set(key, val);
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! `Debug` output rendering the node layout of the tree.
+
+use alloc::vec;
+use core::fmt;
+
+use crate::OxidArtN;
+
+impl<const N: usize> fmt::Debug for OxidArtN<N> {
+    /// Prints one line per node, indented by depth, children in ascending radix order.
+    ///
+    /// Each line shows the radix leading to the node, its slab index, its
+    /// compression (non-printable bytes escaped) and its value length. With the
+    /// `ttl` feature, the expiration timestamp is shown too, values past `now`
+    /// being marked expired. This is a read-only walk meant for test output,
+    /// its exact layout is not stable.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    /// tree.set(Bytes::from_static(b"user:2"), Bytes::from_static(b"bob"));
+    ///
+    /// println!("{tree:?}");
+    /// // OxidArt { len: 2, nodes: 4 }
+    /// // #0 ""
+    /// //   [u] #1 "ser:"
+    /// //     [1] #2 "" value: 5 bytes
+    /// //     [2] #3 "" value: 3 bytes
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "OxidArt {{ len: {}, nodes: {} }}",
+            self.len,
+            self.map.len()
+        )?;
+        let mut stack = vec![(self.root_idx, None, 0usize)];
+        while let Some((idx, radix, depth)) = stack.pop() {
+            let node = self.get_node(idx);
+            write!(f, "{:indent$}", "", indent = depth * 2)?;
+            if let Some(radix) = radix {
+                write!(f, "[{}] ", [radix].escape_ascii())?;
+            }
+            write!(f, "#{idx} \"{}\"", node.compression.escape_ascii())?;
+
+            #[cfg(feature = "ttl")]
+            if let Some((val, ttl)) = &node.val {
+                write!(f, " value: {} bytes", val.len())?;
+                if node.is_expired(self.now) {
+                    write!(f, ", expired at {ttl}")?;
+                } else if *ttl != crate::NO_EXPIRY {
+                    write!(f, ", expires at {ttl}")?;
+                }
+            }
+            #[cfg(not(feature = "ttl"))]
+            if let Some(val) = &node.val {
+                write!(f, " value: {} bytes", val.len())?;
+            }
+            writeln!(f)?;
+
+            for (radix, child_idx) in self.children(idx).rev() {
+                stack.push((child_idx, Some(radix), depth + 1));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+//! Errors returned by the checked (`try_*`) operations.
+
+use core::fmt;
+
+/// Reason why a checked operation could not be applied.
+///
+/// The tree is left unchanged when one of these is returned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OxidArtError {
+    /// The node slab is out of `u32` indexes.
+    CapacityExceeded,
+    /// The key was rejected before reaching the tree.
+    InvalidKey(KeyError),
+}
+
+/// Reason why a key is rejected by `try_set`.
+///
+/// Every byte value is a valid key byte, so only the configured limits apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyError {
+    /// The key is longer than the maximum set with `OxidArtBuilder::max_key_len`.
+    TooLong {
+        /// Length of the rejected key.
+        len: usize,
+        /// Maximum key length of the tree.
+        max: usize,
+    },
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyError::TooLong { len, max } => {
+                write!(f, "key of {len} bytes exceeds the maximum of {max}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for KeyError {}
+
+impl From<KeyError> for OxidArtError {
+    fn from(err: KeyError) -> Self {
+        OxidArtError::InvalidKey(err)
+    }
+}
+
+impl fmt::Display for OxidArtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OxidArtError::CapacityExceeded => f.write_str("tree is out of node indexes"),
+            OxidArtError::InvalidKey(err) => write!(f, "invalid key: {err}"),
+        }
+    }
+}
+
+impl core::error::Error for OxidArtError {}
@@ -0,0 +1,56 @@
+//! Streaming export of the tree contents to an arbitrary writer.
+
+use std::io::{self, Write};
+
+use bytes::Bytes;
+
+use crate::OxidArtN;
+
+/// Writes a `u32` little-endian length followed by the bytes.
+pub(crate) fn write_chunk<W: Write>(w: &mut W, bytes: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(bytes.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "chunk longer than u32::MAX"))?;
+    w.write_all(&len.to_le_bytes())?;
+    w.write_all(bytes)
+}
+
+impl<const N: usize> OxidArtN<N> {
+    /// Streams every entry to `w` without collecting them first.
+    ///
+    /// Each entry is written as a record: the key then the value, each prefixed by
+    /// its length as a `u32` little-endian. Keys are rebuilt in a single scratch
+    /// buffer, so memory stays bounded whatever the tree size.
+    /// With the `ttl` feature, expired entries are skipped.
+    ///
+    /// # Returns
+    ///
+    /// The number of entries written, or the first I/O error encountered.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"key"), Bytes::from_static(b"value"));
+    ///
+    /// let mut file = std::fs::File::create("backup.bin")?;
+    /// let written = tree.write_entries(&mut file)?;
+    /// assert_eq!(written, 1);
+    /// ```
+    pub fn write_entries<W: Write>(&self, w: &mut W) -> io::Result<usize> {
+        let mut key = self.get_node(self.root_idx).compression.to_vec();
+        self.fold_subtree(
+            self.root_idx,
+            &mut key,
+            Ok(0),
+            &mut |written: io::Result<usize>, key: &[u8], val: &Bytes| {
+                let written = written?;
+                write_chunk(w, key)?;
+                write_chunk(w, val)?;
+                Ok(written + 1)
+            },
+        )
+    }
+}
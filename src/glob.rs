@@ -0,0 +1,233 @@
+//! Glob pattern matching, guided by the tree structure.
+//!
+//! Patterns follow the Redis `KEYS` syntax:
+//! - `*` matches any sequence of bytes (including none)
+//! - `?` matches exactly one byte
+//! - `[abc]`, `[a-z]` match one byte of the set, `[^abc]` (or `[!abc]`) one byte outside it
+//! - `\x` matches `x` literally
+//!
+//! The pattern is compiled to a small NFA whose state is the set of pattern
+//! positions reachable so far. The state is advanced byte by byte while the
+//! tree is descended, so whole subtrees are pruned as soon as no position is
+//! left, and keys never need to be rebuilt.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use smallvec::SmallVec;
+
+use crate::OxidArtN;
+
+enum Token {
+    Byte(u8),
+    Any,
+    Star,
+    Class {
+        negated: bool,
+        ranges: SmallVec<[(u8, u8); 4]>,
+    },
+}
+
+impl Token {
+    /// Whether this single-byte token accepts `b` (`Star` is handled by the NFA)
+    fn accepts(&self, b: u8) -> bool {
+        match self {
+            Token::Byte(c) => *c == b,
+            Token::Any => true,
+            Token::Star => false,
+            Token::Class { negated, ranges } => {
+                ranges.iter().any(|&(lo, hi)| lo <= b && b <= hi) != *negated
+            }
+        }
+    }
+}
+
+/// Set of pattern positions, one bit per position (inline up to 127 tokens)
+pub(crate) type GlobState = SmallVec<[u64; 2]>;
+
+/// A compiled glob pattern.
+pub(crate) struct Glob {
+    tokens: Vec<Token>,
+}
+
+impl Glob {
+    pub(crate) fn new(pattern: &[u8]) -> Self {
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < pattern.len() {
+            match pattern[i] {
+                b'*' => {
+                    if !matches!(tokens.last(), Some(Token::Star)) {
+                        tokens.push(Token::Star);
+                    }
+                }
+                b'?' => tokens.push(Token::Any),
+                b'\\' if i + 1 < pattern.len() => {
+                    i += 1;
+                    tokens.push(Token::Byte(pattern[i]));
+                }
+                b'[' => match Self::parse_class(&pattern[i + 1..]) {
+                    Some((token, len)) => {
+                        tokens.push(token);
+                        i += len;
+                    }
+                    // No closing bracket: match it literally
+                    None => tokens.push(Token::Byte(b'[')),
+                },
+                b => tokens.push(Token::Byte(b)),
+            }
+            i += 1;
+        }
+        Self { tokens }
+    }
+
+    /// Parses a class body (after `[`), returning the token and the bytes consumed
+    /// including the closing `]`
+    fn parse_class(body: &[u8]) -> Option<(Token, usize)> {
+        let mut i = 0;
+        let negated = matches!(body.first(), Some(b'^' | b'!'));
+        if negated {
+            i += 1;
+        }
+        let mut ranges = SmallVec::new();
+        loop {
+            let mut lo = *body.get(i)?;
+            if lo == b']' {
+                return Some((Token::Class { negated, ranges }, i + 1));
+            }
+            if lo == b'\\' {
+                i += 1;
+                lo = *body.get(i)?;
+            }
+            let mut hi = lo;
+            if body.get(i + 1) == Some(&b'-') && body.get(i + 2).is_some_and(|&b| b != b']') {
+                i += 2;
+                hi = body[i];
+                if hi == b'\\' {
+                    i += 1;
+                    hi = *body.get(i)?;
+                }
+            }
+            ranges.push((lo.min(hi), lo.max(hi)));
+            i += 1;
+        }
+    }
+
+    /// State before any byte is consumed
+    pub(crate) fn start(&self) -> GlobState {
+        let mut state = self.empty_state();
+        Self::insert(&mut state, 0);
+        self.close(&mut state);
+        state
+    }
+
+    /// State after consuming `b` from `state`
+    pub(crate) fn step(&self, state: &GlobState, b: u8) -> GlobState {
+        let mut next = self.empty_state();
+        for (pos, token) in self.tokens.iter().enumerate() {
+            if !Self::contains(state, pos) {
+                continue;
+            }
+            match token {
+                Token::Star => Self::insert(&mut next, pos),
+                token if token.accepts(b) => Self::insert(&mut next, pos + 1),
+                _ => {}
+            }
+        }
+        self.close(&mut next);
+        next
+    }
+
+    /// `true` if no key going through this state can match
+    pub(crate) fn is_dead(state: &GlobState) -> bool {
+        state.iter().all(|&word| word == 0)
+    }
+
+    /// `true` if the bytes consumed so far match the whole pattern
+    pub(crate) fn is_match(&self, state: &GlobState) -> bool {
+        Self::contains(state, self.tokens.len())
+    }
+
+    fn empty_state(&self) -> GlobState {
+        SmallVec::from_elem(0, self.tokens.len() / 64 + 1)
+    }
+
+    /// A `*` may match nothing: its position also reaches the next one
+    fn close(&self, state: &mut GlobState) {
+        for (pos, token) in self.tokens.iter().enumerate() {
+            if matches!(token, Token::Star) && Self::contains(state, pos) {
+                Self::insert(state, pos + 1);
+            }
+        }
+    }
+
+    fn contains(state: &GlobState, pos: usize) -> bool {
+        state[pos / 64] & (1 << (pos % 64)) != 0
+    }
+
+    fn insert(state: &mut GlobState, pos: usize) {
+        state[pos / 64] |= 1 << (pos % 64);
+    }
+}
+
+impl<const N: usize, V: Clone> OxidArtN<N, V> {
+    /// Counts the keys matching a glob pattern, without collecting them.
+    ///
+    /// The pattern is matched while descending the tree: subtrees that can no
+    /// longer match are skipped, and keys are never rebuilt or cloned.
+    /// See the module documentation for the pattern syntax (`*`, `?`, `[a-z]`, `\`).
+    /// With the `ttl` feature, expired entries are not counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The glob pattern the whole key must match.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"user:1:active"), Bytes::from_static(b"1"));
+    /// tree.set(Bytes::from_static(b"user:2:active"), Bytes::from_static(b"1"));
+    /// tree.set(Bytes::from_static(b"user:3:banned"), Bytes::from_static(b"1"));
+    ///
+    /// assert_eq!(tree.count_matching(b"user:*:active"), 2);
+    /// ```
+    pub fn count_matching(&self, pattern: &[u8]) -> usize {
+        let glob = Glob::new(pattern);
+        let mut count = 0;
+        let mut stack = vec![(self.root_idx, glob.start())];
+
+        while let Some((idx, mut state)) = stack.pop() {
+            let node = self.get_node(idx);
+            for &b in node.compression.iter() {
+                if Glob::is_dead(&state) {
+                    break;
+                }
+                state = glob.step(&state, b);
+            }
+            if Glob::is_dead(&state) {
+                continue;
+            }
+
+            #[cfg(feature = "ttl")]
+            let has_value = node.get_value(self.now).is_some();
+            #[cfg(not(feature = "ttl"))]
+            let has_value = node.get_value().is_some();
+            if has_value && glob.is_match(&state) {
+                count += 1;
+            }
+
+            self.iter_all_children(idx, |radix, child_idx| {
+                let child_state = glob.step(&state, radix);
+                if !Glob::is_dead(&child_state) {
+                    stack.push((child_idx, child_state));
+                }
+            });
+        }
+
+        count
+    }
+}
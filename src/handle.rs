@@ -0,0 +1,98 @@
+//! Versioned handles to entries, for indexes kept outside of the tree.
+
+use bytes::Bytes;
+
+use crate::OxidArtN;
+
+/// Reference to the node holding an entry, returned by [`OxidArtN::set_indexed`].
+///
+/// A handle is the slab index of the node plus the generation of its slot.
+/// The generation changes whenever the slot is reused for another node, or the
+/// node starts to stand for another key (split or recompression), so a stale
+/// handle is detected instead of silently resolving to another entry.
+///
+/// A handle stays valid across unrelated mutations, and across updates and
+/// in-place deletions of its own key. Structural changes around the entry can
+/// move the value to another node though: inserting a key that splits the
+/// entry's compressed path, or deleting the entry's last sibling so that its
+/// node is merged into its parent. The handle is then stale, even though the
+/// key is still stored; look the key up again to get a fresh handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle {
+    idx: u32,
+    version: u32,
+}
+
+impl Handle {
+    /// Raw slab index of the node.
+    ///
+    /// Unlike the handle itself, the raw index can silently point to another
+    /// entry once the handle is stale.
+    pub fn index(self) -> u32 {
+        self.idx
+    }
+}
+
+impl<const N: usize, V: Clone> OxidArtN<N, V> {
+    /// Inserts or updates a key-value pair like `set`, and returns a handle to the entry.
+    ///
+    /// The handle resolves the entry in O(1) with `get_at`, without walking the
+    /// tree. See [`Handle`] for the mutations that make it stale.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to insert.
+    /// * `val` - The value to associate with the key.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// let handle = tree.set_indexed(Bytes::from_static(b"order:42"), Bytes::from_static(b"paid"));
+    /// tree.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    ///
+    /// assert_eq!(tree.get_at(handle), Some(&Bytes::from_static(b"paid")));
+    /// ```
+    pub fn set_indexed(&mut self, key: Bytes, val: V) -> Handle {
+        #[cfg(feature = "ttl")]
+        let result = self.set_internal(key.clone(), crate::NO_EXPIRY, val);
+        #[cfg(not(feature = "ttl"))]
+        let result = self.set_internal(key.clone(), val);
+        result.unwrap_or_else(|e| panic!("set_indexed failed: {e}"));
+
+        let idx = self.find_key_node(&key).expect("a key just set has a node");
+        let handle = Handle {
+            idx,
+            version: self.versions[idx as usize],
+        };
+        // An eviction of the key itself frees the node, leaving the handle stale
+        self.lru_record_write(&key);
+        handle
+    }
+
+    /// Returns the value an entry handle points to, in O(1).
+    ///
+    /// Unlike `get`, the key is not marked as recently used with `with_max_entries`.
+    /// With the `ttl` feature, an expired value is not returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - A handle returned by `set_indexed`.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the entry was deleted or the handle is stale.
+    pub fn get_at(&self, handle: Handle) -> Option<&V> {
+        if self.versions.get(handle.idx as usize) != Some(&handle.version) {
+            return None;
+        }
+        let node = self.try_get_node(handle.idx)?;
+        #[cfg(feature = "ttl")]
+        return node.get_value(self.now);
+        #[cfg(not(feature = "ttl"))]
+        node.get_value()
+    }
+}
@@ -0,0 +1,84 @@
+//! Opt-in value interning, enabled with `with_value_interning`.
+
+use alloc::collections::BTreeSet;
+
+use crate::OxidArtN;
+
+/// Distinct values written so far, each kept as the buffer shared by every key
+/// storing it.
+///
+/// The lookup is kept as a function pointer, resolved where the pool is created
+/// with `V: Ord`, so the tree methods writing values need no `Ord` bound.
+#[derive(Clone)]
+pub(crate) struct ValuePool<V> {
+    values: BTreeSet<V>,
+    lookup: fn(&mut BTreeSet<V>, V) -> V,
+}
+
+impl<V: Ord + Clone> ValuePool<V> {
+    pub(crate) fn new() -> Self {
+        Self {
+            values: BTreeSet::new(),
+            lookup: |values, val| {
+                if let Some(shared) = values.get(&val) {
+                    return shared.clone();
+                }
+                values.insert(val.clone());
+                val
+            },
+        }
+    }
+}
+
+impl<V> ValuePool<V> {
+    /// Returns the pooled buffer equal to `val`, pooling `val` if it is new
+    fn intern(&mut self, val: V) -> V {
+        (self.lookup)(&mut self.values, val)
+    }
+}
+
+impl<const N: usize> OxidArtN<N> {
+    /// Creates a new empty tree that deduplicates the values it stores.
+    ///
+    /// Every value written by `set`, `set_ttl` or `try_set` is looked up in a
+    /// pool of the distinct values seen so far: if an equal value is already in
+    /// the pool, its buffer is stored instead, so keys with equal values share a
+    /// single allocation and the incoming buffer is dropped.
+    ///
+    /// This pays off when many keys hold a few distinct values (enum-like states
+    /// such as `"active"` / `"inactive"`). The tradeoff is an `O(log p)` lookup
+    /// and comparison on every write, `p` being the pool size, and a pool that
+    /// only grows: values stay pooled after their keys are deleted or
+    /// overwritten, so mostly-unique values cost memory instead of saving it.
+    /// A pooled value that is a slice of a larger buffer keeps that buffer alive.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::with_value_interning();
+    /// tree.set(Bytes::from_static(b"user:1"), Bytes::from("active".to_string()));
+    /// tree.set(Bytes::from_static(b"user:2"), Bytes::from("active".to_string()));
+    ///
+    /// let a = tree.get(Bytes::from_static(b"user:1")).unwrap();
+    /// let b = tree.get(Bytes::from_static(b"user:2")).unwrap();
+    /// assert_eq!(a.as_ptr(), b.as_ptr());
+    /// ```
+    pub fn with_value_interning() -> Self {
+        let mut art = Self::new();
+        art.value_pool = Some(ValuePool::new());
+        art
+    }
+}
+
+impl<const N: usize, V: Clone> OxidArtN<N, V> {
+    /// Returns the shared buffer for `val` when interning is enabled
+    pub(crate) fn intern_value(&mut self, val: V) -> V {
+        match self.value_pool.as_mut() {
+            Some(pool) => pool.intern(val),
+            None => val,
+        }
+    }
+}
@@ -0,0 +1,1004 @@
+//! Lazy iterators over the tree entries.
+
+use alloc::vec::Vec;
+
+use bytes::Bytes;
+
+use crate::node_childs::ChildAble;
+use crate::{CompResult, Node, OxidArtN};
+
+enum Frame {
+    /// Node to visit, reached from a parent whose key is `key[..parent_len]`
+    Enter {
+        idx: u32,
+        radix: u8,
+        parent_len: usize,
+    },
+    /// Node value to emit, its key being `key[..key_len]`
+    Emit { idx: u32, key_len: usize },
+}
+
+/// Schedules the children of a node whose key is `key[..parent_len]`, the smallest on top
+fn push_children<const N: usize, V: Clone>(
+    stack: &mut Vec<Frame>,
+    art: &OxidArtN<N, V>,
+    idx: u32,
+    parent_len: usize,
+) {
+    for (radix, child_idx) in art.children(idx).rev() {
+        stack.push(Frame::Enter {
+            idx: child_idx,
+            radix,
+            parent_len,
+        });
+    }
+}
+
+/// Lazy iterator over the entries in ascending key order, see [`OxidArtN::iter`].
+///
+/// Stack-based pre-order DFS over children sorted by ascending radix: a key is
+/// always smaller than the keys it prefixes, so a node's value is emitted as
+/// soon as the node is entered. Keys are rebuilt in a single scratch buffer.
+pub struct Iter<'a, const N: usize, V = Bytes> {
+    art: &'a OxidArtN<N, V>,
+    stack: Vec<Frame>,
+    key: Vec<u8>,
+}
+
+impl<'a, const N: usize, V: Clone> Iter<'a, N, V> {
+    fn new(art: &'a OxidArtN<N, V>) -> Self {
+        let key = art.get_node(art.root_idx).compression.to_vec();
+        Self::from_node(art, art.root_idx, key)
+    }
+
+    /// Iterates over the subtree of `idx`, whose full key is `key`
+    fn from_node(art: &'a OxidArtN<N, V>, idx: u32, key: Vec<u8>) -> Self {
+        let mut iter = Self {
+            art,
+            stack: Vec::new(),
+            key,
+        };
+        iter.push_children(idx);
+        // The node value comes before every other key of the subtree
+        iter.stack.push(Frame::Emit {
+            idx,
+            key_len: iter.key.len(),
+        });
+        iter
+    }
+
+    /// Iterates over the keys greater than or equal to `start`.
+    ///
+    /// Only the path toward `start` is walked: on the way, the children after
+    /// the path are scheduled whole, and the ones before it are skipped.
+    fn seek(art: &'a OxidArtN<N, V>, start: &[u8]) -> Self {
+        let mut idx = art.root_idx;
+        let mut iter = Self {
+            art,
+            stack: Vec::new(),
+            key: art.get_node(idx).compression.to_vec(),
+        };
+
+        loop {
+            let shared = iter
+                .key
+                .iter()
+                .zip(start)
+                .take_while(|(a, b)| a == b)
+                .count();
+            // The node key diverges from `start`, or `start` is a prefix of it
+            let whole_subtree = if shared < iter.key.len() {
+                shared == start.len() || iter.key[shared] > start[shared]
+            } else {
+                iter.key.len() == start.len()
+            };
+            if whole_subtree {
+                iter.push_children(idx);
+                iter.stack.push(Frame::Emit {
+                    idx,
+                    key_len: iter.key.len(),
+                });
+                break;
+            }
+            if shared < iter.key.len() {
+                break;
+            }
+
+            // The node key is a proper prefix of `start`, so its value is before it
+            let parent_len = iter.key.len();
+            let next_radix = start[parent_len];
+            let mut next = None;
+            for (radix, child_idx) in art.children(idx).rev() {
+                if radix > next_radix {
+                    iter.stack.push(Frame::Enter {
+                        idx: child_idx,
+                        radix,
+                        parent_len,
+                    });
+                } else {
+                    if radix == next_radix {
+                        next = Some(child_idx);
+                    }
+                    break;
+                }
+            }
+            let Some(child_idx) = next else {
+                break;
+            };
+            iter.key.push(next_radix);
+            iter.key
+                .extend_from_slice(&art.get_node(child_idx).compression);
+            idx = child_idx;
+        }
+
+        iter
+    }
+
+    /// Schedules the children of a node, the smallest on top
+    fn push_children(&mut self, idx: u32) {
+        push_children(&mut self.stack, self.art, idx, self.key.len());
+    }
+
+    /// The node of `idx` if it holds a live value, with its key `key[..key_len]`
+    fn entry(&self, idx: u32, key_len: usize) -> Option<(Bytes, &'a Node<N, V>)> {
+        let node = self.art.get_node(idx);
+        #[cfg(feature = "ttl")]
+        let live = node.get_value(self.art.now).is_some();
+        #[cfg(not(feature = "ttl"))]
+        let live = node.get_value().is_some();
+        live.then(|| (Bytes::copy_from_slice(&self.key[..key_len]), node))
+    }
+
+    /// Advances to the next live entry, returning its key and the node holding it
+    pub(crate) fn next_node(&mut self) -> Option<(Bytes, &'a Node<N, V>)> {
+        while let Some(frame) = self.stack.pop() {
+            let entry = match frame {
+                Frame::Enter {
+                    idx,
+                    radix,
+                    parent_len,
+                } => {
+                    self.key.truncate(parent_len);
+                    self.key.push(radix);
+                    self.key
+                        .extend_from_slice(&self.art.get_node(idx).compression);
+                    self.push_children(idx);
+                    self.entry(idx, self.key.len())
+                }
+                Frame::Emit { idx, key_len } => self.entry(idx, key_len),
+            };
+            if entry.is_some() {
+                return entry;
+            }
+        }
+        None
+    }
+}
+
+impl<const N: usize, V: Clone> Iterator for Iter<'_, N, V> {
+    type Item = (Bytes, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, node) = self.next_node()?;
+        Some((key, node.stored_value()?.clone()))
+    }
+}
+
+/// Lazy iterator over the entries under a prefix, see [`OxidArtN::prefix_iter`].
+///
+/// The subtree matching the prefix is located once, then walked like [`Iter`]:
+/// only the partial key path and the pending siblings are kept on the stack.
+pub struct PrefixIter<'a, const N: usize, V = Bytes> {
+    inner: Iter<'a, N, V>,
+}
+
+impl<const N: usize, V: Clone> Iterator for PrefixIter<'_, N, V> {
+    type Item = (Bytes, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+/// Owning iterator over the entries in ascending key order, returned by the
+/// `IntoIterator` impl of [`OxidArtN`].
+///
+/// Same pre-order DFS and scratch key buffer as [`Iter`], but each node value
+/// is moved out as the node is popped instead of being cloned. The slabs are
+/// dropped with the iterator.
+pub struct IntoIter<const N: usize, V = Bytes> {
+    art: OxidArtN<N, V>,
+    stack: Vec<Frame>,
+    key: Vec<u8>,
+}
+
+impl<const N: usize, V: Clone> Iterator for IntoIter<N, V> {
+    type Item = (Bytes, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(frame) = self.stack.pop() {
+            let (idx, key_len) = match frame {
+                Frame::Enter {
+                    idx,
+                    radix,
+                    parent_len,
+                } => {
+                    self.key.truncate(parent_len);
+                    self.key.push(radix);
+                    self.key
+                        .extend_from_slice(&self.art.get_node(idx).compression);
+                    push_children(&mut self.stack, &self.art, idx, self.key.len());
+                    (idx, self.key.len())
+                }
+                Frame::Emit { idx, key_len } => (idx, key_len),
+            };
+
+            let node = self.art.get_node_mut(idx);
+            #[cfg(feature = "ttl")]
+            let val = node
+                .val
+                .take()
+                .filter(|&(_, ttl)| ttl == crate::NO_EXPIRY || ttl >= self.art.now)
+                .map(|(val, _)| val);
+            #[cfg(not(feature = "ttl"))]
+            let val = node.val.take();
+            if let Some(val) = val {
+                return Some((Bytes::copy_from_slice(&self.key[..key_len]), val));
+            }
+        }
+        None
+    }
+}
+
+impl<const N: usize, V: Clone> IntoIterator for OxidArtN<N, V> {
+    type Item = (Bytes, V);
+    type IntoIter = IntoIter<N, V>;
+
+    /// Consumes the tree into an iterator over its entries in ascending key order.
+    ///
+    /// Values are moved out of the nodes instead of being cloned, unlike `iter`.
+    /// With the `ttl` feature, expired entries are dropped.
+    fn into_iter(self) -> IntoIter<N, V> {
+        let root_idx = self.root_idx;
+        let key = self.get_node(root_idx).compression.to_vec();
+        let mut stack = Vec::new();
+        push_children(&mut stack, &self, root_idx, key.len());
+        // The root value comes before every other key
+        stack.push(Frame::Emit {
+            idx: root_idx,
+            key_len: key.len(),
+        });
+        IntoIter {
+            art: self,
+            stack,
+            key,
+        }
+    }
+}
+
+/// Entries in descending key order, see [`OxidArtN::iter_rev`].
+///
+/// Stack-based post-order DFS over children sorted by descending radix: a key
+/// is always greater than its own prefix, so a node's value is emitted after
+/// all of its descendants. Keys are rebuilt in a single scratch buffer.
+struct IterRev<'a, const N: usize, V> {
+    art: &'a OxidArtN<N, V>,
+    stack: Vec<Frame>,
+    key: Vec<u8>,
+}
+
+impl<'a, const N: usize, V: Clone> IterRev<'a, N, V> {
+    fn new(art: &'a OxidArtN<N, V>) -> Self {
+        let key = art.get_node(art.root_idx).compression.to_vec();
+        Self::from_node(art, art.root_idx, key)
+    }
+
+    /// Iterates over the subtree of `idx`, whose full key is `key`
+    fn from_node(art: &'a OxidArtN<N, V>, idx: u32, key: Vec<u8>) -> Self {
+        let mut iter = Self {
+            art,
+            stack: Vec::new(),
+            key,
+        };
+        iter.enter(idx);
+        iter
+    }
+
+    /// Iterates over the keys smaller than or equal to `end`.
+    ///
+    /// Mirror of `Iter::seek`: on the path toward `end`, the node values and the
+    /// children before the path are scheduled, and the ones after it are skipped.
+    fn seek(art: &'a OxidArtN<N, V>, end: &[u8]) -> Self {
+        let mut idx = art.root_idx;
+        let mut iter = Self {
+            art,
+            stack: Vec::new(),
+            key: art.get_node(idx).compression.to_vec(),
+        };
+
+        loop {
+            let key_len = iter.key.len();
+            let shared = iter.key.iter().zip(end).take_while(|(a, b)| a == b).count();
+            if shared < key_len {
+                // Diverges from `end`: the whole subtree is on one side of it
+                if shared < end.len() && iter.key[shared] < end[shared] {
+                    iter.enter(idx);
+                }
+                break;
+            }
+            // The node key is a prefix of `end`, so its value is not after it
+            iter.stack.push(Frame::Emit { idx, key_len });
+            if key_len == end.len() {
+                break;
+            }
+
+            let next_radix = end[key_len];
+            let mut next = None;
+            for (radix, child_idx) in art.children(idx) {
+                if radix < next_radix {
+                    iter.stack.push(Frame::Enter {
+                        idx: child_idx,
+                        radix,
+                        parent_len: key_len,
+                    });
+                } else {
+                    if radix == next_radix {
+                        next = Some(child_idx);
+                    }
+                    break;
+                }
+            }
+            let Some(child_idx) = next else {
+                break;
+            };
+            iter.key.push(next_radix);
+            iter.key
+                .extend_from_slice(&art.get_node(child_idx).compression);
+            idx = child_idx;
+        }
+
+        iter
+    }
+
+    /// Schedules the node value after its children, the largest child on top
+    fn enter(&mut self, idx: u32) {
+        let key_len = self.key.len();
+        self.stack.push(Frame::Emit { idx, key_len });
+        for (radix, child_idx) in self.art.children(idx) {
+            self.stack.push(Frame::Enter {
+                idx: child_idx,
+                radix,
+                parent_len: key_len,
+            });
+        }
+    }
+}
+
+impl<const N: usize, V: Clone> Iterator for IterRev<'_, N, V> {
+    type Item = (Bytes, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(frame) = self.stack.pop() {
+            match frame {
+                Frame::Enter {
+                    idx,
+                    radix,
+                    parent_len,
+                } => {
+                    self.key.truncate(parent_len);
+                    self.key.push(radix);
+                    self.key
+                        .extend_from_slice(&self.art.get_node(idx).compression);
+                    self.enter(idx);
+                }
+                Frame::Emit { idx, key_len } => {
+                    let node = self.art.get_node(idx);
+                    #[cfg(feature = "ttl")]
+                    let val = node.get_value(self.art.now);
+                    #[cfg(not(feature = "ttl"))]
+                    let val = node.get_value();
+                    if let Some(val) = val {
+                        return Some((Bytes::copy_from_slice(&self.key[..key_len]), val.clone()));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<const N: usize, V: Clone> OxidArtN<N, V> {
+    /// Returns an iterator over all entries in ascending key order.
+    ///
+    /// The iterator is lazy and borrows the tree: nodes are only visited as
+    /// entries are consumed, so breaking out early (or `take(n)`) does not
+    /// materialize the rest of the tree like `getn(Bytes::new())` would. The
+    /// walk is the same pre-order DFS as `getn`, with children visited by
+    /// ascending radix: the root value, if any, comes first.
+    /// With the `ttl` feature, expired entries are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"b"), Bytes::from_static(b"2"));
+    /// tree.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+    ///
+    /// let keys: Vec<Bytes> = tree.iter().map(|(key, _)| key).collect();
+    /// assert_eq!(keys, vec![Bytes::from_static(b"a"), Bytes::from_static(b"b")]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, N, V> {
+        Iter::new(self)
+    }
+
+    /// Returns the entries whose key is in the half-open interval `start..end`, in ascending order.
+    ///
+    /// See `range_iter` for the lazy version.
+    /// With the `ttl` feature, expired entries are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - Smallest key included.
+    /// * `end` - First key excluded, nothing is returned if `end <= start`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// for i in 0..3000 {
+    ///     tree.set(Bytes::from(format!("user:{i:04}")), Bytes::from_static(b"x"));
+    /// }
+    ///
+    /// let users = tree.range(Bytes::from_static(b"user:1000"), Bytes::from_static(b"user:2000"));
+    /// assert_eq!(users.len(), 1000);
+    /// ```
+    pub fn range(&self, start: Bytes, end: Bytes) -> Vec<(Bytes, V)> {
+        self.range_iter(start, end).collect()
+    }
+
+    /// Returns a lazy iterator over the entries whose key is in `start..end`, in ascending order.
+    ///
+    /// The subtrees entirely before `start` are pruned while seeking to it, and
+    /// the walk stops at the first key not before `end`, so only the interval
+    /// (plus one path at each bound) is visited.
+    /// With the `ttl` feature, expired entries are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - Smallest key included.
+    /// * `end` - First key excluded, the iterator is empty if `end <= start`.
+    pub fn range_iter(&self, start: Bytes, end: Bytes) -> impl Iterator<Item = (Bytes, V)> + '_ {
+        let mut iter = Iter::seek(self, &start);
+        if end <= start {
+            iter.stack.clear();
+        }
+        iter.take_while(move |(key, _)| *key < end)
+    }
+
+    /// Returns a lazy iterator over the entries whose key starts with `prefix`.
+    ///
+    /// Same entries and order as `getn`, but the subtree is walked as entries
+    /// are consumed instead of being collected into a `Vec` up front, so
+    /// `take(n)` or an early `break` only visits what is yielded.
+    /// With the `ttl` feature, expired entries are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to match, the empty prefix yielding every entry.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// for i in 0..1000 {
+    ///     tree.set(Bytes::from(format!("user:{i:04}")), Bytes::from(i.to_string()));
+    /// }
+    ///
+    /// let tens: Vec<Bytes> = tree
+    ///     .prefix_iter(Bytes::from_static(b"user:"))
+    ///     .filter(|(_, val)| val.ends_with(b"0"))
+    ///     .map(|(key, _)| key)
+    ///     .take(2)
+    ///     .collect();
+    /// assert_eq!(tens, vec![Bytes::from_static(b"user:0000"), Bytes::from_static(b"user:0010")]);
+    /// ```
+    pub fn prefix_iter(&self, prefix: Bytes) -> PrefixIter<'_, N, V> {
+        let inner = match self.find_prefix_target(&prefix) {
+            Some(target) => Iter::from_node(self, target.idx, target.key_path),
+            None => Iter {
+                art: self,
+                stack: Vec::new(),
+                key: Vec::new(),
+            },
+        };
+        PrefixIter { inner }
+    }
+
+    /// Returns an iterator over all entries in descending key order.
+    ///
+    /// The iterator is lazy: nodes are only visited as entries are consumed.
+    /// With the `ttl` feature, expired entries are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"event:001"), Bytes::from_static(b"first"));
+    /// tree.set(Bytes::from_static(b"event:002"), Bytes::from_static(b"second"));
+    ///
+    /// let (latest, _) = tree.iter_rev().next().unwrap();
+    /// assert_eq!(latest, Bytes::from_static(b"event:002"));
+    /// ```
+    pub fn iter_rev(&self) -> impl Iterator<Item = (Bytes, V)> + '_ {
+        IterRev::new(self)
+    }
+
+    /// Returns the `n` entries with the smallest keys, in ascending key order.
+    ///
+    /// The walk stops after `n` entries, so only the beginning of the tree is visited.
+    /// With the `ttl` feature, expired entries are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - Maximum number of entries to return.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"b"), Bytes::from_static(b"2"));
+    /// tree.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+    /// tree.set(Bytes::from_static(b"c"), Bytes::from_static(b"3"));
+    ///
+    /// let first = tree.first_n(2);
+    /// assert_eq!(first[0].0, Bytes::from_static(b"a"));
+    /// assert_eq!(first[1].0, Bytes::from_static(b"b"));
+    /// ```
+    pub fn first_n(&self, n: usize) -> Vec<(Bytes, V)> {
+        self.iter().take(n).collect()
+    }
+
+    /// Returns the `n` entries with the largest keys, in descending key order.
+    ///
+    /// This runs `iter_rev` for `n` entries, so only the end of the tree is visited.
+    /// With the `ttl` feature, expired entries are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - Maximum number of entries to return.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"score:010"), Bytes::from_static(b"carol"));
+    /// tree.set(Bytes::from_static(b"score:250"), Bytes::from_static(b"alice"));
+    /// tree.set(Bytes::from_static(b"score:120"), Bytes::from_static(b"bob"));
+    ///
+    /// let top = tree.last_n(1);
+    /// assert_eq!(top[0].1, Bytes::from_static(b"alice"));
+    /// ```
+    pub fn last_n(&self, n: usize) -> Vec<(Bytes, V)> {
+        IterRev::new(self).take(n).collect()
+    }
+
+    /// Keeps only the `n` entries with the smallest keys, deleting all the others.
+    ///
+    /// The first `n` entries are skipped in ascending order and every key past
+    /// them is deleted. Nothing happens when the tree holds `n` entries or less.
+    /// With the `ttl` feature, expired entries are neither counted nor deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - Number of entries to keep.
+    ///
+    /// # Returns
+    ///
+    /// The number of deleted entries.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut queue = OxidArt::new();
+    /// queue.set(Bytes::from_static(b"prio:1"), Bytes::from_static(b"a"));
+    /// queue.set(Bytes::from_static(b"prio:2"), Bytes::from_static(b"b"));
+    /// queue.set(Bytes::from_static(b"prio:3"), Bytes::from_static(b"c"));
+    ///
+    /// assert_eq!(queue.truncate_to(2), 1);
+    /// assert_eq!(queue.get(Bytes::from_static(b"prio:3")), None);
+    /// ```
+    pub fn truncate_to(&mut self, n: usize) -> usize {
+        let excess: Vec<Bytes> = self.iter().skip(n).map(|(key, _)| key).collect();
+        for key in &excess {
+            self.del(key.clone());
+        }
+        excess.len()
+    }
+
+    /// Returns the entry with the smallest key starting with the given prefix.
+    ///
+    /// Descends to the prefix node, then follows the smallest radix at each
+    /// level: the cost is the descent, not the subtree size. A value on the
+    /// prefix node itself is the minimum. With the `ttl` feature, expired entries
+    /// are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to match.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"series:1:1700000060"), Bytes::from_static(b"b"));
+    /// tree.set(Bytes::from_static(b"series:1:1700000000"), Bytes::from_static(b"a"));
+    ///
+    /// let (_, earliest) = tree.prefix_min(Bytes::from_static(b"series:1:")).unwrap();
+    /// assert_eq!(earliest, Bytes::from_static(b"a"));
+    /// ```
+    pub fn prefix_min(&self, prefix: Bytes) -> Option<(Bytes, V)> {
+        let target = self.find_prefix_target(&prefix)?;
+        Iter::from_node(self, target.idx, target.key_path).next()
+    }
+
+    /// Returns the entry with the largest key starting with the given prefix.
+    ///
+    /// Descends to the prefix node, then follows the largest radix at each
+    /// level: the cost is the descent, not the subtree size. With the `ttl`
+    /// feature, expired entries are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to match.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"series:1:1700000000"), Bytes::from_static(b"a"));
+    /// tree.set(Bytes::from_static(b"series:1:1700000060"), Bytes::from_static(b"b"));
+    ///
+    /// let (_, latest) = tree.prefix_max(Bytes::from_static(b"series:1:")).unwrap();
+    /// assert_eq!(latest, Bytes::from_static(b"b"));
+    /// ```
+    pub fn prefix_max(&self, prefix: Bytes) -> Option<(Bytes, V)> {
+        let target = self.find_prefix_target(&prefix)?;
+        IterRev::from_node(self, target.idx, target.key_path).next()
+    }
+
+    /// Returns one page of the entries under a prefix, in ascending key order.
+    ///
+    /// The first `offset` matching entries are skipped and at most `limit` are
+    /// returned. The walk is lazy and stops right after the page (one entry
+    /// further, to know whether another page follows), so the rest of the
+    /// subtree is never visited; the skipped entries are still walked over.
+    /// With the `ttl` feature, expired entries are skipped and not counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to match.
+    /// * `offset` - Number of matching entries to skip.
+    /// * `limit` - Maximum number of entries to return.
+    ///
+    /// # Returns
+    ///
+    /// The page, and `true` if more entries follow it.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// for i in 0..25 {
+    ///     tree.set(Bytes::from(format!("item:{i:02}")), Bytes::from_static(b"x"));
+    /// }
+    ///
+    /// let (page, has_next) = tree.getn_paged(Bytes::from_static(b"item:"), 20, 10);
+    /// assert_eq!(page.len(), 5);
+    /// assert!(!has_next);
+    /// ```
+    pub fn getn_paged(
+        &self,
+        prefix: Bytes,
+        offset: usize,
+        limit: usize,
+    ) -> (Vec<(Bytes, V)>, bool) {
+        let Some(target) = self.find_prefix_target(&prefix) else {
+            return (Vec::new(), false);
+        };
+        let mut page: Vec<(Bytes, V)> = Iter::from_node(self, target.idx, target.key_path)
+            .skip(offset)
+            .take(limit.saturating_add(1))
+            .collect();
+        let has_next = page.len() > limit;
+        page.truncate(limit);
+        (page, has_next)
+    }
+
+    /// Returns the smallest key of the tree.
+    ///
+    /// Descends from the root following the smallest radix, and stops at the
+    /// first node carrying a value (the root itself if it has one): the cost is
+    /// the depth of the tree, not its size. With the `ttl` feature, expired
+    /// entries are skipped.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the tree has no entry.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut queue = OxidArt::new();
+    /// queue.set(Bytes::from_static(b"prio:2:b"), Bytes::from_static(b"b"));
+    /// queue.set(Bytes::from_static(b"prio:1:a"), Bytes::from_static(b"a"));
+    ///
+    /// assert_eq!(queue.min_key(), Some(Bytes::from_static(b"prio:1:a")));
+    /// ```
+    pub fn min_key(&self) -> Option<Bytes> {
+        let mut idx = self.root_idx;
+        let mut key = self.get_node(idx).compression.to_vec();
+        loop {
+            let node = self.get_node(idx);
+            #[cfg(feature = "ttl")]
+            let has_value = node.get_value(self.now).is_some();
+            #[cfg(not(feature = "ttl"))]
+            let has_value = node.get_value().is_some();
+            if has_value {
+                return Some(Bytes::from(key));
+            }
+            let Some((radix, child_idx)) = self.extreme_child(idx, false) else {
+                break;
+            };
+            key.push(radix);
+            key.extend_from_slice(&self.get_node(child_idx).compression);
+            idx = child_idx;
+        }
+        // Only an expired leaf (or an empty tree) ends the descent without a value
+        self.iter().next().map(|(key, _)| key)
+    }
+
+    /// Returns the entry with the largest key of the tree.
+    ///
+    /// Descends from the root following the largest radix down to a leaf: the
+    /// cost is the depth of the tree, not its size. With the `ttl` feature,
+    /// expired entries are skipped.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the tree has no entry.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut queue = OxidArt::new();
+    /// queue.set(Bytes::from_static(b"prio:1:a"), Bytes::from_static(b"a"));
+    /// queue.set(Bytes::from_static(b"prio:2:b"), Bytes::from_static(b"b"));
+    ///
+    /// let (key, val) = queue.max_key().unwrap();
+    /// assert_eq!(key, Bytes::from_static(b"prio:2:b"));
+    /// assert_eq!(val, Bytes::from_static(b"b"));
+    /// ```
+    pub fn max_key(&self) -> Option<(Bytes, V)> {
+        let mut idx = self.root_idx;
+        let mut key = self.get_node(idx).compression.to_vec();
+        while let Some((radix, child_idx)) = self.extreme_child(idx, true) {
+            key.push(radix);
+            key.extend_from_slice(&self.get_node(child_idx).compression);
+            idx = child_idx;
+        }
+        let node = self.get_node(idx);
+        #[cfg(feature = "ttl")]
+        let val = node.get_value(self.now);
+        #[cfg(not(feature = "ttl"))]
+        let val = node.get_value();
+        match val {
+            Some(val) => Some((Bytes::from(key), val.clone())),
+            // The largest leaf is expired (or the tree is empty)
+            None => self.iter_rev().next(),
+        }
+    }
+
+    /// Child with the smallest (or largest) radix
+    fn extreme_child(&self, idx: u32, largest: bool) -> Option<(u8, u32)> {
+        let childs = &self.get_node(idx).childs;
+        if largest {
+            childs.max_child()
+        } else {
+            childs.min_child()
+        }
+    }
+
+    /// Returns the entry with the largest key smaller than or equal to `key`.
+    ///
+    /// The tree is descended along `key`; where it diverges, the walk backtracks
+    /// into the largest subtree before it, so the cost is about two descents.
+    /// With the `ttl` feature, expired entries are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The upper bound, included.
+    ///
+    /// # Returns
+    ///
+    /// `None` if every stored key is greater than `key`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut series = OxidArt::new();
+    /// series.set(Bytes::from_static(b"ts:1700000000"), Bytes::from_static(b"a"));
+    /// series.set(Bytes::from_static(b"ts:1700000060"), Bytes::from_static(b"b"));
+    ///
+    /// let (_, val) = series.floor(Bytes::from_static(b"ts:1700000059")).unwrap();
+    /// assert_eq!(val, Bytes::from_static(b"a"));
+    /// ```
+    pub fn floor(&self, key: Bytes) -> Option<(Bytes, V)> {
+        IterRev::seek(self, &key).next()
+    }
+
+    /// Returns the entry with the smallest key greater than or equal to `key`.
+    ///
+    /// The tree is descended along `key`; where it diverges, the walk backtracks
+    /// into the smallest subtree after it, so the cost is about two descents.
+    /// With the `ttl` feature, expired entries are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The lower bound, included.
+    ///
+    /// # Returns
+    ///
+    /// `None` if every stored key is smaller than `key`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut series = OxidArt::new();
+    /// series.set(Bytes::from_static(b"ts:1700000000"), Bytes::from_static(b"a"));
+    /// series.set(Bytes::from_static(b"ts:1700000060"), Bytes::from_static(b"b"));
+    ///
+    /// let (_, val) = series.ceiling(Bytes::from_static(b"ts:1700000001")).unwrap();
+    /// assert_eq!(val, Bytes::from_static(b"b"));
+    /// ```
+    pub fn ceiling(&self, key: Bytes) -> Option<(Bytes, V)> {
+        Iter::seek(self, &key).next()
+    }
+
+    /// Returns the stored entry whose key shares the longest common prefix with `key`.
+    ///
+    /// The descent follows `key` as far as the tree allows. An exact match is
+    /// returned as is; otherwise every key below the divergence point shares the
+    /// same number of leading bytes with `key`, and the smallest of them is
+    /// returned. Ties are thus always broken by the smallest key. This is about
+    /// prefix similarity, not key order: `closest(b"abd")` prefers `abc` over `abe`
+    /// only because `abc` is smaller, both sharing `ab`.
+    /// With the `ttl` feature, expired entries are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to look up.
+    ///
+    /// # Returns
+    ///
+    /// `None` only if the tree has no entry.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"config:timeout"), Bytes::from_static(b"30"));
+    /// tree.set(Bytes::from_static(b"config:retries"), Bytes::from_static(b"3"));
+    ///
+    /// let (key, _) = tree.closest(Bytes::from_static(b"config:timout")).unwrap();
+    /// assert_eq!(key, Bytes::from_static(b"config:timeout"));
+    /// ```
+    pub fn closest(&self, key: Bytes) -> Option<(Bytes, V)> {
+        // Nodes fully matched by `key`, each with its full key
+        let mut path: Vec<(u32, Vec<u8>)> = Vec::new();
+        let mut idx = self.root_idx;
+        let mut cursor = 0;
+
+        loop {
+            let node = self.get_node(idx);
+            let key_path = key[..cursor]
+                .iter()
+                .chain(node.compression.iter())
+                .copied()
+                .collect::<Vec<u8>>();
+            match node.compare_compression_key(&key[cursor..]) {
+                CompResult::Final => {
+                    #[cfg(feature = "ttl")]
+                    let val = node.get_value(self.now);
+                    #[cfg(not(feature = "ttl"))]
+                    let val = node.get_value();
+                    if let Some(val) = val {
+                        return Some((key, val.clone()));
+                    }
+                    path.push((idx, key_path));
+                    break;
+                }
+                CompResult::Partial(_) => {
+                    path.push((idx, key_path));
+                    break;
+                }
+                CompResult::Path => {
+                    cursor += node.compression.len();
+                    path.push((idx, key_path));
+                    let Some(child_idx) = self.find(idx, key[cursor]) else {
+                        break;
+                    };
+                    idx = child_idx;
+                    cursor += 1;
+                }
+            }
+        }
+
+        // The deepest subtree holds the best matches; an ancestor is only needed
+        // when all of them are expired
+        path.into_iter()
+            .rev()
+            .find_map(|(idx, key_path)| Iter::from_node(self, idx, key_path).next())
+    }
+}
+
+impl<const N: usize, const M: usize, V: Clone + PartialEq> PartialEq<OxidArtN<M, V>>
+    for OxidArtN<N, V>
+{
+    /// Compares the logical contents of two trees: the same keys mapping to the same values.
+    ///
+    /// Node layout, slab indices and inline capacity do not matter, both trees
+    /// are walked in ascending key order and zipped, so this is O(n). The empty
+    /// key is compared like any other. With the `ttl` feature, only live entries
+    /// are compared: expired entries are skipped and expiration timestamps are
+    /// ignored, each tree using its own `now`.
+    fn eq(&self, other: &OxidArtN<M, V>) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<const N: usize, V: Clone + Eq> Eq for OxidArtN<N, V> {}
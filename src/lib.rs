@@ -39,11 +39,45 @@
 //! let count = tree.deln(Bytes::from_static(b"hello"));
 //! ```
 //!
-//! ## Key Requirements
+//! ## Keys
 //!
-//! Keys must be valid ASCII bytes. Non-ASCII keys will trigger a debug assertion.
-
+//! Keys are arbitrary byte strings: any `u8` value is accepted, including `0x00`
+//! and bytes above `0x7F`, so UTF-8 text and binary identifiers can be stored as is.
+//! Keys are ordered bytewise, like `[u8]`.
+//!
+//! ## `no_std`
+//!
+//! Without the default `std` feature the crate is `no_std` and only needs `alloc`.
+//! The TTL clock is then driven manually with `set_now` or `advance`, and
+//! `write_entries` (which needs `std::io`) is not available.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+mod builder;
+mod bulk;
+mod debug;
+mod error;
+#[cfg(feature = "std")]
+mod export;
+mod glob;
+mod handle;
+mod intern;
+mod iter;
+mod lru;
+#[cfg(feature = "metadata")]
+mod meta;
 mod node_childs;
+#[cfg(feature = "persist")]
+mod persist;
+mod snapshot;
+mod stats;
+#[cfg(feature = "ttl")]
+mod ttl;
+mod verify;
+#[cfg(feature = "wal")]
+mod wal;
 
 // Prevent enabling both async runtimes at once
 #[cfg(all(feature = "monoio", feature = "tokio"))]
@@ -58,13 +92,30 @@ pub mod tokio;
 #[cfg(test)]
 mod test;
 
-use bytes::Bytes;
+use alloc::collections::BTreeSet;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use bytes::{Bytes, BytesMut};
 use slab::Slab;
 use smallvec::SmallVec;
 
+use crate::intern::ValuePool;
+use crate::lru::Lru;
 use crate::node_childs::ChildAble;
 use crate::node_childs::Childs;
-use crate::node_childs::HugeChilds;
+
+pub use crate::builder::OxidArtBuilder;
+pub use crate::error::{KeyError, OxidArtError};
+pub use crate::handle::Handle;
+pub use crate::iter::{IntoIter, Iter, PrefixIter};
+pub use crate::node_childs::CHILDS_SIZE;
+#[cfg(feature = "persist")]
+pub use crate::persist::FORMAT_VERSION;
+pub use crate::snapshot::Snapshot;
+pub use crate::stats::{Fragmentation, MemoryStats, NodeInfo, SizeReport, VALUE_SIZE_BUCKETS};
+#[cfg(feature = "wal")]
+pub use crate::wal::{WAL_FORMAT_VERSION, WalArt};
 
 /// Internal sentinel value indicating no expiration (never expires)
 #[cfg(feature = "ttl")]
@@ -87,27 +138,94 @@ const NO_EXPIRY: u64 = u64::MAX;
 /// assert_eq!(tree.get(Bytes::from_static(b"key")), Some(Bytes::from_static(b"value")));
 /// ```
 ///
-pub struct OxidArt {
-    pub(crate) map: Slab<Node>,
-    pub(crate) child_list: Slab<HugeChilds>,
-    #[cfg(feature = "ttl")]
+/// This is `OxidArtN` with the default inline child capacity (`CHILDS_SIZE`, 4).
+pub type OxidArt<V = Bytes> = OxidArtN<CHILDS_SIZE, V>;
+
+/// Compressed Adaptive Radix Tree whose nodes store up to `N` children inline.
+///
+/// A node outgrowing `N` children moves them all to a boxed block, which costs
+/// an extra indirection on every lookup through that node. The block adapts to
+/// the number of children: sorted up to 16, indexed up to 48, then one slot per
+/// byte value, so lookups in dense nodes stay O(1), and goes back inline once
+/// enough children are removed. Raise `N` for dense key distributions where
+/// most nodes have many children, lower it to shrink nodes when the tree is
+/// sparse. Use the `OxidArt` alias for the default capacity.
+///
+/// Values are `Bytes` by default. Any `Clone` type `V` can be stored instead:
+/// the traversal never looks at values, only byte-oriented methods such as
+/// `append` or `getrange` require `Bytes`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use oxidart::OxidArtN;
+/// use bytes::Bytes;
+///
+/// let mut tree = OxidArtN::<32>::new();
+/// tree.set(Bytes::from_static(b"key"), Bytes::from_static(b"value"));
+/// ```
+pub struct OxidArtN<const N: usize, V = Bytes> {
+    pub(crate) map: Slab<Node<N, V>>,
+    /// Generation of every slab slot, bumped when a slot is reused or its node
+    /// changes key, so that a stale `Handle` is detected
     versions: Vec<u32>,
+    /// Version of the slots appended to `versions`, raised by `shrink_to_fit`
+    /// above every version handed out before it truncates `versions`
+    fresh_version: u32,
     /// Current timestamp (seconds since UNIX epoch).
     /// The server is responsible for updating this via `set_now()`.
     #[cfg(feature = "ttl")]
     pub now: u64,
     root_idx: u32,
+    /// Number of stored values, see `len`
+    len: usize,
+    /// Recency tracking, only with `with_max_entries`
+    lru: Option<Lru>,
+    /// Shared value buffers, only with `with_value_interning`
+    value_pool: Option<ValuePool<V>>,
+    /// Longest key accepted by `try_set`, see `OxidArtBuilder::max_key_len`
+    max_key_len: Option<usize>,
 }
-impl Default for OxidArt {
+
+impl<const N: usize, V: Clone> Default for OxidArtN<N, V> {
+    /// Creates an empty tree, like `new` but for any value type.
+    ///
+    /// The `Bytes` constructors (`new`, `with_max_entries`, the builder) keep
+    /// `OxidArt::new()` unambiguous, so a tree of another value type is created
+    /// with `OxidArt::<V>::default()`.
     fn default() -> Self {
-        Self::new()
+        Self::with_node_capacity(1024)
+    }
+}
+
+impl<const N: usize, V: Clone> Clone for OxidArtN<N, V> {
+    /// Deep copy of the tree, cloning every value.
+    ///
+    /// The slabs are cloned slot for slot, so every node keeps its index and
+    /// `versions` stays in step with them: a `Handle` taken on the original is
+    /// also valid on the clone. `Bytes` values are cloned by refcount, sharing
+    /// their buffers.
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map.clone(),
+            versions: self.versions.clone(),
+            fresh_version: self.fresh_version,
+            #[cfg(feature = "ttl")]
+            now: self.now,
+            root_idx: self.root_idx,
+            len: self.len,
+            lru: self.lru.clone(),
+            value_pool: self.value_pool.clone(),
+            max_key_len: self.max_key_len,
+        }
     }
 }
 
-impl OxidArt {
+impl<const N: usize> OxidArtN<N> {
     /// Creates a new empty `OxidArt` tree.
     ///
-    /// The tree is pre-allocated with capacity for 1024 nodes.
+    /// The tree is pre-allocated with capacity for 1024 nodes. It stores `Bytes`
+    /// values, use `default` for another value type.
     ///
     /// # Example
     ///
@@ -117,22 +235,124 @@ impl OxidArt {
     /// let tree = OxidArt::new();
     /// ```
     pub fn new() -> Self {
-        let mut map = Slab::with_capacity(1024);
+        Self::with_node_capacity(1024)
+    }
+
+    /// Creates a new empty tree with room for `nodes` nodes before the slab grows.
+    ///
+    /// Use this instead of `new` when the number of keys is known up front, to
+    /// avoid the slab reallocations (and the copies of every node they imply)
+    /// while the tree fills up. Each new key creates at most two nodes (a leaf
+    /// and a split).
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - Number of nodes to pre-allocate, the root included.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::with_capacity(2 * 100_000);
+    /// assert!(tree.capacity() >= 200_000);
+    /// for i in 0..100_000 {
+    ///     tree.set(Bytes::from(format!("key:{i}")), Bytes::from_static(b"v"));
+    /// }
+    /// ```
+    pub fn with_capacity(nodes: usize) -> Self {
+        Self::with_node_capacity(nodes)
+    }
+}
+
+impl<const N: usize, V: Clone> OxidArtN<N, V> {
+    /// Creates a new empty tree with room for `capacity` nodes before the slab grows
+    fn with_node_capacity(capacity: usize) -> Self {
+        let mut map = Slab::with_capacity(capacity);
         let root_idx = map.insert(Node::default()) as u32;
-        let child_list = Slab::with_capacity(32);
 
-        // On ne crée le vecteur que si la feature est là
-        #[cfg(feature = "ttl")]
-        let versions = vec![0]; // Initialise avec 0 pour le root par exemple
+        let mut versions = Vec::with_capacity(capacity.max(1));
+        versions.push(0); // Initialise avec 0 pour le root par exemple
 
         Self {
             map,
             root_idx,
-            child_list,
-            #[cfg(feature = "ttl")]
             versions,
+            fresh_version: 0,
+            len: 0,
             #[cfg(feature = "ttl")]
             now: 0,
+            lru: None,
+            value_pool: None,
+            max_key_len: None,
+        }
+    }
+
+    /// Reserves room for at least `additional_nodes` more nodes.
+    ///
+    /// Call this before a large batch of inserts into an existing tree, so the
+    /// node slab does not reallocate in the middle of it. Each new key creates at
+    /// most two nodes (a leaf and a split).
+    ///
+    /// # Arguments
+    ///
+    /// * `additional_nodes` - Number of nodes to make room for, on top of the current ones.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.reserve(2 * 100_000);
+    /// for i in 0..100_000 {
+    ///     tree.set(Bytes::from(format!("key:{i}")), Bytes::from_static(b"v"));
+    /// }
+    /// ```
+    pub fn reserve(&mut self, additional_nodes: usize) {
+        self.map.reserve(additional_nodes);
+        self.versions.reserve(additional_nodes);
+    }
+
+    /// Returns the number of nodes the slab can hold before it reallocates.
+    ///
+    /// This counts nodes, not entries: see `with_capacity` for how many nodes a
+    /// key takes.
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// Removes every entry, keeping the allocated memory for reuse.
+    ///
+    /// Unlike `deln(Bytes::new())`, the root value and compression are dropped
+    /// too, leaving a tree equivalent to a fresh one. The slab keeps its
+    /// capacity, so refilling the tree up to its previous size does not
+    /// reallocate. The configuration (`with_max_entries`, value interning) and
+    /// the current timestamp are kept, and every `Handle` becomes stale.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::new(), Bytes::from_static(b"root"));
+    /// tree.set(Bytes::from_static(b"key"), Bytes::from_static(b"v"));
+    ///
+    /// tree.clear();
+    /// assert!(tree.is_empty());
+    /// assert_eq!(tree.get(Bytes::new()), None);
+    /// ```
+    pub fn clear(&mut self) {
+        self.map.clear();
+        // Reused slots get their version bumped, invalidating older handles
+        self.root_idx = self.insert(Node::default());
+        self.len = 0;
+        if let Some(lru) = self.lru.as_mut() {
+            lru.clear();
         }
     }
 
@@ -142,51 +362,97 @@ impl OxidArt {
     pub fn set_now(&mut self, now: u64) {
         self.now = now;
     }
-    fn insert(&mut self, node: Node) -> u32 {
+
+    /// Returns the current timestamp used for TTL checks.
+    #[cfg(feature = "ttl")]
+    #[inline]
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Moves the current timestamp forward by `secs` seconds.
+    ///
+    /// Unlike `set_now`, the clock can only move forward (saturating at `u64::MAX`),
+    /// which makes it suitable to drive TTL from a simulation clock.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set_now(1000);
+    /// tree.advance(60);
+    /// assert_eq!(tree.now(), 1060);
+    /// ```
+    #[cfg(feature = "ttl")]
+    #[inline]
+    pub fn advance(&mut self, secs: u64) {
+        self.now = self.now.saturating_add(secs);
+    }
+    fn insert(&mut self, node: Node<N, V>) -> u32 {
         let idx = self.map.insert(node) as u32;
 
-        // Ce bloc disparaît complètement de la compilation si "ttl" n'est pas actif
-        #[cfg(feature = "ttl")]
-        {
-            if self.versions.len() == idx as usize {
-                self.versions.push(0);
-            } else {
-                self.versions[idx as usize] += 1;
-            }
+        if self.versions.len() == idx as usize {
+            self.versions.push(self.fresh_version);
+        } else {
+            self.bump_version(idx);
         }
 
         idx
     }
-    fn get_node(&self, idx: u32) -> &Node {
+    /// Invalidates the handles to a slot, when it is reused or its node changes key
+    fn bump_version(&mut self, idx: u32) {
+        let version = &mut self.versions[idx as usize];
+        *version = version.wrapping_add(1);
+    }
+    fn get_node(&self, idx: u32) -> &Node<N, V> {
         self.try_get_node(idx)
             .expect("Call to unfailable get_node failed")
     }
-    fn get_node_mut(&mut self, idx: u32) -> &mut Node {
+    fn get_node_mut(&mut self, idx: u32) -> &mut Node<N, V> {
         self.try_get_node_mut(idx)
             .expect("Call to unfailable get_node failed")
     }
 
-    fn try_get_node(&self, idx: u32) -> Option<&Node> {
+    fn try_get_node(&self, idx: u32) -> Option<&Node<N, V>> {
         self.map.get(idx as usize)
     }
-    fn try_get_node_mut(&mut self, idx: u32) -> Option<&mut Node> {
+    fn try_get_node_mut(&mut self, idx: u32) -> Option<&mut Node<N, V>> {
         self.map.get_mut(idx as usize)
     }
     fn find(&self, idx: u32, radix: u8) -> Option<u32> {
-        let child = &self.try_get_node(idx)?.childs;
+        self.try_get_node(idx)?.childs.find(radix)
+    }
+    /// Finds the node whose full key is exactly `key`, whether or not it holds a value
+    fn find_key_node(&self, key: &[u8]) -> Option<u32> {
+        self.find_key_node_from(self.root_idx, 0, key)
+    }
 
-        if let Some(index) = child.find(radix) {
-            return Some(index);
+    /// Like `find_key_node`, but starting from `idx` once `skip` bytes of its
+    /// compression have already been matched
+    fn find_key_node_from(&self, mut idx: u32, skip: usize, key: &[u8]) -> Option<u32> {
+        let compression_rest = &self.try_get_node(idx)?.compression[skip..];
+        if !key.starts_with(compression_rest) {
+            return None;
         }
-        self.child_list
-            .get(child.get_next_idx()? as usize)?
-            .find(radix)
-    }
-    fn intiate_new_huge_child(&mut self, radix: u8, idx: u32) -> u32 {
-        self.child_list.insert(HugeChilds::new(radix, idx)) as u32
+        let mut cursor = compression_rest.len();
+
+        while cursor < key.len() {
+            idx = self.find(idx, key[cursor])?;
+            cursor += 1;
+            let node = self.try_get_node(idx)?;
+            match node.compare_compression_key(&key[cursor..]) {
+                CompResult::Final => return Some(idx),
+                CompResult::Partial(_) => return None,
+                CompResult::Path => cursor += node.compression.len(),
+            }
+        }
+
+        Some(idx)
     }
 }
-impl OxidArt {
+impl<const N: usize, V: Clone> OxidArtN<N, V> {
     /// Retrieves the value associated with the given key.
     ///
     /// Returns `Some(value)` if the key exists (and is not expired with `ttl` feature), or `None` otherwise.
@@ -194,29 +460,12 @@ impl OxidArt {
     ///
     /// # Arguments
     ///
-    /// * `key` - The key to look up. Must be valid ASCII.
-    pub fn get(&mut self, key: Bytes) -> Option<Bytes> {
-        debug_assert!(key.is_ascii(), "key must be ASCII");
-        let key_len = key.len();
-        if key_len == 0 {
-            #[cfg(feature = "ttl")]
-            if self.get_node(self.root_idx).is_expired(self.now) {
-                self.get_node_mut(self.root_idx).val = None;
-                self.try_recompress(self.root_idx);
-                return None;
-            }
-            #[cfg(feature = "ttl")]
-            return self.get_node(self.root_idx).get_value(self.now).cloned();
-            #[cfg(not(feature = "ttl"))]
-            return self.get_node(self.root_idx).get_value().cloned();
-        }
-
-        #[cfg(feature = "ttl")]
-        let mut parent_idx = self.root_idx;
+    /// * `key` - The key to look up.
+    pub fn get(&mut self, key: Bytes) -> Option<V> {
         #[cfg(feature = "ttl")]
-        let mut parent_radix = key[0];
-        let mut idx = self.find(self.root_idx, key[0])?;
-        let mut cursor = 1;
+        let mut parent = None;
+        let mut idx = self.root_idx;
+        let mut cursor = 0;
 
         loop {
             let node = self.try_get_node(idx)?;
@@ -224,13 +473,22 @@ impl OxidArt {
                 CompResult::Final => {
                     #[cfg(feature = "ttl")]
                     if node.is_expired(self.now) {
-                        self.delete_node_inline(idx, parent_idx, parent_radix);
+                        self.delete_node_inline(idx, parent);
+                        if let Some(lru) = self.lru.as_mut() {
+                            lru.remove(&key);
+                        }
                         return None;
                     }
                     #[cfg(feature = "ttl")]
-                    return self.get_node(idx).get_value(self.now).cloned();
+                    let val = self.get_node(idx).get_value(self.now).cloned();
                     #[cfg(not(feature = "ttl"))]
-                    return node.get_value().cloned();
+                    let val = node.get_value().cloned();
+                    if let Some(lru) = self.lru.as_mut()
+                        && val.is_some()
+                    {
+                        lru.touch(&key);
+                    }
+                    return val;
                 }
                 CompResult::Partial(_) => return None,
                 CompResult::Path => {
@@ -239,45 +497,28 @@ impl OxidArt {
             }
             #[cfg(feature = "ttl")]
             {
-                parent_idx = idx;
-                parent_radix = key[cursor];
+                parent = Some((idx, key[cursor]));
             }
             idx = self.find(idx, key[cursor])?;
             cursor += 1;
         }
     }
 
-    /// Deletes a node inline (used for TTL expiration cleanup)
-    #[cfg(feature = "ttl")]
-    fn delete_node_inline(&mut self, target_idx: u32, parent_idx: u32, parent_radix: u8) {
-        let has_children = {
-            let node = self.get_node(target_idx);
-            !node.childs.is_empty() || node.childs.get_next_idx().is_some()
-        };
-
-        if has_children {
-            self.get_node_mut(target_idx).val = None;
-            self.try_recompress(target_idx);
-        } else {
-            self.map.remove(target_idx as usize);
-            self.remove_child(parent_idx, parent_radix);
-            if parent_idx != self.root_idx {
-                self.try_recompress(parent_idx);
-            }
-        }
-    }
-
-    /// Returns all key-value pairs where the key starts with the given prefix.
+    /// Retrieves the value associated with the given key, inserting the value made by `f` on miss.
     ///
-    /// If the prefix is empty, returns all entries in the tree.
+    /// The tree is walked once: a live value is returned as is, without calling `f`;
+    /// otherwise `f` is called and its value is inserted where the walk stopped,
+    /// splitting a node if needed, like `set` (no expiration).
+    /// With the `ttl` feature, an expired entry is treated as a miss.
     ///
     /// # Arguments
     ///
-    /// * `prefix` - The prefix to match. Must be valid ASCII.
+    /// * `key` - The key to look up.
+    /// * `f` - Computes the value to insert, only called on miss.
     ///
     /// # Returns
     ///
-    /// A vector of `(key, value)` tuples for all matching entries.
+    /// The existing value, or the inserted one.
     ///
     /// # Example
     ///
@@ -285,152 +526,50 @@ impl OxidArt {
     /// use oxidart::OxidArt;
     /// use bytes::Bytes;
     ///
-    /// let mut tree = OxidArt::new();
-    /// tree.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
-    /// tree.set(Bytes::from_static(b"user:2"), Bytes::from_static(b"bob"));
-    /// tree.set(Bytes::from_static(b"post:1"), Bytes::from_static(b"hello"));
+    /// let mut cache = OxidArt::new();
+    /// let page = cache.get_or_insert_with(Bytes::from_static(b"/home"), || Bytes::from_static(b"<html>"));
+    /// assert_eq!(page, Bytes::from_static(b"<html>"));
     ///
-    /// let users = tree.getn(Bytes::from_static(b"user:"));
-    /// assert_eq!(users.len(), 2);
+    /// // Already cached: the closure is not called
+    /// let page = cache.get_or_insert_with(Bytes::from_static(b"/home"), || unreachable!());
+    /// assert_eq!(page, Bytes::from_static(b"<html>"));
     /// ```
-    pub fn getn(&self, prefix: Bytes) -> Vec<(Bytes, Bytes)> {
-        debug_assert!(prefix.is_ascii(), "prefix must be ASCII");
-        let mut results = Vec::new();
-        let prefix_len = prefix.len();
-
-        if prefix_len == 0 {
-            self.collect_all(self.root_idx, Vec::new(), &mut results);
-            return results;
-        }
-
-        // Traverse like get, tracking the actual path
-        let mut idx = self.root_idx;
-        let mut cursor = 0;
-        let mut key_path: Vec<u8> = Vec::new();
-
-        loop {
-            let radix = prefix[cursor];
-            let Some(child_idx) = self.find(idx, radix) else {
-                return results;
-            };
-            idx = child_idx;
-            key_path.push(radix);
-
-            let Some(node) = self.try_get_node(idx) else {
-                return results;
-            };
-            cursor += 1;
-
-            match node.compare_compression_key(&prefix[cursor..]) {
-                CompResult::Final => {
-                    // Exact prefix found
-                    key_path.extend_from_slice(&node.compression);
-                    self.collect_all_from(idx, key_path, &mut results);
-                    return results;
-                }
-                CompResult::Partial(common_len) => {
-                    let prefix_rest_len = prefix_len - cursor;
-                    if common_len == prefix_rest_len {
-                        // Prefix ends within the compression
-                        key_path.extend_from_slice(&node.compression);
-                        self.collect_all_from(idx, key_path, &mut results);
-                    }
-                    return results;
-                }
-                CompResult::Path => {
-                    key_path.extend_from_slice(&node.compression);
-                    cursor += node.compression.len();
-                }
-            }
-        }
-    }
-
-    /// Collects from a node whose key is already complete in key_path
-    fn collect_all_from(
-        &self,
-        node_idx: u32,
-        key_path: Vec<u8>,
-        results: &mut Vec<(Bytes, Bytes)>,
-    ) {
-        let Some(node) = self.try_get_node(node_idx) else {
-            return;
-        };
-
-        #[cfg(feature = "ttl")]
-        if let Some(val) = node.get_value(self.now) {
-            results.push((Bytes::from(key_path.clone()), val.clone()));
-        }
-        #[cfg(not(feature = "ttl"))]
-        if let Some(val) = node.get_value() {
-            results.push((Bytes::from(key_path.clone()), val.clone()));
-        }
-
-        self.iter_all_children(node_idx, |radix, child_idx| {
-            let mut child_key = key_path.clone();
-            child_key.push(radix);
-            self.collect_all(child_idx, child_key, results);
-        });
-    }
-
-    /// Recursively collects, adding the node's compression
-    fn collect_all(
-        &self,
-        node_idx: u32,
-        mut key_prefix: Vec<u8>,
-        results: &mut Vec<(Bytes, Bytes)>,
-    ) {
-        let Some(node) = self.try_get_node(node_idx) else {
-            return;
+    pub fn get_or_insert_with<F: FnOnce() -> V>(&mut self, key: Bytes, f: F) -> V {
+        let mut inserted = None;
+        let make_val = || {
+            let val = f();
+            inserted = Some(val.clone());
+            val
         };
-
-        key_prefix.extend_from_slice(&node.compression);
-
         #[cfg(feature = "ttl")]
-        if let Some(val) = node.get_value(self.now) {
-            results.push((Bytes::from(key_prefix.clone()), val.clone()));
-        }
+        let result = self.upsert_internal(key.clone(), NO_EXPIRY, false, make_val);
         #[cfg(not(feature = "ttl"))]
-        if let Some(val) = node.get_value() {
-            results.push((Bytes::from(key_prefix.clone()), val.clone()));
-        }
-
-        self.iter_all_children(node_idx, |radix, child_idx| {
-            let mut child_key = key_prefix.clone();
-            child_key.push(radix);
-            self.collect_all(child_idx, child_key, results);
-        });
-    }
-
-    /// Iterates over all children of a node (childs + huge_childs)
-    fn iter_all_children<F>(&self, node_idx: u32, mut f: F)
-    where
-        F: FnMut(u8, u32),
-    {
-        let Some(node) = self.try_get_node(node_idx) else {
-            return;
-        };
-
-        for (radix, child_idx) in node.childs.iter() {
-            f(radix, child_idx);
-        }
+        let result = self.upsert_internal(key.clone(), false, make_val);
+        let existing = result.unwrap_or_else(|e| panic!("get_or_insert_with failed: {e}"));
 
-        if let Some(huge_idx) = node.childs.get_next_idx()
-            && let Some(huge_childs) = self.child_list.get(huge_idx as usize)
-        {
-            for (radix, child_idx) in huge_childs.iter() {
-                f(radix, child_idx);
+        match existing {
+            Some(val) => {
+                if let Some(lru) = self.lru.as_mut() {
+                    lru.touch(&key);
+                }
+                val
+            }
+            None => {
+                self.lru_record_write(&key);
+                inserted.expect("a miss inserts the value")
             }
         }
     }
 
-    /// Inserts or updates a key-value pair in the tree (no expiration).
+    /// Retrieves the value associated with the given key, inserting an empty value on miss.
     ///
-    /// If the key already exists, the value is replaced.
+    /// Unlike `get(key).unwrap_or_default()`, this **mutates the tree on miss**: the key is
+    /// stored with an empty value (and no expiration), so subsequent reads are stable.
+    /// With the `ttl` feature, an expired entry is treated as a miss.
     ///
     /// # Arguments
     ///
-    /// * `key` - The key to insert. Must be valid ASCII.
-    /// * `val` - The value to associate with the key.
+    /// * `key` - The key to look up.
     ///
     /// # Example
     ///
@@ -439,263 +578,2209 @@ impl OxidArt {
     /// use bytes::Bytes;
     ///
     /// let mut tree = OxidArt::new();
+    /// assert_eq!(tree.get_or_default(Bytes::from_static(b"counter")), Bytes::new());
     ///
-    /// // Insert a new key
-    /// tree.set(Bytes::from_static(b"key"), Bytes::from_static(b"value1"));
-    ///
-    /// // Update an existing key
-    /// tree.set(Bytes::from_static(b"key"), Bytes::from_static(b"value2"));
-    ///
-    /// assert_eq!(tree.get(Bytes::from_static(b"key")), Some(Bytes::from_static(b"value2")));
+    /// // The key now exists
+    /// assert_eq!(tree.get(Bytes::from_static(b"counter")), Some(Bytes::new()));
     /// ```
-    pub fn set(&mut self, key: Bytes, val: Bytes) {
+    pub fn get_or_default(&mut self, key: Bytes) -> V
+    where
+        V: Default,
+    {
+        self.get_or_insert_with(key, V::default)
+    }
+
+    /// Returns `true` if the empty key holds a value.
+    ///
+    /// The root node holds the empty key only while it has no compression: after
+    /// recompression it holds the key made of its compression instead.
+    pub fn root_has_value(&self) -> bool {
+        let root = self.get_node(self.root_idx);
+        if !root.compression.is_empty() {
+            return false;
+        }
         #[cfg(feature = "ttl")]
-        self.set_internal(key, NO_EXPIRY, val);
+        return root.get_value(self.now).is_some();
         #[cfg(not(feature = "ttl"))]
-        self.set_internal(key, val);
+        root.get_value().is_some()
     }
 
-    /// Inserts or updates a key-value pair with a time-to-live duration.
+    /// Retrieves the value associated with the given key, along with the stored key.
     ///
-    /// The key will expire after `ttl` duration from the current timestamp (`self.now`).
-    /// Make sure to call `tick()` or `set_now()` to keep the internal clock updated.
+    /// The key is reconstructed from the traversal path (radixes and compressions),
+    /// so it is the exact key bytes held by the tree.
+    /// Unlike `get`, this does not clean up expired entries (they are just filtered out).
     ///
     /// # Arguments
     ///
-    /// * `key` - The key to insert. Must be valid ASCII.
-    /// * `ttl` - Duration after which the key expires.
-    /// * `val` - The value to associate with the key.
+    /// * `key` - The key to look up.
     ///
     /// # Example
     ///
     /// ```rust,ignore
     /// use oxidart::OxidArt;
     /// use bytes::Bytes;
-    /// use std::time::Duration;
     ///
     /// let mut tree = OxidArt::new();
-    /// tree.set_now(1000); // Set current time
+    /// tree.set(Bytes::from_static(b"key"), Bytes::from_static(b"value"));
+    ///
+    /// assert_eq!(
+    ///     tree.get_with_key(Bytes::from_static(b"key")),
+    ///     Some((Bytes::from_static(b"key"), Bytes::from_static(b"value")))
+    /// );
+    /// ```
+    pub fn get_with_key(&self, key: Bytes) -> Option<(Bytes, V)> {
+        let mut idx = self.root_idx;
+        let mut cursor = 0;
+        let mut key_path: Vec<u8> = Vec::with_capacity(key.len());
+
+        let node = loop {
+            let node = self.try_get_node(idx)?;
+            match node.compare_compression_key(&key[cursor..]) {
+                CompResult::Final => {
+                    key_path.extend_from_slice(&node.compression);
+                    break node;
+                }
+                CompResult::Partial(_) => return None,
+                CompResult::Path => {
+                    key_path.extend_from_slice(&node.compression);
+                    cursor += node.compression.len();
+                }
+            }
+
+            let radix = key[cursor];
+            idx = self.find(idx, radix)?;
+            key_path.push(radix);
+            cursor += 1;
+        };
+
+        #[cfg(feature = "ttl")]
+        let val = node.get_value(self.now)?;
+        #[cfg(not(feature = "ttl"))]
+        let val = node.get_value()?;
+        Some((Bytes::from(key_path), val.clone()))
+    }
+
+    /// Returns every entry whose key is a prefix of `key`, from the shortest to `key` itself.
+    ///
+    /// All of them lie on the descent path toward `key`, so they are collected in a
+    /// single traversal instead of one `get` per ancestor. The returned keys are
+    /// slices of `key`, nothing is copied.
+    /// With the `ttl` feature, expired entries are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key whose ancestors are collected.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"a"), Bytes::from_static(b"root"));
+    /// tree.set(Bytes::from_static(b"a/b"), Bytes::from_static(b"team"));
+    /// tree.set(Bytes::from_static(b"a/b/c"), Bytes::from_static(b"user"));
+    ///
+    /// let chain = tree.get_ancestors(Bytes::from_static(b"a/b/c/d"));
+    /// assert_eq!(chain.len(), 3);
+    /// assert_eq!(chain[0].0, Bytes::from_static(b"a"));
+    /// assert_eq!(chain[2].1, Bytes::from_static(b"user"));
+    /// ```
+    pub fn get_ancestors(&self, key: Bytes) -> Vec<(Bytes, V)> {
+        let mut ancestors = Vec::new();
+        self.for_each_ancestor(&key, |len, val| {
+            ancestors.push((key.slice(..len), val.clone()));
+        });
+        ancestors
+    }
+
+    /// Returns the entry with the longest key that is a prefix of `key`.
+    ///
+    /// This is the lookup of a routing table: with `/api/` and `/api/v2/` stored,
+    /// `/api/v2/users` matches `/api/v2/`. The descent stops where `key` leaves the
+    /// tree, including in the middle of a compressed path, and the last entry met
+    /// on the way is returned. An exact match is its own longest prefix.
+    /// The returned key is a slice of `key`, nothing is copied.
+    /// With the `ttl` feature, expired entries are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to match.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut routes = OxidArt::new();
+    /// routes.set(Bytes::from_static(b"/api/"), Bytes::from_static(b"api"));
+    /// routes.set(Bytes::from_static(b"/api/v2/"), Bytes::from_static(b"api-v2"));
+    ///
+    /// let (route, handler) = routes.longest_prefix_match(Bytes::from_static(b"/api/v2/users")).unwrap();
+    /// assert_eq!(route, Bytes::from_static(b"/api/v2/"));
+    /// assert_eq!(handler, Bytes::from_static(b"api-v2"));
+    /// ```
+    pub fn longest_prefix_match(&self, key: Bytes) -> Option<(Bytes, V)> {
+        let mut longest = None;
+        self.for_each_ancestor(&key, |len, val| longest = Some((len, val.clone())));
+        longest.map(|(len, val)| (key.slice(..len), val))
+    }
+
+    /// Calls `f` with the length and the value of every live entry whose key is a
+    /// prefix of `key`, from the shortest
+    fn for_each_ancestor<'a, F>(&'a self, key: &[u8], mut f: F)
+    where
+        F: FnMut(usize, &'a V),
+    {
+        let mut idx = self.root_idx;
+        let mut cursor = 0;
+
+        loop {
+            let node = self.get_node(idx);
+            if !key[cursor..].starts_with(&node.compression) {
+                break;
+            }
+            cursor += node.compression.len();
+
+            #[cfg(feature = "ttl")]
+            let val = node.get_value(self.now);
+            #[cfg(not(feature = "ttl"))]
+            let val = node.get_value();
+            if let Some(val) = val {
+                f(cursor, val);
+            }
+
+            let Some(&radix) = key.get(cursor) else {
+                break;
+            };
+            let Some(child_idx) = self.find(idx, radix) else {
+                break;
+            };
+            idx = child_idx;
+            cursor += 1;
+        }
+    }
+
+    /// Returns `true` if a value is stored under the given key.
+    ///
+    /// The lookup is the same as `get`, but the value is never touched nor
+    /// cloned, and the tree is only borrowed: unlike `get`, an expired entry is
+    /// not cleaned up, and the key is not marked as recently used with
+    /// `with_max_entries`. With the `ttl` feature, an expired entry is absent.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to look up.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    ///
+    /// assert!(tree.contains_key(Bytes::from_static(b"user:1")));
+    /// assert!(!tree.contains_key(Bytes::from_static(b"user:")));
+    /// ```
+    pub fn contains_key(&self, key: Bytes) -> bool {
+        self.contains_key_slice(&key)
+    }
+
+    /// Same as `contains_key`, with a borrowed key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to look up.
+    pub fn contains_key_slice(&self, key: &[u8]) -> bool {
+        self.live_value(key).is_some()
+    }
+
+    /// Retrieves the value associated with the given key, without mutating the tree.
+    ///
+    /// Unlike `get`, the key is borrowed and the tree is only borrowed too: an
+    /// expired entry is not cleaned up, and the key is not marked as recently
+    /// used with `with_max_entries`. With the `ttl` feature, an expired entry is
+    /// absent. The key is only used to walk the tree, so a `&str` or a borrowed
+    /// buffer can be passed without building a `Bytes` first.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to look up.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    ///
+    /// assert_eq!(tree.get_slice("user:1".as_bytes()), Some(Bytes::from_static(b"alice")));
+    /// ```
+    pub fn get_slice(&self, key: &[u8]) -> Option<V> {
+        self.live_value(key).cloned()
+    }
+
+    /// Live value stored under `key`, if any
+    fn live_value(&self, key: &[u8]) -> Option<&V> {
+        let node = self.get_node(self.find_key_node(key)?);
+        #[cfg(feature = "ttl")]
+        return node.get_value(self.now);
+        #[cfg(not(feature = "ttl"))]
+        node.get_value()
+    }
+}
+
+/// Byte-oriented accessors, for the default `Bytes` value type
+impl<const N: usize> OxidArtN<N> {
+    /// Returns the length of the value associated with the given key.
+    ///
+    /// The value is read in place, without cloning the `Bytes` handle.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to look up.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the key doesn't exist (or is expired with `ttl` feature).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"blob"), Bytes::from_static(b"hello world"));
+    ///
+    /// assert_eq!(tree.value_len(Bytes::from_static(b"blob")), Some(11));
+    /// assert_eq!(tree.value_len(Bytes::from_static(b"blo")), None);
+    /// ```
+    pub fn value_len(&self, key: Bytes) -> Option<usize> {
+        self.live_value(&key).map(Bytes::len)
+    }
+
+    /// Returns the `start..end` byte range of the value associated with the given key.
+    ///
+    /// The range is clamped to the value length: `end` past the end is truncated, and
+    /// an empty `Bytes` is returned when `start >= end` or `start` is past the end.
+    /// The slice shares the stored buffer, nothing is copied.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to look up.
+    /// * `start` - First byte of the range (inclusive).
+    /// * `end` - Last byte of the range (exclusive).
+    ///
+    /// # Returns
+    ///
+    /// `None` if the key doesn't exist (or is expired with `ttl` feature).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"blob"), Bytes::from_static(b"hello world"));
+    ///
+    /// assert_eq!(tree.getrange(Bytes::from_static(b"blob"), 6, 100), Some(Bytes::from_static(b"world")));
+    /// ```
+    pub fn getrange(&self, key: Bytes, start: usize, end: usize) -> Option<Bytes> {
+        let val = self.live_value(&key)?;
+        let end = end.min(val.len());
+        let start = start.min(end);
+        Some(val.slice(start..end))
+    }
+}
+
+impl<const N: usize, V: Clone> OxidArtN<N, V> {
+    /// Retrieves the values of several keys sharing a common prefix.
+    ///
+    /// The common prefix is traversed once, then each suffix is looked up from there.
+    /// Results are returned in the same order as `suffixes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `common_prefix` - The prefix shared by all keys.
+    /// * `suffixes` - The rest of each key after the prefix.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"obj:123:name"), Bytes::from_static(b"alice"));
+    /// tree.set(Bytes::from_static(b"obj:123:age"), Bytes::from_static(b"42"));
+    ///
+    /// let values = tree.get_siblings(Bytes::from_static(b"obj:123:"), &[b"name", b"email", b"age"]);
+    /// assert_eq!(values, vec![Some(Bytes::from_static(b"alice")), None, Some(Bytes::from_static(b"42"))]);
+    /// ```
+    pub fn get_siblings(&self, common_prefix: Bytes, suffixes: &[&[u8]]) -> Vec<Option<V>> {
+        // Position reached by the prefix: a node and how much of its compression matched
+        let position = self.find_prefix_target(&common_prefix).map(|target| {
+            let compression_len = self.get_node(target.idx).compression.len();
+            let consumed = common_prefix.len() + compression_len - target.key_path.len();
+            (target.idx, consumed)
+        });
+        let Some((idx, skip)) = position else {
+            return vec![None; suffixes.len()];
+        };
+
+        suffixes
+            .iter()
+            .map(|suffix| {
+                let node = self.get_node(self.find_key_node_from(idx, skip, suffix)?);
+                #[cfg(feature = "ttl")]
+                let val = node.get_value(self.now);
+                #[cfg(not(feature = "ttl"))]
+                let val = node.get_value();
+                val.cloned()
+            })
+            .collect()
+    }
+
+    /// Deletes a node inline (used for TTL expiration cleanup)
+    ///
+    /// `parent` is the parent index and the radix leading to the node, `None` for the root.
+    #[cfg(feature = "ttl")]
+    fn delete_node_inline(&mut self, target_idx: u32, parent: Option<(u32, u8)>) {
+        let has_children = {
+            let node = self.get_node(target_idx);
+            !node.childs.is_empty()
+        };
+
+        match parent {
+            Some((parent_idx, parent_radix)) if !has_children => {
+                self.map.remove(target_idx as usize);
+                self.remove_child(parent_idx, parent_radix);
+                self.try_recompress(parent_idx);
+            }
+            _ => {
+                let node = self.get_node_mut(target_idx);
+                node.val = None;
+                #[cfg(feature = "metadata")]
+                {
+                    node.meta = 0;
+                }
+                self.try_recompress(target_idx);
+            }
+        }
+        self.len -= 1;
+    }
+
+    /// Returns all key-value pairs where the key starts with the given prefix.
+    ///
+    /// If the prefix is empty, returns all entries in the tree.
+    /// Entries come in ascending byte order of their keys: every node keeps its
+    /// children sorted by radix, so the subtree is walked in order as is.
+    /// See `prefix_iter` for the lazy version.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to match.
+    ///
+    /// # Returns
+    ///
+    /// A vector of `(key, value)` tuples for all matching entries.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    /// tree.set(Bytes::from_static(b"user:2"), Bytes::from_static(b"bob"));
+    /// tree.set(Bytes::from_static(b"post:1"), Bytes::from_static(b"hello"));
+    ///
+    /// let users = tree.getn(Bytes::from_static(b"user:"));
+    /// assert_eq!(users.len(), 2);
+    /// ```
+    pub fn getn(&self, prefix: Bytes) -> Vec<(Bytes, V)> {
+        self.getn_slice(&prefix)
+    }
+
+    /// Same as `getn`, with a borrowed prefix.
+    ///
+    /// The prefix is only used to walk the tree, so a `&str` or a borrowed
+    /// buffer can be passed without building a `Bytes` first.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to match.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    ///
+    /// let users = tree.getn_slice("user:".as_bytes());
+    /// assert_eq!(users.len(), 1);
+    /// ```
+    pub fn getn_slice(&self, prefix: &[u8]) -> Vec<(Bytes, V)> {
+        let mut results = Vec::new();
+
+        if let Some(target) = self.find_prefix_target(prefix) {
+            self.collect_all_from(target.idx, target.key_path, &mut results);
+        }
+        results
+    }
+
+    /// Returns the entries under a prefix whose key extends it by at most `max_depth` bytes.
+    ///
+    /// Depth counts key bytes, not nodes, since node boundaries depend on which
+    /// other keys are stored. Subtrees are pruned as soon as their keys get too
+    /// long. See `getn_depth_by` to count hierarchy levels separated by a byte.
+    /// Entries come in ascending key order.
+    /// With the `ttl` feature, expired entries are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to match.
+    /// * `max_depth` - Maximum number of key bytes after the prefix, `0` only matches the prefix itself.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"id:7"), Bytes::from_static(b"a"));
+    /// tree.set(Bytes::from_static(b"id:42"), Bytes::from_static(b"b"));
+    ///
+    /// assert_eq!(tree.getn_depth(Bytes::from_static(b"id:"), 1).len(), 1);
+    /// ```
+    pub fn getn_depth(&self, prefix: Bytes, max_depth: usize) -> Vec<(Bytes, V)> {
+        self.collect_within(&prefix, |suffix| suffix.len() <= max_depth)
+    }
+
+    /// Returns the entries under a prefix at most `max_depth` levels below it.
+    ///
+    /// Levels are delimited by `separator`: the part of the key after the prefix
+    /// is at level 1 without separator, at level 2 with one, and so on. With
+    /// `max_depth = 1`, listing `dir/` returns `dir/a` but not `dir/a/b` nor
+    /// `dir/a/`. Subtrees are pruned as soon as a key has too many separators.
+    /// Entries come in ascending key order.
+    /// With the `ttl` feature, expired entries are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to match.
+    /// * `separator` - The byte delimiting levels, such as `b'/'` or `b':'`.
+    /// * `max_depth` - Maximum number of levels after the prefix, `0` only matches the prefix itself.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"dir/a"), Bytes::from_static(b"file"));
+    /// tree.set(Bytes::from_static(b"dir/b/c"), Bytes::from_static(b"nested"));
+    ///
+    /// let listing = tree.getn_depth_by(Bytes::from_static(b"dir/"), b'/', 1);
+    /// assert_eq!(listing.len(), 1);
+    /// ```
+    pub fn getn_depth_by(&self, prefix: Bytes, separator: u8, max_depth: usize) -> Vec<(Bytes, V)> {
+        if max_depth == 0 {
+            return self.collect_within(&prefix, <[u8]>::is_empty);
+        }
+        self.collect_within(&prefix, |suffix| {
+            suffix.iter().filter(|&&b| b == separator).count() < max_depth
+        })
+    }
+
+    /// Returns the distinct segments one level below a prefix, in ascending order.
+    ///
+    /// Keys are seen as paths delimited by `separator`. For each key under the
+    /// prefix, its segment is the part after the prefix up to the next separator
+    /// (excluded), so `a/b/c`, `a/b/d` and `a/e` listed under `a/` give `b` and `e`.
+    /// The walk stops at the first separator of each branch: deeper levels are
+    /// never visited. A prefix not ending with the separator, such as `a`, is
+    /// listed as the directory `a/`. The empty prefix lists the top level.
+    /// The key equal to the prefix itself is not a segment. With the `ttl`
+    /// feature, segments holding only expired entries are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The directory to list.
+    /// * `separator` - The byte delimiting segments, such as `b'/'` or `b':'`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"a/b/c"), Bytes::from_static(b"1"));
+    /// tree.set(Bytes::from_static(b"a/b/d"), Bytes::from_static(b"2"));
+    /// tree.set(Bytes::from_static(b"a/e"), Bytes::from_static(b"3"));
+    ///
+    /// let segments = tree.children_segments(Bytes::from_static(b"a"), b'/');
+    /// assert_eq!(segments, vec![Bytes::from_static(b"b"), Bytes::from_static(b"e")]);
+    /// ```
+    pub fn children_segments(&self, prefix: Bytes, separator: u8) -> Vec<Bytes> {
+        let mut dir = prefix.to_vec();
+        if dir.last().is_some_and(|&b| b != separator) {
+            dir.push(separator);
+        }
+        let Some(target) = self.find_prefix_target(&dir) else {
+            return Vec::new();
+        };
+
+        let mut segments = BTreeSet::new();
+        let mut stack = vec![(target.idx, target.key_path)];
+        while let Some((idx, key)) = stack.pop() {
+            let suffix = &key[dir.len()..];
+            if let Some(end) = suffix.iter().position(|&b| b == separator) {
+                // Everything below shares this segment
+                if self.subtree_has_value(idx) {
+                    segments.insert(Bytes::copy_from_slice(&suffix[..end]));
+                }
+                continue;
+            }
+
+            let node = self.get_node(idx);
+            #[cfg(feature = "ttl")]
+            let has_value = node.get_value(self.now).is_some();
+            #[cfg(not(feature = "ttl"))]
+            let has_value = node.get_value().is_some();
+            if has_value && !suffix.is_empty() {
+                segments.insert(Bytes::copy_from_slice(suffix));
+            }
+            self.iter_all_children(idx, |radix, child_idx| {
+                let mut child_key = key.clone();
+                child_key.push(radix);
+                child_key.extend_from_slice(&self.get_node(child_idx).compression);
+                stack.push((child_idx, child_key));
+            });
+        }
+
+        segments.into_iter().collect()
+    }
+
+    /// Collects the entries under `prefix` whose key suffix after it passes `within`
+    ///
+    /// `within` must stay false once false for longer suffixes, the subtree
+    /// below a failing node is skipped. Entries come in ascending key order.
+    fn collect_within<F>(&self, prefix: &[u8], within: F) -> Vec<(Bytes, V)>
+    where
+        F: Fn(&[u8]) -> bool,
+    {
+        let Some(target) = self.find_prefix_target(prefix) else {
+            return Vec::new();
+        };
+        let mut key = target.key_path;
+        self.fold_subtree_within(
+            target.idx,
+            &mut key,
+            Vec::new(),
+            &|key| within(&key[prefix.len()..]),
+            &mut |mut results, key, val| {
+                results.push((Bytes::copy_from_slice(key), val.clone()));
+                results
+            },
+        )
+    }
+
+    /// Returns `true` if at least one key starts with the given prefix.
+    ///
+    /// Descends to the prefix node like `getn`, then stops at the first value found
+    /// in its subtree instead of collecting every entry.
+    /// With the `ttl` feature, expired entries don't count.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to match.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    ///
+    /// assert!(tree.prefix_exists(Bytes::from_static(b"us")));
+    /// assert!(!tree.prefix_exists(Bytes::from_static(b"post:")));
+    /// ```
+    pub fn prefix_exists(&self, prefix: Bytes) -> bool {
+        let Some(target) = self.find_prefix_target(&prefix) else {
+            return false;
+        };
+
+        self.subtree_has_value(target.idx)
+    }
+
+    /// Returns `true` if the node or one of its descendants holds a live value
+    fn subtree_has_value(&self, idx: u32) -> bool {
+        let mut stack = vec![idx];
+        while let Some(idx) = stack.pop() {
+            let node = self.get_node(idx);
+            #[cfg(feature = "ttl")]
+            let has_value = node.get_value(self.now).is_some();
+            #[cfg(not(feature = "ttl"))]
+            let has_value = node.get_value().is_some();
+            if has_value {
+                return true;
+            }
+            self.iter_all_children(idx, |_, child_idx| stack.push(child_idx));
+        }
+        false
+    }
+
+    /// Returns the entries matching each of the given prefixes, in the same order.
+    ///
+    /// Equivalent to calling `getn` for each prefix, but each descent resumes from the
+    /// deepest node shared with the previous prefix instead of starting from the root.
+    /// Passing the prefixes sorted maximizes this sharing.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefixes` - The prefixes to match.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"host:1:cpu"), Bytes::from_static(b"42"));
+    /// tree.set(Bytes::from_static(b"host:1:mem"), Bytes::from_static(b"1024"));
+    ///
+    /// let results = tree.getn_multi(&[
+    ///     Bytes::from_static(b"host:1:cpu"),
+    ///     Bytes::from_static(b"host:1:mem"),
+    ///     Bytes::from_static(b"host:1:net"),
+    /// ]);
+    /// assert_eq!(results.iter().map(Vec::len).collect::<Vec<_>>(), vec![1, 1, 0]);
+    /// ```
+    pub fn getn_multi(&self, prefixes: &[Bytes]) -> Vec<Vec<(Bytes, V)>> {
+        let mut descent = DescentCursor::default();
+        prefixes
+            .iter()
+            .map(|prefix| {
+                let mut results = Vec::new();
+                if let Some(target) = self.find_prefix_target_resumed(prefix, &mut descent) {
+                    self.collect_all_from(target.idx, target.key_path, &mut results);
+                }
+                results
+            })
+            .collect()
+    }
+
+    /// Retrieves the values of several keys at once, in the order of `keys`.
+    ///
+    /// The keys are looked up in sorted order, each descent resuming from the
+    /// deepest node shared with the previous key instead of starting from the
+    /// root: keys sharing a long prefix (the fields of one record) only walk it
+    /// once. Like `get_slice`, the tree is only borrowed: expired entries are
+    /// absent but not cleaned up, and keys are not marked as recently used.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - The keys to look up, in any order, duplicates allowed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"user:42:name"), Bytes::from_static(b"alice"));
+    /// tree.set(Bytes::from_static(b"user:42:email"), Bytes::from_static(b"a@x.io"));
+    ///
+    /// let values = tree.get_many(&[
+    ///     Bytes::from_static(b"user:42:name"),
+    ///     Bytes::from_static(b"user:42:phone"),
+    ///     Bytes::from_static(b"user:42:email"),
+    /// ]);
+    /// assert_eq!(values[0], Some(Bytes::from_static(b"alice")));
+    /// assert_eq!(values[1], None);
+    /// ```
+    pub fn get_many(&self, keys: &[Bytes]) -> Vec<Option<V>> {
+        let mut order: Vec<usize> = (0..keys.len()).collect();
+        order.sort_unstable_by(|&a, &b| keys[a].cmp(&keys[b]));
+
+        let mut results = vec![None; keys.len()];
+        let mut descent = DescentCursor::default();
+        for i in order {
+            let key = &keys[i];
+            let target = self.find_prefix_target_resumed(key, &mut descent);
+            // The key may end inside the compression of the node reached
+            let Some(target) = target.filter(|target| target.key_path.len() == key.len()) else {
+                continue;
+            };
+            let node = self.get_node(target.idx);
+            #[cfg(feature = "ttl")]
+            let val = node.get_value(self.now);
+            #[cfg(not(feature = "ttl"))]
+            let val = node.get_value();
+            results[i] = val.cloned();
+        }
+        results
+    }
+
+    /// Returns `true` if the tree has a node at the given prefix, whether or not any value is under it.
+    ///
+    /// This is a structural check, unlike `prefix_exists` which needs a live value at or
+    /// below the prefix. A prefix ending in the middle of a node compression also counts:
+    /// the keys below that node all start with it. In a tree whose invariants hold, only
+    /// expired values (with the `ttl` feature) make the two answers differ, but it also
+    /// reports value-less intermediate nodes, such as the empty root.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to look up.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    ///
+    /// assert!(tree.prefix_node_exists(Bytes::from_static(b"user")));
+    /// assert!(!tree.prefix_node_exists(Bytes::from_static(b"usx")));
+    ///
+    /// // The root always exists, even without any value
+    /// assert!(OxidArt::new().prefix_node_exists(Bytes::new()));
+    /// ```
+    pub fn prefix_node_exists(&self, prefix: Bytes) -> bool {
+        self.find_prefix_target(&prefix).is_some()
+    }
+
+    /// Folds over all entries whose key starts with the given prefix, without collecting them.
+    ///
+    /// `f` receives the accumulator, the reconstructed key and a reference to the value.
+    /// Entries are visited in ascending key order, as with `getn`.
+    /// With the `ttl` feature, expired entries are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to match.
+    /// * `init` - The initial accumulator.
+    /// * `f` - The folding function.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"metrics:req:a"), Bytes::from_static(b"3"));
+    /// tree.set(Bytes::from_static(b"metrics:req:b"), Bytes::from_static(b"4"));
+    ///
+    /// let total = tree.fold_prefix(Bytes::from_static(b"metrics:req:"), 0u64, |acc, _key, val| {
+    ///     acc + std::str::from_utf8(val).unwrap().parse::<u64>().unwrap()
+    /// });
+    /// assert_eq!(total, 7);
+    /// ```
+    pub fn fold_prefix<T, F>(&self, prefix: Bytes, init: T, mut f: F) -> T
+    where
+        F: FnMut(T, &[u8], &V) -> T,
+    {
+        let Some(target) = self.find_prefix_target(&prefix) else {
+            return init;
+        };
+        let mut key = target.key_path;
+        self.fold_subtree(target.idx, &mut key, init, &mut f)
+    }
+
+    /// Folds over the live values of a subtree in DFS order, children by ascending radix.
+    ///
+    /// `key` holds the full key of the node and is used as a scratch buffer:
+    /// it grows on descent and is truncated back on the way up.
+    fn fold_subtree<T, F>(&self, node_idx: u32, key: &mut Vec<u8>, acc: T, f: &mut F) -> T
+    where
+        F: FnMut(T, &[u8], &V) -> T,
+    {
+        self.fold_subtree_within(node_idx, key, acc, &|_| true, f)
+    }
+
+    /// Like `fold_subtree`, skipping the nodes whose full key fails `within`
+    /// along with their whole subtree.
+    fn fold_subtree_within<T, W, F>(
+        &self,
+        node_idx: u32,
+        key: &mut Vec<u8>,
+        mut acc: T,
+        within: &W,
+        f: &mut F,
+    ) -> T
+    where
+        W: Fn(&[u8]) -> bool,
+        F: FnMut(T, &[u8], &V) -> T,
+    {
+        let Some(node) = self.try_get_node(node_idx) else {
+            return acc;
+        };
+        if !within(key) {
+            return acc;
+        }
+
+        #[cfg(feature = "ttl")]
+        if let Some(val) = node.get_value(self.now) {
+            acc = f(acc, key, val);
+        }
+        #[cfg(not(feature = "ttl"))]
+        if let Some(val) = node.get_value() {
+            acc = f(acc, key, val);
+        }
+
+        let key_len = key.len();
+        for (radix, child_idx) in self.children(node_idx) {
+            key.push(radix);
+            key.extend_from_slice(&self.get_node(child_idx).compression);
+            acc = self.fold_subtree_within(child_idx, key, acc, within, f);
+            key.truncate(key_len);
+        }
+        acc
+    }
+
+    /// Consumes the tree and returns all entries sorted by key in ascending order.
+    ///
+    /// Values are moved out of the nodes instead of being cloned, see `into_iter`
+    /// for the lazy version.
+    /// With the `ttl` feature, expired entries are dropped.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"b"), Bytes::from_static(b"2"));
+    /// tree.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+    ///
+    /// let entries = tree.into_sorted_vec();
+    /// assert_eq!(entries[0].0, Bytes::from_static(b"a"));
+    /// ```
+    pub fn into_sorted_vec(self) -> Vec<(Bytes, V)> {
+        self.into_iter().collect()
+    }
+
+    /// Returns all children of a node
+    fn all_children(&self, node_idx: u32) -> SmallVec<[(u8, u32); CHILDS_SIZE]> {
+        let mut children = SmallVec::new();
+        self.iter_all_children(node_idx, |radix, child_idx| {
+            children.push((radix, child_idx));
+        });
+        children
+    }
+
+    /// Iterates over the children of a node in ascending radix order
+    fn children(&self, node_idx: u32) -> impl DoubleEndedIterator<Item = (u8, u32)> + '_ {
+        self.try_get_node(node_idx)
+            .into_iter()
+            .flat_map(|node| node.childs.iter())
+    }
+
+    /// Collects from a node whose key is already complete in key_path
+    ///
+    /// The key path is used as a single scratch buffer for the whole subtree,
+    /// keys are only copied out when an entry is emitted.
+    fn collect_all_from(
+        &self,
+        node_idx: u32,
+        mut key_path: Vec<u8>,
+        results: &mut Vec<(Bytes, V)>,
+    ) {
+        self.fold_subtree(node_idx, &mut key_path, (), &mut |(), key, val| {
+            results.push((Bytes::copy_from_slice(key), val.clone()));
+        });
+    }
+
+    /// Iterates over all children of a node
+    fn iter_all_children<F>(&self, node_idx: u32, mut f: F)
+    where
+        F: FnMut(u8, u32),
+    {
+        let Some(node) = self.try_get_node(node_idx) else {
+            return;
+        };
+
+        for (radix, child_idx) in node.childs.iter() {
+            f(radix, child_idx);
+        }
+    }
+
+    /// Inserts or updates a key-value pair in the tree (no expiration).
+    ///
+    /// If the key already exists, the value is replaced.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to insert.
+    /// * `val` - The value to associate with the key.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    ///
+    /// // Insert a new key
+    /// tree.set(Bytes::from_static(b"key"), Bytes::from_static(b"value1"));
+    ///
+    /// // Update an existing key
+    /// tree.set(Bytes::from_static(b"key"), Bytes::from_static(b"value2"));
+    ///
+    /// assert_eq!(tree.get(Bytes::from_static(b"key")), Some(Bytes::from_static(b"value2")));
+    /// ```
+    pub fn set(&mut self, key: Bytes, val: V) {
+        let lru_key = self.lru.is_some().then(|| key.clone());
+        #[cfg(feature = "ttl")]
+        let result = self.set_internal(key, NO_EXPIRY, val);
+        #[cfg(not(feature = "ttl"))]
+        let result = self.set_internal(key, val);
+        result.unwrap_or_else(|e| panic!("set failed: {e}"));
+        if let Some(key) = lru_key {
+            self.lru_record_write(&key);
+        }
+    }
+
+    /// Inserts or updates a key-value pair, then passes the previous value to `on_prev`.
+    ///
+    /// Same as `set`, with `on_prev` called once the new value is stored: with
+    /// `Some(old)` when a value was overwritten, `None` on a fresh insert. With
+    /// the `ttl` feature, an expired previous value counts as absent.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to insert.
+    /// * `val` - The value to associate with the key.
+    /// * `on_prev` - Called with the previous value, for instance to invalidate dependents.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"config"), Bytes::from_static(b"v1"));
+    ///
+    /// tree.set_observed(Bytes::from_static(b"config"), Bytes::from_static(b"v2"), |prev| {
+    ///     assert_eq!(prev, Some(Bytes::from_static(b"v1")));
+    /// });
+    /// ```
+    pub fn set_observed<F>(&mut self, key: Bytes, val: V, on_prev: F)
+    where
+        F: FnOnce(Option<V>),
+    {
+        on_prev(self.replace(key, val));
+    }
+
+    /// Inserts or updates a key-value pair, returning the previous value.
+    ///
+    /// Same as `set`, in one traversal: the displaced value is taken from the
+    /// node being written, so no `get` is needed beforehand. With the `ttl`
+    /// feature, an expired previous value counts as absent.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to insert.
+    /// * `val` - The value to associate with the key.
+    ///
+    /// # Returns
+    ///
+    /// The previous value, or `None` on a fresh insert.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// assert_eq!(tree.replace(Bytes::from_static(b"config"), Bytes::from_static(b"v1")), None);
+    /// assert_eq!(
+    ///     tree.replace(Bytes::from_static(b"config"), Bytes::from_static(b"v2")),
+    ///     Some(Bytes::from_static(b"v1"))
+    /// );
+    /// ```
+    pub fn replace(&mut self, key: Bytes, val: V) -> Option<V> {
+        let lru_key = self.lru.is_some().then(|| key.clone());
+        #[cfg(feature = "ttl")]
+        let result = self.set_internal(key, NO_EXPIRY, val);
+        #[cfg(not(feature = "ttl"))]
+        let result = self.set_internal(key, val);
+        let prev = result.unwrap_or_else(|e| panic!("replace failed: {e}"));
+        if let Some(key) = lru_key {
+            self.lru_record_write(&key);
+        }
+        prev
+    }
+
+    /// Inserts or updates a key-value pair, returning an error instead of panicking.
+    ///
+    /// Same as `set`, but the key and the structural limits are checked before
+    /// anything is modified, so that bad input can be rejected gracefully. The
+    /// maximum key length set with `OxidArtBuilder::max_key_len` is only
+    /// enforced here: `set` and the other writes accept keys of any length.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to insert.
+    /// * `val` - The value to associate with the key.
+    ///
+    /// # Errors
+    ///
+    /// - `OxidArtError::InvalidKey(KeyError::TooLong { .. })` if the key exceeds the maximum length.
+    /// - `OxidArtError::CapacityExceeded` if the tree is out of node indexes.
+    ///
+    /// The tree is left unchanged on error.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// assert_eq!(tree.try_set(Bytes::from_static(b"key"), Bytes::from_static(b"value")), Ok(()));
+    /// ```
+    pub fn try_set(&mut self, key: Bytes, val: V) -> Result<(), OxidArtError> {
+        if let Some(max) = self.max_key_len
+            && key.len() > max
+        {
+            return Err(KeyError::TooLong {
+                len: key.len(),
+                max,
+            }
+            .into());
+        }
+        let lru_key = self.lru.is_some().then(|| key.clone());
+        #[cfg(feature = "ttl")]
+        self.set_internal(key, NO_EXPIRY, val)?;
+        #[cfg(not(feature = "ttl"))]
+        self.set_internal(key, val)?;
+        if let Some(key) = lru_key {
+            self.lru_record_write(&key);
+        }
+        Ok(())
+    }
+
+    /// Inserts or updates a key-value pair with a time-to-live duration.
+    ///
+    /// The key will expire after `ttl` duration from the current timestamp (`self.now`).
+    /// Make sure to call `tick()` or `set_now()` to keep the internal clock updated.
+    ///
+    /// The expiry saturates: a TTL too large to be represented (such as
+    /// `Duration::MAX`) means the key never expires. A zero TTL expires the key
+    /// immediately, so it is deleted instead of being stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to insert.
+    /// * `ttl` - Duration after which the key expires.
+    /// * `val` - The value to associate with the key.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    /// use std::time::Duration;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set_now(1000); // Set current time
     ///
     /// // Insert with 60 second TTL
     /// tree.set_ttl(Bytes::from_static(b"session"), Duration::from_secs(60), Bytes::from_static(b"data"));
     ///
-    /// // Key expires at timestamp 1060
+    /// // Key expires at timestamp 1060
+    /// ```
+    #[cfg(feature = "ttl")]
+    pub fn set_ttl(&mut self, key: Bytes, ttl: core::time::Duration, val: V) {
+        if ttl.is_zero() {
+            self.del(key);
+            return;
+        }
+        let expires_at = self.now.saturating_add(ttl.as_secs());
+        let lru_key = self.lru.is_some().then(|| key.clone());
+        self.set_internal(key, expires_at, val)
+            .unwrap_or_else(|e| panic!("set_ttl failed: {e}"));
+        if let Some(key) = lru_key {
+            self.lru_record_write(&key);
+        }
+    }
+}
+
+impl<const N: usize> OxidArtN<N> {
+    /// Appends `suffix` to the value of `key`, creating it if it doesn't exist.
+    ///
+    /// The value is grown in place when its buffer is not shared, otherwise it is
+    /// copied once into a new buffer. With the `ttl` feature, the expiration of an
+    /// existing entry is kept, and an expired entry is replaced as if absent.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to append to.
+    /// * `suffix` - The bytes to append to the value.
+    ///
+    /// # Returns
+    ///
+    /// The total length of the value after the append.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// assert_eq!(tree.append(Bytes::from_static(b"log"), b"hello"), 5);
+    /// assert_eq!(tree.append(Bytes::from_static(b"log"), b" world"), 11);
+    ///
+    /// assert_eq!(tree.get(Bytes::from_static(b"log")), Some(Bytes::from_static(b"hello world")));
+    /// ```
+    pub fn append(&mut self, key: Bytes, suffix: &[u8]) -> usize {
+        #[cfg(feature = "ttl")]
+        let now = self.now;
+
+        if let Some(idx) = self.find_key_node(&key) {
+            let node = self.get_node_mut(idx);
+            #[cfg(feature = "ttl")]
+            let existing = node.get_value_mut(now);
+            #[cfg(not(feature = "ttl"))]
+            let existing = node.get_value_mut();
+
+            if let Some(val) = existing {
+                let mut buf = match core::mem::take(val).try_into_mut() {
+                    Ok(buf) => buf,
+                    Err(shared) => {
+                        let mut buf = BytesMut::with_capacity(shared.len() + suffix.len());
+                        buf.extend_from_slice(&shared);
+                        buf
+                    }
+                };
+                buf.extend_from_slice(suffix);
+                *val = buf.freeze();
+                let len = val.len();
+                if let Some(lru) = self.lru.as_mut() {
+                    lru.touch(&key);
+                }
+                return len;
+            }
+        }
+
+        self.set(key, Bytes::copy_from_slice(suffix));
+        suffix.len()
+    }
+}
+
+impl<const N: usize, V: Clone> OxidArtN<N, V> {
+    /// Calls `f` on the value of `key`, allowing a read-modify-write in a single walk.
+    ///
+    /// `f` receives a mutable reference to the stored value, which it can modify
+    /// in place or reassign. Nothing happens if the key is absent. With the
+    /// `ttl` feature, an expired entry is absent and the expiration of a live
+    /// one is kept.
+    ///
+    /// `Bytes` itself is immutable, so for the default value type `f` reassigns
+    /// it, for instance after `try_into_mut` or a copy.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the value to update.
+    /// * `f` - The function applied to the value.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the key was found and `f` called.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"hits"), Bytes::from_static(b"41"));
+    ///
+    /// let updated = tree.update(Bytes::from_static(b"hits"), |val| {
+    ///     let hits: u64 = std::str::from_utf8(val).unwrap().parse().unwrap();
+    ///     *val = Bytes::from((hits + 1).to_string());
+    /// });
+    /// assert!(updated);
+    /// assert_eq!(tree.get(Bytes::from_static(b"hits")), Some(Bytes::from_static(b"42")));
+    /// ```
+    pub fn update<F: FnOnce(&mut V)>(&mut self, key: Bytes, f: F) -> bool {
+        let Some(idx) = self.find_key_node(&key) else {
+            return false;
+        };
+        #[cfg(feature = "ttl")]
+        let now = self.now;
+        let node = self.get_node_mut(idx);
+        #[cfg(feature = "ttl")]
+        let val = node.get_value_mut(now);
+        #[cfg(not(feature = "ttl"))]
+        let val = node.get_value_mut();
+        let Some(val) = val else {
+            return false;
+        };
+        f(val);
+        if let Some(lru) = self.lru.as_mut() {
+            lru.touch(&key);
+        }
+        true
+    }
+
+    /// Replaces the value of a key only if it currently holds `expected`, in one traversal.
+    ///
+    /// `expected` is compared to the live value: `None` means the key must be
+    /// absent, in which case `new` is inserted. Like `set`, the stored value has
+    /// no expiration. On mismatch the tree is left unchanged.
+    /// With the `ttl` feature, an expired entry counts as absent.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to update.
+    /// * `expected` - The value the key must hold, `None` if it must be absent.
+    /// * `new` - The value stored on match.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the current value matched and `new` was stored.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// assert!(tree.cas(Bytes::from_static(b"lock"), None, Bytes::from_static(b"owner-1")));
+    /// // Already taken
+    /// assert!(!tree.cas(Bytes::from_static(b"lock"), None, Bytes::from_static(b"owner-2")));
+    ///
+    /// let read = tree.get(Bytes::from_static(b"lock"));
+    /// assert!(tree.cas(Bytes::from_static(b"lock"), read, Bytes::from_static(b"owner-3")));
+    /// ```
+    pub fn cas(&mut self, key: Bytes, expected: Option<V>, new: V) -> bool
+    where
+        V: PartialEq,
+    {
+        let swapped = match expected {
+            None => {
+                #[cfg(feature = "ttl")]
+                let result = self.upsert_internal(key.clone(), NO_EXPIRY, false, || new);
+                #[cfg(not(feature = "ttl"))]
+                let result = self.upsert_internal(key.clone(), false, || new);
+                // Nothing was live, so the value was inserted
+                result
+                    .unwrap_or_else(|e| panic!("cas failed: {e}"))
+                    .is_none()
+            }
+            Some(expected) => {
+                let Some(idx) = self.find_key_node(&key) else {
+                    return false;
+                };
+                #[cfg(feature = "ttl")]
+                let current = self.get_node(idx).get_value(self.now);
+                #[cfg(not(feature = "ttl"))]
+                let current = self.get_node(idx).get_value();
+                if current != Some(&expected) {
+                    return false;
+                }
+                let new = self.intern_value(new);
+                #[cfg(feature = "ttl")]
+                self.get_node_mut(idx).set_val(new, NO_EXPIRY);
+                #[cfg(not(feature = "ttl"))]
+                self.get_node_mut(idx).set_val(new);
+                true
+            }
+        };
+        if swapped {
+            self.lru_record_write(&key);
+        }
+        swapped
+    }
+
+    /// Calls `f` on every value whose key starts with the given prefix, allowing in-place updates.
+    ///
+    /// `f` receives the reconstructed key and a mutable reference to the value.
+    /// The matching nodes are collected first, then `f` is applied to each of them,
+    /// so the tree structure is never borrowed while `f` runs.
+    /// With the `ttl` feature, expired entries are skipped and expirations are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to match.
+    /// * `f` - The function applied to each matching entry.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"user:123:s1"), Bytes::from_static(b"active"));
+    /// tree.set(Bytes::from_static(b"user:123:s2"), Bytes::from_static(b"active"));
+    ///
+    /// tree.for_each_prefix_mut(Bytes::from_static(b"user:123:"), |_key, val| {
+    ///     *val = Bytes::from_static(b"stale");
+    /// });
+    /// assert_eq!(tree.get(Bytes::from_static(b"user:123:s1")), Some(Bytes::from_static(b"stale")));
     /// ```
+    pub fn for_each_prefix_mut<F>(&mut self, prefix: Bytes, mut f: F)
+    where
+        F: FnMut(&[u8], &mut V),
+    {
+        let Some(target) = self.find_prefix_target(&prefix) else {
+            return;
+        };
+
+        let mut targets: Vec<(Vec<u8>, u32)> = Vec::new();
+        let mut stack = vec![(target.idx, target.key_path)];
+        while let Some((idx, key)) = stack.pop() {
+            self.iter_all_children(idx, |radix, child_idx| {
+                let mut child_key = key.clone();
+                child_key.push(radix);
+                child_key.extend_from_slice(&self.get_node(child_idx).compression);
+                stack.push((child_idx, child_key));
+            });
+            if self.get_node(idx).val.is_some() {
+                targets.push((key, idx));
+            }
+        }
+
+        #[cfg(feature = "ttl")]
+        let now = self.now;
+        for (key, idx) in targets {
+            let node = self.get_node_mut(idx);
+            #[cfg(feature = "ttl")]
+            let val = node.get_value_mut(now);
+            #[cfg(not(feature = "ttl"))]
+            let val = node.get_value_mut();
+            if let Some(val) = val {
+                f(&key, val);
+            }
+        }
+    }
+
+    /// Stores the value, returning the previous live one
     #[cfg(feature = "ttl")]
-    pub fn set_ttl(&mut self, key: Bytes, ttl: std::time::Duration, val: Bytes) {
-        let expires_at = self.now.saturating_add(ttl.as_secs());
-        self.set_internal(key, expires_at, val);
+    fn set_internal(&mut self, key: Bytes, ttl: u64, val: V) -> Result<Option<V>, OxidArtError> {
+        self.upsert_internal(key, ttl, true, || val)
+    }
+
+    /// Stores the value made by `make_val`, returning the previous live one
+    ///
+    /// With `overwrite` unset, a live value is kept and `make_val` is not called.
+    /// Either way the tree is walked once.
+    #[cfg(feature = "ttl")]
+    fn upsert_internal<F>(
+        &mut self,
+        key: Bytes,
+        ttl: u64,
+        overwrite: bool,
+        make_val: F,
+    ) -> Result<Option<V>, OxidArtError>
+    where
+        F: FnOnce() -> V,
+    {
+        self.check_node_capacity()?;
+        let mut idx = self.root_idx;
+        let mut cursor = 0;
+
+        loop {
+            let node_comparaison = self.get_node(idx).compare_compression_key(&key[cursor..]);
+            let common_len = match node_comparaison {
+                CompResult::Final => {
+                    let now = self.now;
+                    let prev = self.get_node(idx).get_value(now).cloned();
+                    if prev.is_some() && !overwrite {
+                        return Ok(prev);
+                    }
+                    let val = self.intern_value(make_val());
+                    let node = self.get_node_mut(idx);
+                    let is_new = node.val.is_none();
+                    node.set_val(val, ttl);
+                    // Metadata belongs to the previous live value
+                    #[cfg(feature = "metadata")]
+                    if prev.is_none() {
+                        node.meta = 0;
+                    }
+                    self.len += usize::from(is_new);
+                    return Ok(prev);
+                }
+                CompResult::Path => {
+                    cursor += self.get_node(idx).compression.len();
+                    let Some(child_idx) = self.find(idx, key[cursor]) else {
+                        let val = self.intern_value(make_val());
+                        self.create_node_with_val(idx, key[cursor], val, &key[(cursor + 1)..], ttl);
+                        return Ok(None);
+                    };
+                    idx = child_idx;
+                    cursor += 1;
+                    continue;
+                }
+                CompResult::Partial(common_len) => common_len,
+            };
+
+            // Split: node compression only partially matches the key
+            let key_rest = &key[cursor..];
+            let val = self.intern_value(make_val());
+            self.split_node(idx, common_len);
+
+            if common_len == key_rest.len() {
+                self.get_node_mut(idx).set_val(val, ttl);
+                self.len += 1;
+            } else {
+                // The value doesn't go on the intermediate node, create a new leaf
+                let new_radix = key_rest[common_len];
+                let new_compression = &key_rest[common_len + 1..];
+                self.create_node_with_val(idx, new_radix, val, new_compression, ttl);
+            }
+
+            return Ok(None);
+        }
+    }
+
+    /// Stores the value, returning the previous one
+    #[cfg(not(feature = "ttl"))]
+    fn set_internal(&mut self, key: Bytes, val: V) -> Result<Option<V>, OxidArtError> {
+        self.upsert_internal(key, true, || val)
+    }
+
+    /// Stores the value made by `make_val`, returning the previous one
+    ///
+    /// With `overwrite` unset, a value is kept and `make_val` is not called.
+    /// Either way the tree is walked once.
+    #[cfg(not(feature = "ttl"))]
+    fn upsert_internal<F>(
+        &mut self,
+        key: Bytes,
+        overwrite: bool,
+        make_val: F,
+    ) -> Result<Option<V>, OxidArtError>
+    where
+        F: FnOnce() -> V,
+    {
+        self.check_node_capacity()?;
+        let mut idx = self.root_idx;
+        let mut cursor = 0;
+
+        loop {
+            let node_comparaison = self.get_node(idx).compare_compression_key(&key[cursor..]);
+            let common_len = match node_comparaison {
+                CompResult::Final => {
+                    let prev = self.get_node(idx).get_value().cloned();
+                    if prev.is_some() && !overwrite {
+                        return Ok(prev);
+                    }
+                    let val = self.intern_value(make_val());
+                    let node = self.get_node_mut(idx);
+                    let is_new = node.val.is_none();
+                    node.set_val(val);
+                    #[cfg(feature = "metadata")]
+                    if prev.is_none() {
+                        node.meta = 0;
+                    }
+                    self.len += usize::from(is_new);
+                    return Ok(prev);
+                }
+                CompResult::Path => {
+                    cursor += self.get_node(idx).compression.len();
+                    let Some(child_idx) = self.find(idx, key[cursor]) else {
+                        let val = self.intern_value(make_val());
+                        self.create_node_with_val(idx, key[cursor], val, &key[(cursor + 1)..]);
+                        return Ok(None);
+                    };
+                    idx = child_idx;
+                    cursor += 1;
+                    continue;
+                }
+                CompResult::Partial(common_len) => common_len,
+            };
+
+            // Split: node compression only partially matches the key
+            let key_rest = &key[cursor..];
+            let val = self.intern_value(make_val());
+            self.split_node(idx, common_len);
+
+            if common_len == key_rest.len() {
+                self.get_node_mut(idx).set_val(val);
+                self.len += 1;
+            } else {
+                // The value doesn't go on the intermediate node, create a new leaf
+                let new_radix = key_rest[common_len];
+                let new_compression = &key_rest[common_len + 1..];
+                self.create_node_with_val(idx, new_radix, val, new_compression);
+            }
+
+            return Ok(None);
+        }
+    }
+
+    #[cfg(feature = "ttl")]
+    fn create_node_with_val(
+        &mut self,
+        idx: u32,
+        radix: u8,
+        val: V,
+        compression: &[u8],
+        ttl: u64,
+    ) -> u32 {
+        let new_leaf = Node::new_leaf(compression, val, ttl);
+        let inserted_idx = self.insert(new_leaf);
+        self.attach_child(idx, radix, inserted_idx);
+        self.len += 1;
+        inserted_idx
+    }
+
+    #[cfg(not(feature = "ttl"))]
+    fn create_node_with_val(&mut self, idx: u32, radix: u8, val: V, compression: &[u8]) -> u32 {
+        let new_leaf = Node::new_leaf(compression, val);
+        let inserted_idx = self.insert(new_leaf);
+        self.attach_child(idx, radix, inserted_idx);
+        self.len += 1;
+        inserted_idx
+    }
+
+    /// Splits the compression of `idx` after `common_len` bytes.
+    ///
+    /// The node keeps the first `common_len` bytes; the rest, along with its value
+    /// and children, moves to a new child under the next compression byte.
+    fn split_node(&mut self, idx: u32, common_len: usize) {
+        let (old_compression, old_val, old_childs) = {
+            let node = self.get_node_mut(idx);
+            let old_compression = core::mem::take(&mut node.compression);
+            let old_val = node.val.take();
+            let old_childs = core::mem::take(&mut node.childs);
+            node.compression = SmallVec::from_slice(&old_compression[..common_len]);
+            (old_compression, old_val, old_childs)
+        };
+        #[cfg(feature = "metadata")]
+        let old_meta = core::mem::take(&mut self.get_node_mut(idx).meta);
+
+        let old_radix = old_compression[common_len];
+        let old_child = Node {
+            compression: SmallVec::from_slice(&old_compression[common_len + 1..]),
+            val: old_val,
+            childs: old_childs,
+            #[cfg(feature = "metadata")]
+            meta: old_meta,
+        };
+        let old_child_idx = self.insert(old_child);
+        self.attach_child(idx, old_radix, old_child_idx);
+        // The node now stands for a shorter key
+        self.bump_version(idx);
+    }
+
+    /// Links `child_idx` under `idx`, growing its children into a larger tier when full.
+    fn attach_child(&mut self, idx: u32, radix: u8, child_idx: u32) {
+        self.get_node_mut(idx).childs.push(radix, child_idx);
+    }
+
+    /// Checks that an insertion (at most two nodes) still fits in `u32` indexes,
+    /// `u32::MAX` being reserved as the "no child" sentinel.
+    fn check_node_capacity(&self) -> Result<(), OxidArtError> {
+        if self.map.len() + 2 >= u32::MAX as usize {
+            return Err(OxidArtError::CapacityExceeded);
+        }
+        Ok(())
+    }
+
+    /// Deletes a key from the tree and returns its value.
+    ///
+    /// Returns `Some(value)` if the key existed, or `None` if it didn't.
+    /// The tree automatically recompresses paths after deletion.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to delete.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"key"), Bytes::from_static(b"value"));
+    ///
+    /// let deleted = tree.del(Bytes::from_static(b"key"));
+    /// assert_eq!(deleted, Some(Bytes::from_static(b"value")));
+    ///
+    /// // Key no longer exists
+    /// assert_eq!(tree.get(Bytes::from_static(b"key")), None);
+    /// ```
+    pub fn del(&mut self, key: Bytes) -> Option<V> {
+        if let Some(lru) = self.lru.as_mut() {
+            lru.remove(&key);
+        }
+
+        // Traverse like get, keeping track of the immediate parent
+        let mut parent = None;
+        let mut idx = self.root_idx;
+        let mut cursor = 0;
+
+        let target_idx = loop {
+            let node = self.try_get_node(idx)?;
+            match node.compare_compression_key(&key[cursor..]) {
+                CompResult::Final => break idx,
+                CompResult::Partial(_) => return None,
+                CompResult::Path => {
+                    cursor += node.compression.len();
+                }
+            }
+
+            // Continue traversal
+            let radix = key[cursor];
+            parent = Some((idx, radix));
+            idx = self.find(idx, radix)?;
+            cursor += 1;
+        };
+
+        self.remove_value_at(target_idx, parent)
     }
 
-    #[cfg(feature = "ttl")]
-    fn set_internal(&mut self, key: Bytes, ttl: u64, val: Bytes) {
-        debug_assert!(key.is_ascii(), "key must be ASCII");
-        let key_len = key.len();
-        if key_len == 0 {
-            self.get_node_mut(self.root_idx).set_val(val, ttl);
-            return;
+    /// Deletes a key and returns the stored key along with its value.
+    ///
+    /// Mirrors `HashMap::remove_entry`: the returned key is reconstructed from the
+    /// traversal path (radixes and compressions) before the node is removed, so it
+    /// is the exact key bytes held by the tree. Like `del`, an expired entry is
+    /// still removed and returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to delete.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"key"), Bytes::from_static(b"value"));
+    ///
+    /// assert_eq!(
+    ///     tree.remove_entry(Bytes::from_static(b"key")),
+    ///     Some((Bytes::from_static(b"key"), Bytes::from_static(b"value")))
+    /// );
+    /// assert_eq!(tree.get(Bytes::from_static(b"key")), None);
+    /// ```
+    pub fn remove_entry(&mut self, key: Bytes) -> Option<(Bytes, V)> {
+        if let Some(lru) = self.lru.as_mut() {
+            lru.remove(&key);
         }
+
+        let mut parent = None;
         let mut idx = self.root_idx;
         let mut cursor = 0;
+        let mut key_path: Vec<u8> = Vec::with_capacity(key.len());
 
-        loop {
-            let Some(child_idx) = self.find(idx, key[cursor]) else {
-                self.create_node_with_val(idx, key[cursor], val, &key[(cursor + 1)..], ttl);
-                return;
-            };
-            idx = child_idx;
-            cursor += 1;
-            let node_comparaison = self.get_node(idx).compare_compression_key(&key[cursor..]);
-            let common_len = match node_comparaison {
+        let target_idx = loop {
+            let node = self.try_get_node(idx)?;
+            match node.compare_compression_key(&key[cursor..]) {
                 CompResult::Final => {
-                    self.get_node_mut(idx).set_val(val, ttl);
-                    return;
+                    key_path.extend_from_slice(&node.compression);
+                    break idx;
                 }
+                CompResult::Partial(_) => return None,
                 CompResult::Path => {
-                    cursor += self.get_node(idx).compression.len();
-                    continue;
+                    key_path.extend_from_slice(&node.compression);
+                    cursor += node.compression.len();
                 }
-                CompResult::Partial(common_len) => common_len,
-            };
+            }
 
-            // Split: node compression only partially matches the key
-            let key_rest = &key[cursor..];
-            let val_on_intermediate = common_len == key_rest.len();
-
-            // Extract old state and configure intermediate in one pass
-            let (old_compression, old_val, old_childs) = {
-                let node = self.get_node_mut(idx);
-                let old_compression = std::mem::take(&mut node.compression);
-                let old_val = node.val.take();
-                let old_childs = std::mem::take(&mut node.childs);
-
-                node.compression = SmallVec::from_slice(&old_compression[..common_len]);
-                if val_on_intermediate {
-                    node.val = Some((val.clone(), ttl));
-                }
+            let radix = key[cursor];
+            parent = Some((idx, radix));
+            idx = self.find(idx, radix)?;
+            key_path.push(radix);
+            cursor += 1;
+        };
 
-                (old_compression, old_val, old_childs)
-            };
+        let val = self.remove_value_at(target_idx, parent)?;
+        Some((Bytes::from(key_path), val))
+    }
 
-            // Create a node for the old content
-            let old_radix = old_compression[common_len];
-            let old_child = Node {
-                compression: SmallVec::from_slice(&old_compression[common_len + 1..]),
-                val: old_val,
-                childs: old_childs,
-            };
-            let old_child_idx = self.insert(old_child);
-            self.get_node_mut(idx).childs.push(old_radix, old_child_idx);
+    /// Removes the value held by `target_idx`, reached from `parent` (`None` for the root).
+    ///
+    /// A leaf is freed and its parent recompressed; a node with children (or the root)
+    /// keeps its place and is recompressed itself.
+    fn remove_value_at(&mut self, target_idx: u32, parent: Option<(u32, u8)>) -> Option<V> {
+        // Check if the node has children
+        let has_children = {
+            let node = self.get_node(target_idx);
+            !node.childs.is_empty()
+        };
 
-            // If the value doesn't go on the intermediate node, create a new leaf
-            if !val_on_intermediate {
-                let new_radix = key_rest[common_len];
-                let new_compression = &key_rest[common_len + 1..];
-                self.create_node_with_val(idx, new_radix, val, new_compression, ttl);
+        match parent {
+            Some((parent_idx, parent_radix)) if !has_children => {
+                // Node without children (leaf): completely remove from the slab
+                self.get_node(target_idx).val.as_ref()?;
+                let node = self.map.remove(target_idx as usize);
+                let old_val = node.val?;
+                self.len -= 1;
+                self.remove_child(parent_idx, parent_radix);
+                self.try_recompress(parent_idx);
+                #[cfg(feature = "ttl")]
+                return Some(old_val.0);
+                #[cfg(not(feature = "ttl"))]
+                Some(old_val)
+            }
+            _ => {
+                // Node with children (or root): keep the node, just remove the value
+                let node = self.get_node_mut(target_idx);
+                let old_val = node.val.take()?;
+                #[cfg(feature = "metadata")]
+                {
+                    node.meta = 0;
+                }
+                self.len -= 1;
+                self.try_recompress(target_idx);
+                #[cfg(feature = "ttl")]
+                return Some(old_val.0);
+                #[cfg(not(feature = "ttl"))]
+                Some(old_val)
             }
+        }
+    }
 
-            return;
+    /// Deletes all keys that start with the given prefix.
+    ///
+    /// Returns the number of key-value pairs that were deleted.
+    /// If the prefix is empty, all entries are deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to match.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    /// tree.set(Bytes::from_static(b"user:2"), Bytes::from_static(b"bob"));
+    /// tree.set(Bytes::from_static(b"post:1"), Bytes::from_static(b"hello"));
+    ///
+    /// // Delete all user entries
+    /// let count = tree.deln(Bytes::from_static(b"user:"));
+    /// assert_eq!(count, 2);
+    ///
+    /// // Only post entries remain
+    /// assert_eq!(tree.getn(Bytes::from_static(b"")).len(), 1);
+    /// ```
+    pub fn deln(&mut self, prefix: Bytes) -> usize {
+        if let Some(lru) = self.lru.as_mut() {
+            lru.remove_prefix(&prefix);
         }
+        let Some(target) = self.find_prefix_target(&prefix) else {
+            return 0;
+        };
+        self.detach_prefix_target(target)
     }
 
-    #[cfg(not(feature = "ttl"))]
-    fn set_internal(&mut self, key: Bytes, val: Bytes) {
-        debug_assert!(key.is_ascii(), "key must be ASCII");
+    /// Deletes all keys that start with the given prefix, passing each removed entry to a callback.
+    ///
+    /// This is `deln` for callers that need to invalidate something downstream:
+    /// the subtree is freed in a single walk, and `cb` is called with the full key
+    /// and the value of every entry just before its node is freed. The order of
+    /// the calls is unspecified. Like `deln`, entries that are expired but not yet
+    /// cleaned up are removed and counted, so they are passed to `cb` as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to match.
+    /// * `cb` - Called with the full key and the value of each removed entry.
+    ///
+    /// # Returns
+    ///
+    /// The number of deleted entries, which is the number of calls to `cb`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"page:/home"), Bytes::from_static(b"<html>"));
+    /// tree.set(Bytes::from_static(b"page:/about"), Bytes::from_static(b"<html>"));
+    ///
+    /// let mut purged = Vec::new();
+    /// let count = tree.deln_observed(Bytes::from_static(b"page:"), |key, _| purged.push(key.to_vec()));
+    /// assert_eq!(count, 2);
+    /// assert_eq!(purged.len(), 2);
+    /// ```
+    pub fn deln_observed<F>(&mut self, prefix: Bytes, mut cb: F) -> usize
+    where
+        F: FnMut(&[u8], &V),
+    {
+        self.deln_moving(&prefix, |key, val| cb(key, &val))
+    }
+
+    /// Deletes all keys that start with the given prefix, handing each removed entry over to `f`.
+    ///
+    /// Same as `deln_observed`, with owned entries: every key is rebuilt from the
+    /// path down to the prefix target plus the compressions below it, and every
+    /// value is moved out of its node as the node is freed, without a clone.
+    /// The order of the calls is unspecified, and entries that are expired but
+    /// not yet cleaned up are passed to `f` as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to match.
+    /// * `f` - Called with the full key and the value of each removed entry.
+    ///
+    /// # Returns
+    ///
+    /// The number of deleted entries, which is the number of calls to `f`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"session:a"), Bytes::from_static(b"1"));
+    /// tree.set(Bytes::from_static(b"session:b"), Bytes::from_static(b"2"));
+    ///
+    /// let mut replica_log = Vec::new();
+    /// let count = tree.deln_with(Bytes::from_static(b"session:"), |key, _val| replica_log.push(key));
+    /// assert_eq!(count, 2);
+    /// ```
+    pub fn deln_with<F>(&mut self, prefix: Bytes, mut f: F) -> usize
+    where
+        F: FnMut(Bytes, V),
+    {
+        self.deln_moving(&prefix, |key, val| f(Bytes::copy_from_slice(key), val))
+    }
+
+    /// Frees the subtree under `prefix` in a single walk, moving each value into `cb`
+    fn deln_moving<F>(&mut self, prefix: &[u8], mut cb: F) -> usize
+    where
+        F: FnMut(&[u8], V),
+    {
+        if let Some(lru) = self.lru.as_mut() {
+            lru.remove_prefix(prefix);
+        }
+        let Some(PrefixTarget {
+            idx,
+            parent,
+            key_path: mut key,
+        }) = self.find_prefix_target(prefix)
+        else {
+            return 0;
+        };
+        let children = self.all_children(idx);
+        // Detach the target, the root node staying in place
+        let mut target = match parent {
+            Some((parent_idx, parent_radix)) => {
+                self.remove_child(parent_idx, parent_radix);
+                self.map.remove(idx as usize)
+            }
+            None => {
+                let root = core::mem::take(self.get_node_mut(idx));
+                if !root.compression.is_empty() {
+                    // The root now stands for the empty key
+                    self.bump_version(idx);
+                }
+                root
+            }
+        };
+
+        let mut count = 0;
+        if let Some(val) = target.take_stored_value() {
+            cb(&key, val);
+            self.len -= 1;
+            count += 1;
+        }
         let key_len = key.len();
-        if key_len == 0 {
-            self.get_node_mut(self.root_idx).set_val(val);
-            return;
+        let stack = children
+            .into_iter()
+            .map(|(radix, child_idx)| (child_idx, key_len, radix))
+            .collect();
+        count += self.free_subtree_observed(&mut key, stack, &mut cb);
+
+        if let Some((parent_idx, _)) = parent {
+            self.try_recompress(parent_idx);
         }
-        let mut idx = self.root_idx;
-        let mut cursor = 0;
+        count
+    }
 
-        loop {
-            let Some(child_idx) = self.find(idx, key[cursor]) else {
-                self.create_node_with_val(idx, key[cursor], val, &key[(cursor + 1)..]);
-                return;
-            };
-            idx = child_idx;
-            cursor += 1;
-            let node_comparaison = self.get_node(idx).compare_compression_key(&key[cursor..]);
-            let common_len = match node_comparaison {
-                CompResult::Final => {
-                    self.get_node_mut(idx).set_val(val);
-                    return;
-                }
-                CompResult::Path => {
-                    cursor += self.get_node(idx).compression.len();
-                    continue;
-                }
-                CompResult::Partial(common_len) => common_len,
-            };
+    /// Deletes the keys under a prefix for which a predicate returns `true`.
+    ///
+    /// Unlike `deln`, which frees the whole subtree, only the matching entries are
+    /// removed. The subtree is walked once in post-order, so every node is cleaned
+    /// up (freed or recompressed) after its children, in the same pass.
+    /// With the `ttl` feature, expired entries are not passed to the predicate.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to match.
+    /// * `pred` - Called with the full key and the value of each entry, deletes it on `true`.
+    ///
+    /// # Returns
+    ///
+    /// The number of deleted entries.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"session:1"), Bytes::from_static(b"revoked"));
+    /// tree.set(Bytes::from_static(b"session:2"), Bytes::from_static(b"active"));
+    ///
+    /// let count = tree.deln_where(Bytes::from_static(b"session:"), |_, val| val == "revoked");
+    /// assert_eq!(count, 1);
+    /// assert_eq!(tree.getn(Bytes::from_static(b"session:")).len(), 1);
+    /// ```
+    pub fn deln_where<F>(&mut self, prefix: Bytes, mut pred: F) -> usize
+    where
+        F: FnMut(&[u8], &V) -> bool,
+    {
+        #[cfg(feature = "ttl")]
+        let now = self.now;
+        self.prune_where(&prefix, |key, node| {
+            #[cfg(feature = "ttl")]
+            let val = node.get_value(now);
+            #[cfg(not(feature = "ttl"))]
+            let val = node.get_value();
+            val.is_some_and(|val| pred(key, val))
+        })
+    }
 
-            // Split: node compression only partially matches the key
-            let key_rest = &key[cursor..];
-            let val_on_intermediate = common_len == key_rest.len();
-
-            // Extract old state and configure intermediate in one pass
-            let (old_compression, old_val, old_childs) = {
-                let node = self.get_node_mut(idx);
-                let old_compression = std::mem::take(&mut node.compression);
-                let old_val = node.val.take();
-                let old_childs = std::mem::take(&mut node.childs);
-
-                node.compression = SmallVec::from_slice(&old_compression[..common_len]);
-                if val_on_intermediate {
-                    node.val = Some(val.clone());
+    /// Removes the values under a prefix for which `remove` returns `true`, in one post-order pass
+    ///
+    /// `remove` is only called on nodes holding a value (with `ttl`, possibly
+    /// expired). Every node is freed or recompressed after its children.
+    fn prune_where<F>(&mut self, prefix: &[u8], mut remove: F) -> usize
+    where
+        F: FnMut(&[u8], &Node<N, V>) -> bool,
+    {
+        let Some(target) = self.find_prefix_target(prefix) else {
+            return 0;
+        };
+
+        let mut count = 0;
+        let mut key = target.key_path;
+        let mut stack = vec![PruneFrame::Exit {
+            idx: target.idx,
+            parent: target.parent,
+            key_len: key.len(),
+        }];
+        self.push_prune_children(target.idx, key.len(), &mut stack);
+
+        while let Some(frame) = stack.pop() {
+            match frame {
+                PruneFrame::Enter {
+                    idx,
+                    parent,
+                    parent_len,
+                } => {
+                    key.truncate(parent_len);
+                    key.push(parent.1);
+                    key.extend_from_slice(&self.get_node(idx).compression);
+                    stack.push(PruneFrame::Exit {
+                        idx,
+                        parent: Some(parent),
+                        key_len: key.len(),
+                    });
+                    self.push_prune_children(idx, key.len(), &mut stack);
                 }
+                PruneFrame::Exit {
+                    idx,
+                    parent,
+                    key_len,
+                } => {
+                    key.truncate(key_len);
+                    let node = self.get_node(idx);
+                    if node.val.is_some() && remove(&key, node) {
+                        let node = self.get_node_mut(idx);
+                        node.val = None;
+                        #[cfg(feature = "metadata")]
+                        {
+                            node.meta = 0;
+                        }
+                        if let Some(lru) = self.lru.as_mut() {
+                            lru.remove(&key);
+                        }
+                        self.len -= 1;
+                        count += 1;
+                    }
 
-                (old_compression, old_val, old_childs)
-            };
+                    // Children are already cleaned up, only this node is left
+                    let node = self.get_node(idx);
+                    let is_dead_leaf = node.val.is_none() && node.childs.is_empty();
+                    match parent {
+                        Some((parent_idx, radix)) if is_dead_leaf => {
+                            self.map.remove(idx as usize);
+                            self.remove_child(parent_idx, radix);
+                            // The target's parent is outside the walk
+                            if idx == target.idx {
+                                self.try_recompress(parent_idx);
+                            }
+                        }
+                        _ => self.try_recompress(idx),
+                    }
+                }
+            }
+        }
 
-            // Create a node for the old content
-            let old_radix = old_compression[common_len];
-            let old_child = Node {
-                compression: SmallVec::from_slice(&old_compression[common_len + 1..]),
-                val: old_val,
-                childs: old_childs,
-            };
-            let old_child_idx = self.insert(old_child);
-            self.get_node_mut(idx).childs.push(old_radix, old_child_idx);
+        count
+    }
 
-            // If the value doesn't go on the intermediate node, create a new leaf
-            if !val_on_intermediate {
-                let new_radix = key_rest[common_len];
-                let new_compression = &key_rest[common_len + 1..];
-                self.create_node_with_val(idx, new_radix, val, new_compression);
-            }
+    /// Keeps only the entries for which a predicate returns `true`.
+    ///
+    /// This is `deln_where` over the whole tree with the predicate negated: the
+    /// tree is walked once in post-order and cleaned up in the same pass, so no
+    /// key has to be collected first and the tree stays compact afterwards.
+    /// With the `ttl` feature, expired entries are not passed to the predicate.
+    ///
+    /// # Arguments
+    ///
+    /// * `keep` - Called with the full key and the value of each entry, deletes it on `false`.
+    ///
+    /// # Returns
+    ///
+    /// The number of deleted entries.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"rule:a"), Bytes::from_static(b"on"));
+    /// tree.set(Bytes::from_static(b"rule:b"), Bytes::from_static(b"off"));
+    ///
+    /// assert_eq!(tree.retain(|_, val| val == "on"), 1);
+    /// assert_eq!(tree.len(), 1);
+    /// ```
+    pub fn retain<F>(&mut self, mut keep: F) -> usize
+    where
+        F: FnMut(&[u8], &V) -> bool,
+    {
+        self.deln_where(Bytes::new(), |key, val| !keep(key, val))
+    }
 
-            return;
+    /// Removes the values under a prefix but keeps every node in place.
+    ///
+    /// Unlike `deln`, no node is freed and nothing is recompressed, so setting
+    /// the same keys again reuses the existing nodes without any split. Until
+    /// then, the subtree holds value-less nodes that `verify_invariants` rejects;
+    /// keys that are not set again can be reclaimed with `compact`.
+    /// With the `ttl` feature, expired values are cleared too but not counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to match.
+    ///
+    /// # Returns
+    ///
+    /// The number of cleared entries.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"slot:1"), Bytes::from_static(b"a"));
+    /// tree.set(Bytes::from_static(b"slot:2"), Bytes::from_static(b"b"));
+    ///
+    /// assert_eq!(tree.clear_prefix_values(Bytes::from_static(b"slot:")), 2);
+    /// // Repopulating reuses the same nodes
+    /// tree.set(Bytes::from_static(b"slot:1"), Bytes::from_static(b"c"));
+    /// ```
+    pub fn clear_prefix_values(&mut self, prefix: Bytes) -> usize {
+        if let Some(lru) = self.lru.as_mut() {
+            lru.remove_prefix(&prefix);
         }
-    }
-
-    #[cfg(feature = "ttl")]
-    fn create_node_with_val(
-        &mut self,
-        idx: u32,
-        radix: u8,
-        val: Bytes,
-        compression: &[u8],
-        ttl: u64,
-    ) {
-        let (is_full, huge_child_idx) = {
-            let father_node = self.get_node(idx);
-            (
-                father_node.childs.is_full(),
-                father_node.get_huge_childs_idx(),
-            )
+        let Some(target) = self.find_prefix_target(&prefix) else {
+            return 0;
         };
-        let new_leaf = Node::new_leaf(compression, val, ttl);
-        let inserted_idx = self.insert(new_leaf);
-        match (is_full, huge_child_idx) {
-            (false, _) => self.get_node_mut(idx).childs.push(radix, inserted_idx),
-            (true, None) => {
-                let new_child_idx = self.intiate_new_huge_child(radix, inserted_idx);
-                self.get_node_mut(idx).childs.set_new_childs(new_child_idx);
+
+        let mut count = 0;
+        let mut stack = vec![target.idx];
+        while let Some(idx) = stack.pop() {
+            self.iter_all_children(idx, |_, child_idx| stack.push(child_idx));
+            #[cfg(feature = "ttl")]
+            let now = self.now;
+            let node = self.get_node_mut(idx);
+            #[cfg(feature = "ttl")]
+            let is_live = node.get_value(now).is_some();
+            #[cfg(not(feature = "ttl"))]
+            let is_live = node.get_value().is_some();
+            if is_live {
+                count += 1;
             }
-            (true, Some(huge_idx)) => {
-                self.child_list
-                    .get_mut(huge_idx as usize)
-                    .expect("if key exist childs should too")
-                    .push(radix, inserted_idx);
+            let had_value = node.val.take().is_some();
+            #[cfg(feature = "metadata")]
+            {
+                node.meta = 0;
             }
+            self.len -= usize::from(had_value);
         }
+
+        count
     }
 
-    #[cfg(not(feature = "ttl"))]
-    fn create_node_with_val(&mut self, idx: u32, radix: u8, val: Bytes, compression: &[u8]) {
-        let (is_full, huge_child_idx) = {
-            let father_node = self.get_node(idx);
-            (
-                father_node.childs.is_full(),
-                father_node.get_huge_childs_idx(),
-            )
-        };
-        let new_leaf = Node::new_leaf(compression, val);
-        let inserted_idx = self.insert(new_leaf);
-        match (is_full, huge_child_idx) {
-            (false, _) => self.get_node_mut(idx).childs.push(radix, inserted_idx),
-            (true, None) => {
-                let new_child_idx = self.intiate_new_huge_child(radix, inserted_idx);
-                self.get_node_mut(idx).childs.set_new_childs(new_child_idx);
-            }
-            (true, Some(huge_idx)) => {
-                self.child_list
-                    .get_mut(huge_idx as usize)
-                    .expect("if key exist childs should too")
-                    .push(radix, inserted_idx);
-            }
-        }
+    /// Schedules the children of a node whose key is `key[..key_len]` for `deln_where`
+    fn push_prune_children(&self, idx: u32, key_len: usize, stack: &mut Vec<PruneFrame>) {
+        self.iter_all_children(idx, |radix, child_idx| {
+            stack.push(PruneFrame::Enter {
+                idx: child_idx,
+                parent: (idx, radix),
+                parent_len: key_len,
+            });
+        });
     }
 
-    /// Deletes a key from the tree and returns its value.
+    /// Removes all keys that start with the given prefix and returns them.
     ///
-    /// Returns `Some(value)` if the key existed, or `None` if it didn't.
-    /// The tree automatically recompresses paths after deletion.
+    /// This is `getn` followed by `deln` in a single descent: the matching entries
+    /// are collected, then the subtree is freed and the parent recompressed.
+    /// With the `ttl` feature, expired entries are freed but not returned.
     ///
     /// # Arguments
     ///
-    /// * `key` - The key to delete. Must be valid ASCII.
+    /// * `prefix` - The prefix to match.
     ///
     /// # Example
     ///
@@ -704,86 +2789,38 @@ impl OxidArt {
     /// use bytes::Bytes;
     ///
     /// let mut tree = OxidArt::new();
-    /// tree.set(Bytes::from_static(b"key"), Bytes::from_static(b"value"));
+    /// tree.set(Bytes::from_static(b"job:1"), Bytes::from_static(b"a"));
+    /// tree.set(Bytes::from_static(b"job:2"), Bytes::from_static(b"b"));
     ///
-    /// let deleted = tree.del(Bytes::from_static(b"key"));
-    /// assert_eq!(deleted, Some(Bytes::from_static(b"value")));
-    ///
-    /// // Key no longer exists
-    /// assert_eq!(tree.get(Bytes::from_static(b"key")), None);
+    /// let jobs = tree.drain_prefix(Bytes::from_static(b"job:"));
+    /// assert_eq!(jobs.len(), 2);
+    /// assert!(tree.getn(Bytes::from_static(b"job:")).is_empty());
     /// ```
-    pub fn del(&mut self, key: Bytes) -> Option<Bytes> {
-        debug_assert!(key.is_ascii(), "key must be ASCII");
-        let key_len = key.len();
-        if key_len == 0 {
-            let old_val = self.get_node_mut(self.root_idx).val.take();
-            self.try_recompress(self.root_idx);
-            #[cfg(feature = "ttl")]
-            return old_val.map(|(v, _)| v);
-            #[cfg(not(feature = "ttl"))]
-            return old_val;
+    pub fn drain_prefix(&mut self, prefix: Bytes) -> Vec<(Bytes, V)> {
+        if let Some(lru) = self.lru.as_mut() {
+            lru.remove_prefix(&prefix);
         }
-
-        // Traverse like get, keeping track of the immediate parent
-        let mut parent_idx = self.root_idx;
-        let mut parent_radix = key[0];
-        let mut idx = self.find(parent_idx, parent_radix)?;
-        let mut cursor = 1;
-
-        let target_idx = loop {
-            let node = self.try_get_node(idx)?;
-            match node.compare_compression_key(&key[cursor..]) {
-                CompResult::Final => break idx,
-                CompResult::Partial(_) => return None,
-                CompResult::Path => {
-                    cursor += node.compression.len();
-                }
-            }
-
-            // Continue traversal
-            parent_idx = idx;
-            parent_radix = key[cursor];
-            idx = self.find(idx, parent_radix)?;
-            cursor += 1;
-        };
-
-        // Check if the node has children
-        let has_children = {
-            let node = self.get_node(target_idx);
-            !node.childs.is_empty() || node.childs.get_next_idx().is_some()
+        let mut results = Vec::new();
+        let Some(target) = self.find_prefix_target(&prefix) else {
+            return results;
         };
-
-        if has_children {
-            // Node with children: keep the node, just remove the value
-            let old_val = self.get_node_mut(target_idx).val.take()?;
-            self.try_recompress(target_idx);
-            #[cfg(feature = "ttl")]
-            return Some(old_val.0);
-            #[cfg(not(feature = "ttl"))]
-            Some(old_val)
-        } else {
-            // Node without children (leaf): completely remove from the slab
-            let node = self.map.remove(target_idx as usize);
-            let old_val = node.val?;
-            self.remove_child(parent_idx, parent_radix);
-            if parent_idx != self.root_idx {
-                self.try_recompress(parent_idx);
-            }
-            #[cfg(feature = "ttl")]
-            return Some(old_val.0);
-            #[cfg(not(feature = "ttl"))]
-            Some(old_val)
-        }
+        self.collect_all_from(target.idx, target.key_path.clone(), &mut results);
+        self.detach_prefix_target(target);
+        results
     }
 
-    /// Deletes all keys that start with the given prefix.
+    /// Removes every entry whose key starts with the given prefix and returns them as a new tree.
     ///
-    /// Returns the number of key-value pairs that were deleted.
-    /// If the prefix is empty, all entries are deleted.
+    /// Like `BTreeMap::split_off`, but prefix-based: the entries keep their full keys in
+    /// the returned tree. The subtree nodes are moved to the new tree's slab as they are,
+    /// without re-inserting each key, the new root taking the prefix node's full key as
+    /// compression. With the `ttl` feature, expirations are kept and the new tree starts
+    /// at the same `now`. The returned tree has no entry limit, even if `self` has one.
+    /// If the prefix is empty, everything is moved and `self` is left empty.
     ///
     /// # Arguments
     ///
-    /// * `prefix` - The prefix to match. Must be valid ASCII.
+    /// * `prefix` - The prefix to match.
     ///
     /// # Example
     ///
@@ -793,56 +2830,132 @@ impl OxidArt {
     ///
     /// let mut tree = OxidArt::new();
     /// tree.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
-    /// tree.set(Bytes::from_static(b"user:2"), Bytes::from_static(b"bob"));
     /// tree.set(Bytes::from_static(b"post:1"), Bytes::from_static(b"hello"));
     ///
-    /// // Delete all user entries
-    /// let count = tree.deln(Bytes::from_static(b"user:"));
-    /// assert_eq!(count, 2);
-    ///
-    /// // Only post entries remain
-    /// assert_eq!(tree.getn(Bytes::from_static(b"")).len(), 1);
+    /// let mut users = tree.split_off_prefix(Bytes::from_static(b"user:"));
+    /// assert_eq!(users.get(Bytes::from_static(b"user:1")), Some(Bytes::from_static(b"alice")));
+    /// assert_eq!(tree.get(Bytes::from_static(b"user:1")), None);
     /// ```
-    pub fn deln(&mut self, prefix: Bytes) -> usize {
-        debug_assert!(prefix.is_ascii(), "prefix must be ASCII");
-        let prefix_len = prefix.len();
+    pub fn split_off_prefix(&mut self, prefix: Bytes) -> Self {
+        if let Some(lru) = self.lru.as_mut() {
+            lru.remove_prefix(&prefix);
+        }
+
+        let mut moved = Self::default();
+        #[cfg(feature = "ttl")]
+        {
+            moved.now = self.now;
+        }
+        let Some(target) = self.find_prefix_target(&prefix) else {
+            return moved;
+        };
+
+        let Some((parent_idx, parent_radix)) = target.parent else {
+            // Everything moves: hand over the whole slabs
+            core::mem::swap(&mut self.map, &mut moved.map);
+            core::mem::swap(&mut self.versions, &mut moved.versions);
+            core::mem::swap(&mut self.fresh_version, &mut moved.fresh_version);
+            core::mem::swap(&mut self.root_idx, &mut moved.root_idx);
+            core::mem::swap(&mut self.len, &mut moved.len);
+            return moved;
+        };
+
+        self.remove_child(parent_idx, parent_radix);
+        let new_root = self.move_subtree_into(target.idx, &mut moved);
+        moved.map.remove(moved.root_idx as usize);
+        moved.root_idx = new_root;
+        moved.get_node_mut(new_root).compression = SmallVec::from_slice(&target.key_path);
 
-        if prefix_len == 0 {
-            // Delete everything from root (keep root node, clear its content)
-            let root = self.get_node_mut(self.root_idx);
-            let had_val = root.val.take().is_some();
-            let childs_to_free: Vec<u32> = self.collect_child_indices(self.root_idx);
+        self.try_recompress(parent_idx);
+        moved
+    }
 
-            // Clear children of root (note: root's huge_childs not freed, negligible)
-            self.get_node_mut(self.root_idx).childs = Childs::default();
+    /// Moves the subtree rooted at `idx` out of `self` into `other`, returning its new index.
+    ///
+    /// Nodes keep their compression and value; child links are rebuilt in `other`.
+    fn move_subtree_into(&mut self, idx: u32, other: &mut Self) -> u32 {
+        let children = self.all_children(idx);
+        let mut node = self.map.remove(idx as usize);
+        node.childs = Childs::default();
+        if node.val.is_some() {
+            self.len -= 1;
+            other.len += 1;
+        }
 
-            let freed = self.free_subtree_iterative(childs_to_free);
-            return freed + if had_val { 1 } else { 0 };
+        let new_idx = other.insert(node);
+        for (radix, child_idx) in children {
+            let new_child_idx = self.move_subtree_into(child_idx, other);
+            other.attach_child(new_idx, radix, new_child_idx);
         }
+        new_idx
+    }
 
-        // Traverse like del
-        let mut parent_idx = self.root_idx;
-        let mut parent_radix = prefix[0];
-        let Some(mut idx) = self.find(parent_idx, parent_radix) else {
-            return 0;
-        };
-        let mut cursor = 1;
+    /// Finds the node holding every key that starts with the given prefix.
+    ///
+    /// The prefix may end within the node compression, in which case the whole
+    /// node still matches. An empty prefix targets the root.
+    fn find_prefix_target(&self, prefix: &[u8]) -> Option<PrefixTarget> {
+        self.find_prefix_target_from(prefix, self.root_idx, 0, None, |_, _, _| {})
+    }
 
-        let target_idx = loop {
-            let Some(node) = self.try_get_node(idx) else {
-                return 0;
-            };
+    /// Like `find_prefix_target`, but resuming from the deepest node the previous
+    /// descent of `descent` entered with a prefix shared by `prefix`
+    fn find_prefix_target_resumed<'p>(
+        &self,
+        prefix: &'p [u8],
+        descent: &mut DescentCursor<'p>,
+    ) -> Option<PrefixTarget> {
+        let shared = descent
+            .prev
+            .iter()
+            .zip(prefix)
+            .take_while(|(a, b)| a == b)
+            .count();
+        descent.prev = prefix;
+        let path = &mut descent.path;
+        while path.last().is_some_and(|&(_, cursor, _)| cursor > shared) {
+            path.pop();
+        }
+        // Re-recorded by `on_enter` when the descent resumes from it
+        let (idx, cursor, parent) = path.pop().unwrap_or((self.root_idx, 0, None));
+
+        self.find_prefix_target_from(prefix, idx, cursor, parent, |i, c, p| {
+            path.push((i, c, p));
+        })
+    }
+
+    /// Like `find_prefix_target`, but resuming the descent at node `idx`, entered with
+    /// `prefix[..cursor]` matched through `parent`
+    ///
+    /// `on_enter` is called with `(idx, cursor, parent)` for every node entered, so
+    /// that a later descent sharing `prefix[..cursor]` can resume from there.
+    fn find_prefix_target_from<F>(
+        &self,
+        prefix: &[u8],
+        mut idx: u32,
+        mut cursor: usize,
+        mut parent: Option<(u32, u8)>,
+        mut on_enter: F,
+    ) -> Option<PrefixTarget>
+    where
+        F: FnMut(u32, usize, Option<(u32, u8)>),
+    {
+        let prefix_len = prefix.len();
+
+        loop {
+            let node = self.try_get_node(idx)?;
+            on_enter(idx, cursor, parent);
 
             match node.compare_compression_key(&prefix[cursor..]) {
-                CompResult::Final => break idx,
+                CompResult::Final => break,
                 CompResult::Partial(common_len) => {
                     // Does the prefix end within the compression?
                     let prefix_rest_len = prefix_len - cursor;
                     if common_len == prefix_rest_len {
-                        break idx;
+                        break;
                     }
-                    // Divergence, nothing to delete
-                    return 0;
+                    // Divergence, nothing matches
+                    return None;
                 }
                 CompResult::Path => {
                     cursor += node.compression.len();
@@ -850,27 +2963,52 @@ impl OxidArt {
             }
 
             // Continue traversal
-            parent_idx = idx;
-            parent_radix = prefix[cursor];
-            let Some(child_idx) = self.find(idx, parent_radix) else {
-                return 0;
-            };
-            idx = child_idx;
+            let radix = prefix[cursor];
+            parent = Some((idx, radix));
+            idx = self.find(idx, radix)?;
             cursor += 1;
+        }
+
+        let mut key_path = prefix[..cursor].to_vec();
+        key_path.extend_from_slice(&self.get_node(idx).compression);
+        Some(PrefixTarget {
+            idx,
+            parent,
+            key_path,
+        })
+    }
+
+    /// Cuts a prefix subtree from its parent and frees it, returns the number of deleted values
+    fn detach_prefix_target(&mut self, target: PrefixTarget) -> usize {
+        let Some((parent_idx, parent_radix)) = target.parent else {
+            return self.clear_root();
         };
 
         // Cut the link from parent
         self.remove_child(parent_idx, parent_radix);
 
         // Free the entire subtree (iterative DFS)
-        let count = self.free_subtree_iterative(vec![target_idx]);
+        let count = self.free_subtree_iterative(vec![target.idx]);
 
-        // Recompression of parent (except root since get doesn't handle root with compression)
-        if parent_idx != self.root_idx {
-            self.try_recompress(parent_idx);
+        self.try_recompress(parent_idx);
+        count
+    }
+
+    /// Deletes everything from root (keep root node, clear its content), returns the number of deleted values
+    fn clear_root(&mut self) -> usize {
+        let childs_to_free: Vec<u32> = self.collect_child_indices(self.root_idx);
+
+        // Reset root, including its children
+        let root = core::mem::take(self.get_node_mut(self.root_idx));
+        if !root.compression.is_empty() {
+            // The root now stands for the empty key
+            self.bump_version(self.root_idx);
         }
 
-        count
+        let freed = self.free_subtree_iterative(childs_to_free);
+        let root_count = usize::from(root.val.is_some());
+        self.len -= root_count;
+        freed + root_count
     }
 
     /// Collects all child indices of a node
@@ -884,14 +3022,6 @@ impl OxidArt {
             indices.push(child_idx);
         }
 
-        if let Some(huge_idx) = node.childs.get_next_idx()
-            && let Some(huge_childs) = self.child_list.get(huge_idx as usize)
-        {
-            for (_, child_idx) in huge_childs.iter() {
-                indices.push(child_idx);
-            }
-        }
-
         indices
     }
 
@@ -902,21 +3032,14 @@ impl OxidArt {
 
         while let Some(node_idx) = stack.pop() {
             // Collect children before removing the node
-            let (children, has_val, huge_child_idx) = {
+            let (children, has_val) = {
                 let Some(node) = self.try_get_node(node_idx) else {
                     continue;
                 };
 
-                let mut children: Vec<u32> = node.childs.iter().map(|(_, idx)| idx).collect();
-
-                let huge_idx = node.childs.get_next_idx();
-                if let Some(hi) = huge_idx
-                    && let Some(huge_childs) = self.child_list.get(hi as usize)
-                {
-                    children.extend(huge_childs.iter().map(|(_, idx)| idx));
-                }
+                let children: Vec<u32> = node.childs.iter().map(|(_, idx)| idx).collect();
 
-                (children, node.val.is_some(), huge_idx)
+                (children, node.val.is_some())
             };
 
             // Add children to the stack
@@ -927,69 +3050,277 @@ impl OxidArt {
                 count += 1;
             }
 
-            // Remove huge_childs if present
-            if let Some(huge_idx) = huge_child_idx {
-                self.child_list.remove(huge_idx as usize);
-            }
-
             // Remove the node from the slab
             self.map.remove(node_idx as usize);
         }
 
+        self.len -= count;
         count
     }
 
-    /// If the node has exactly 1 child and no value, absorb the child
-    fn try_recompress(&mut self, node_idx: u32) {
-        let node = self.get_node(node_idx);
-        if node.val.is_some() {
-            return;
+    /// Frees detached subtrees like `free_subtree_iterative`, rebuilding the keys
+    /// in `key` to pass every freed value to `cb`
+    ///
+    /// Each stack entry is a node with the key length of its parent and its radix.
+    fn free_subtree_observed<F>(
+        &mut self,
+        key: &mut Vec<u8>,
+        mut stack: Vec<(u32, usize, u8)>,
+        cb: &mut F,
+    ) -> usize
+    where
+        F: FnMut(&[u8], V),
+    {
+        let mut count = 0;
+
+        while let Some((node_idx, parent_len, radix)) = stack.pop() {
+            let mut node = self.map.remove(node_idx as usize);
+            key.truncate(parent_len);
+            key.push(radix);
+            key.extend_from_slice(&node.compression);
+
+            if let Some(val) = node.take_stored_value() {
+                cb(key, val);
+                count += 1;
+            }
+
+            let key_len = key.len();
+            stack.extend(
+                node.childs
+                    .iter()
+                    .map(|(radix, child_idx)| (child_idx, key_len, radix)),
+            );
         }
 
-        let Some((child_radix, child_idx)) = node.childs.get_single_child() else {
-            return;
-        };
+        self.len -= count;
+        count
+    }
 
-        // Absorb the child: compression = current + radix + child.compression
-        let child = self.map.remove(child_idx as usize);
-        let node = self.get_node_mut(node_idx);
+    /// Re-runs recompression over the whole tree.
+    ///
+    /// Deletions only recompress the nodes they touch, so value-less nodes can be
+    /// left behind with a single child (or none at all). This does a post-order
+    /// pass that removes dead value-less leaves and absorbs every collapsible
+    /// chain, so that [`verify_invariants`](Self::verify_invariants) holds afterward.
+    ///
+    /// This is O(n); run it during read-heavy periods rather than on the hot path.
+    pub fn compact(&mut self) {
+        // Pre-order with parent links, processed in reverse so children come first
+        let mut order: Vec<(u32, u32, u8)> = Vec::new();
+        let mut stack = vec![self.root_idx];
+        while let Some(node_idx) = stack.pop() {
+            self.iter_all_children(node_idx, |radix, child_idx| {
+                order.push((child_idx, node_idx, radix));
+                stack.push(child_idx);
+            });
+        }
 
-        node.compression.push(child_radix);
-        node.compression.extend_from_slice(&child.compression);
-        node.val = child.val;
-        node.childs = child.childs;
+        for &(node_idx, parent_idx, radix) in order.iter().rev() {
+            let is_dead = {
+                let node = self.get_node(node_idx);
+                node.val.is_none() && self.collect_child_indices(node_idx).is_empty()
+            };
+            if is_dead {
+                self.remove_child(parent_idx, radix);
+                self.free_subtree_iterative(vec![node_idx]);
+            } else {
+                self.try_recompress(node_idx);
+            }
+        }
+        self.try_recompress(self.root_idx);
     }
 
-    fn remove_child(&mut self, parent_idx: u32, radix: u8) {
-        let parent = self.get_node_mut(parent_idx);
-        if parent.childs.remove(radix).is_some() {
-            return;
+    /// Moves every node into a slab of exactly the needed size.
+    ///
+    /// Slabs never give memory back: after large deletions, the freed slots stay
+    /// allocated for later inserts. This rebuilds the slab, and the handle
+    /// versions alongside it, compactly, keeping the slot order, and rewrites
+    /// every child index along the way. Entries are unchanged,
+    /// but every `Handle` becomes stale, since slots are renumbered.
+    ///
+    /// This is O(n) and reallocates the whole tree; call `compact` first to also
+    /// drop the nodes that deletions left behind.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// for i in 0..10_000 {
+    ///     tree.set(Bytes::from(format!("key:{i}")), Bytes::from_static(b"v"));
+    /// }
+    /// tree.deln(Bytes::from_static(b"key:"));
+    ///
+    /// tree.shrink_to_fit();
+    /// assert!(tree.memory_stats().slab_capacity < 10_000);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        // New index of every used slot, slots keeping their relative order
+        let mut node_remap = vec![u32::MAX; self.map.capacity()];
+        for (new_idx, (old_idx, _)) in self.map.iter().enumerate() {
+            node_remap[old_idx] = new_idx as u32;
+        }
+        let node_idx = |idx: u32| node_remap[idx as usize];
+
+        let mut map = Slab::with_capacity(self.map.len());
+        for (_, mut node) in core::mem::take(&mut self.map) {
+            node.childs.remap(node_idx);
+            map.insert(node);
+        }
+        self.root_idx = node_idx(self.root_idx);
+        self.map = map;
+
+        // A version no handle was given before, for every slot ever handed out
+        let version = self
+            .versions
+            .iter()
+            .copied()
+            .fold(self.fresh_version, u32::max)
+            .wrapping_add(1);
+        self.versions.fill(version);
+        self.versions.truncate(self.map.len());
+        self.versions.shrink_to_fit();
+        // Slots past the truncation start over from it when handed out again
+        self.fresh_version = version;
+    }
+
+    /// While the node has exactly 1 child and no value, absorb the child
+    ///
+    /// The whole chain of value-less single-child descendants is collapsed in one
+    /// call. Operations keep such nodes from existing below a valid node, so this
+    /// usually stops after one level, but a chain never survives a delete.
+    /// An emptied root also drops its compression, so that it doesn't keep
+    /// pointing to a key that no longer exists.
+    fn try_recompress(&mut self, node_idx: u32) {
+        loop {
+            let node = self.get_node(node_idx);
+            if node.val.is_some() {
+                return;
+            }
+
+            if node_idx == self.root_idx && node.childs.is_empty() {
+                if !node.compression.is_empty() {
+                    self.get_node_mut(node_idx).compression.clear();
+                    // The root now stands for the empty key
+                    self.bump_version(node_idx);
+                }
+                return;
+            }
+
+            let Some((child_radix, child_idx)) = self.get_single_child(node_idx) else {
+                return;
+            };
+
+            // Absorb the child: compression = current + radix + child.compression
+            let child = self.map.remove(child_idx as usize);
+            let node = self.get_node_mut(node_idx);
+
+            node.compression.push(child_radix);
+            node.compression.extend_from_slice(&child.compression);
+            node.val = child.val;
+            node.childs = child.childs;
+            #[cfg(feature = "metadata")]
+            {
+                node.meta = child.meta;
+            }
+            // The node now stands for a longer key
+            self.bump_version(node_idx);
         }
-        // Otherwise it's in huge_childs
-        if let Some(huge_idx) = parent.childs.get_next_idx() {
-            self.child_list
-                .get_mut(huge_idx as usize)
-                .expect("huge_childs should exist")
-                .remove(radix);
+    }
+
+    /// Returns (radix, idx) if the node has exactly 1 child
+    fn get_single_child(&self, node_idx: u32) -> Option<(u8, u32)> {
+        let mut children = self.get_node(node_idx).childs.iter();
+        let single = children.next()?;
+        if children.next().is_some() {
+            return None;
         }
+        Some(single)
+    }
+
+    /// Unlinks a child
+    ///
+    /// The remaining children are demoted to a smaller tier once few enough are
+    /// left, so a node shrinking after many deletions gives its block back.
+    fn remove_child(&mut self, parent_idx: u32, radix: u8) {
+        self.get_node_mut(parent_idx).childs.remove(radix);
     }
 }
 
 #[cfg(feature = "ttl")]
-#[derive(Default)]
-struct Node {
-    childs: Childs,
+#[derive(Clone)]
+struct Node<const N: usize, V> {
+    childs: Childs<N>,
     compression: SmallVec<[u8; 8]>,
-    val: Option<(Bytes, u64)>,
+    val: Option<(V, u64)>,
+    /// User metadata of the value, `0` when unset or without value
+    #[cfg(feature = "metadata")]
+    meta: u64,
 }
 
 #[cfg(not(feature = "ttl"))]
-#[derive(Default)]
-struct Node {
+#[derive(Clone)]
+struct Node<const N: usize, V> {
     compression: SmallVec<[u8; 23]>,
-    val: Option<Bytes>,
-    childs: Childs,
+    val: Option<V>,
+    childs: Childs<N>,
+    /// User metadata of the value, `0` when unset or without value
+    #[cfg(feature = "metadata")]
+    meta: u64,
+}
+
+impl<const N: usize, V> Default for Node<N, V> {
+    fn default() -> Self {
+        Self {
+            childs: Childs::default(),
+            compression: SmallVec::new(),
+            val: None,
+            #[cfg(feature = "metadata")]
+            meta: 0,
+        }
+    }
+}
+
+/// Subtree matching a prefix, with the link to cut to detach it
+struct PrefixTarget {
+    idx: u32,
+    /// Parent index and radix leading to the target, `None` for the root
+    parent: Option<(u32, u8)>,
+    /// Full key of the target node (includes its compression)
+    key_path: Vec<u8>,
+}
+
+/// Pending step of the post-order walk of `deln_where`
+enum PruneFrame {
+    /// Node to visit, reached through `parent` from the key `key[..parent_len]`
+    Enter {
+        idx: u32,
+        parent: (u32, u8),
+        parent_len: usize,
+    },
+    /// Node whose children are done, its key being `key[..key_len]`
+    Exit {
+        idx: u32,
+        parent: Option<(u32, u8)>,
+        key_len: usize,
+    },
+}
+
+/// Node entered during a prefix descent: index, prefix bytes matched before its
+/// compression, and the parent link leading to it
+type DescentPoint = (u32, usize, Option<(u32, u8)>);
+
+/// State carried from one prefix descent to the next, see `find_prefix_target_resumed`
+#[derive(Default)]
+struct DescentCursor<'p> {
+    /// Nodes entered by the previous descent, shallowest first
+    path: Vec<DescentPoint>,
+    /// Prefix of the previous descent
+    prev: &'p [u8],
 }
+
 enum CompResult {
     ///The compresion completely part of the key need travel for more
     Path,
@@ -997,9 +3328,9 @@ enum CompResult {
     Partial(usize),
 }
 
-impl Node {
+impl<const N: usize, V> Node<N, V> {
     fn compare_compression_key(&self, key_rest: &[u8]) -> CompResult {
-        use std::cmp::Ordering::*;
+        use core::cmp::Ordering::*;
         match self.compression.len().cmp(&key_rest.len()) {
             Equal => {
                 let common_len = self.get_common_len(key_rest);
@@ -1032,18 +3363,18 @@ impl Node {
         len
     }
     #[cfg(feature = "ttl")]
-    fn set_val(&mut self, val: Bytes, ttl: u64) {
+    fn set_val(&mut self, val: V, ttl: u64) {
         self.val = Some((val, ttl));
     }
 
     #[cfg(not(feature = "ttl"))]
-    fn set_val(&mut self, val: Bytes) {
+    fn set_val(&mut self, val: V) {
         self.val = Some(val);
     }
 
     /// Returns the value if present and not expired
     #[cfg(feature = "ttl")]
-    fn get_value(&self, now: u64) -> Option<&Bytes> {
+    fn get_value(&self, now: u64) -> Option<&V> {
         let (bytes, ttl) = self.val.as_ref()?;
         if *ttl != NO_EXPIRY && *ttl < now {
             return None;
@@ -1052,10 +3383,47 @@ impl Node {
     }
 
     #[cfg(not(feature = "ttl"))]
-    fn get_value(&self) -> Option<&Bytes> {
+    fn get_value(&self) -> Option<&V> {
+        self.val.as_ref()
+    }
+
+    /// Returns a mutable reference to the value if present and not expired
+    #[cfg(feature = "ttl")]
+    fn get_value_mut(&mut self, now: u64) -> Option<&mut V> {
+        let (bytes, ttl) = self.val.as_mut()?;
+        if *ttl != NO_EXPIRY && *ttl < now {
+            return None;
+        }
+        Some(bytes)
+    }
+
+    #[cfg(not(feature = "ttl"))]
+    fn get_value_mut(&mut self) -> Option<&mut V> {
+        self.val.as_mut()
+    }
+
+    /// Returns the stored value, ignoring expiration
+    #[cfg(feature = "ttl")]
+    fn stored_value(&self) -> Option<&V> {
+        self.val.as_ref().map(|(bytes, _)| bytes)
+    }
+
+    #[cfg(not(feature = "ttl"))]
+    fn stored_value(&self) -> Option<&V> {
         self.val.as_ref()
     }
 
+    /// Moves the stored value out, ignoring expiration
+    #[cfg(feature = "ttl")]
+    fn take_stored_value(&mut self) -> Option<V> {
+        self.val.take().map(|(bytes, _)| bytes)
+    }
+
+    #[cfg(not(feature = "ttl"))]
+    fn take_stored_value(&mut self) -> Option<V> {
+        self.val.take()
+    }
+
     /// Check if value exists and is expired
     #[cfg(feature = "ttl")]
     fn is_expired(&self, now: u64) -> bool {
@@ -1066,25 +3434,25 @@ impl Node {
         }
     }
 
-    fn get_huge_childs_idx(&self) -> Option<u32> {
-        self.childs.get_next_idx()
-    }
-
     #[cfg(feature = "ttl")]
-    fn new_leaf(compression: &[u8], val: Bytes, ttl: u64) -> Self {
+    fn new_leaf(compression: &[u8], val: V, ttl: u64) -> Self {
         Node {
             compression: SmallVec::from_slice(compression),
             val: Some((val, ttl)),
             childs: Childs::default(),
+            #[cfg(feature = "metadata")]
+            meta: 0,
         }
     }
 
     #[cfg(not(feature = "ttl"))]
-    fn new_leaf(compression: &[u8], val: Bytes) -> Self {
+    fn new_leaf(compression: &[u8], val: V) -> Self {
         Node {
             compression: SmallVec::from_slice(compression),
             val: Some(val),
             childs: Childs::default(),
+            #[cfg(feature = "metadata")]
+            meta: 0,
         }
     }
 }
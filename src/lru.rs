@@ -0,0 +1,131 @@
+//! Opt-in LRU eviction, enabled with `with_max_entries`.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::ops::Bound;
+
+use bytes::Bytes;
+
+use crate::OxidArtN;
+
+/// Access recency of the stored keys.
+///
+/// Tracking is keyed by the full key rather than by node index, since splits
+/// and recompression move values between nodes.
+#[derive(Clone)]
+pub(crate) struct Lru {
+    max_entries: usize,
+    clock: u64,
+    /// Last access tick of every tracked key
+    ticks: BTreeMap<Bytes, u64>,
+    /// Tracked keys by access tick, least recently used first
+    order: BTreeMap<u64, Bytes>,
+}
+
+impl Lru {
+    pub(crate) fn new(max_entries: usize) -> Self {
+        Self {
+            max_entries,
+            clock: 0,
+            ticks: BTreeMap::new(),
+            order: BTreeMap::new(),
+        }
+    }
+
+    /// Marks `key` as the most recently used, tracking it if needed
+    pub(crate) fn touch(&mut self, key: &Bytes) {
+        self.clock += 1;
+        if let Some(tick) = self.ticks.get_mut(key.as_ref()) {
+            let key = self.order.remove(tick).expect("tracked key has a tick");
+            *tick = self.clock;
+            self.order.insert(self.clock, key);
+        } else {
+            self.ticks.insert(key.clone(), self.clock);
+            self.order.insert(self.clock, key.clone());
+        }
+    }
+
+    pub(crate) fn remove(&mut self, key: &[u8]) {
+        if let Some(tick) = self.ticks.remove(key) {
+            self.order.remove(&tick);
+        }
+    }
+
+    pub(crate) fn remove_prefix(&mut self, prefix: &[u8]) {
+        let keys: Vec<Bytes> = self
+            .ticks
+            .range::<[u8], _>((Bound::Included(prefix), Bound::Unbounded))
+            .map(|(key, _)| key)
+            .take_while(|key| key.starts_with(prefix))
+            .cloned()
+            .collect();
+        for key in keys {
+            self.remove(&key);
+        }
+    }
+
+    /// Untracks every key, keeping the limit
+    pub(crate) fn clear(&mut self) {
+        self.ticks.clear();
+        self.order.clear();
+    }
+
+    /// Untracks and returns the least recently used key while over the limit
+    fn pop_over_limit(&mut self) -> Option<Bytes> {
+        if self.ticks.len() <= self.max_entries {
+            return None;
+        }
+        let (_, key) = self.order.pop_first()?;
+        self.ticks.remove(&key);
+        Some(key)
+    }
+}
+
+impl<const N: usize> OxidArtN<N> {
+    /// Creates a new empty tree holding at most `max_entries` entries.
+    ///
+    /// Every `get` hit and every write (`set`, `set_ttl`, `try_set`, `append`)
+    /// marks the key as the most recently used. When a write makes the tree
+    /// exceed `max_entries`, the least recently used entries are deleted.
+    ///
+    /// With the `ttl` feature, expired entries keep counting against the limit
+    /// until they are cleaned up (by `get`, `del` or eviction).
+    ///
+    /// # Arguments
+    ///
+    /// * `max_entries` - Maximum number of entries kept in the tree.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut cache = OxidArt::with_max_entries(2);
+    /// cache.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+    /// cache.set(Bytes::from_static(b"b"), Bytes::from_static(b"2"));
+    /// cache.get(Bytes::from_static(b"a"));
+    ///
+    /// // "b" is the least recently used entry
+    /// cache.set(Bytes::from_static(b"c"), Bytes::from_static(b"3"));
+    /// assert_eq!(cache.get(Bytes::from_static(b"b")), None);
+    /// ```
+    pub fn with_max_entries(max_entries: usize) -> Self {
+        let mut art = Self::new();
+        art.lru = Some(Lru::new(max_entries));
+        art
+    }
+}
+
+impl<const N: usize, V: Clone> OxidArtN<N, V> {
+    /// Marks a written key as the most recently used, then evicts over the limit
+    pub(crate) fn lru_record_write(&mut self, key: &Bytes) {
+        let Some(lru) = self.lru.as_mut() else {
+            return;
+        };
+        lru.touch(key);
+        while let Some(victim) = self.lru.as_mut().and_then(Lru::pop_over_limit) {
+            self.del(victim);
+        }
+    }
+}
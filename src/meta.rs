@@ -0,0 +1,78 @@
+//! Per-entry user metadata, enabled with the `metadata` feature.
+//!
+//! Every node carries a `u64` next to its value, value-less structural nodes
+//! included, whether or not metadata is used. With `OxidArt` a node is two
+//! cache lines: the children (inline, or a pointer to a grown tier) padded to
+//! the first one, then compression and value filling the second. These 8 bytes
+//! need a third line, growing each node from 128 to 192 bytes. This is why the
+//! feature is opt-in.
+
+use bytes::Bytes;
+
+use crate::OxidArtN;
+
+impl<const N: usize, V: Clone> OxidArtN<N, V> {
+    /// Attaches metadata to an existing entry.
+    ///
+    /// The metadata stays attached when the value is overwritten (by `set`,
+    /// `set_ttl`, `append`...), and is dropped with the entry when it is deleted
+    /// or expires. A new entry starts with metadata `0`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the entry.
+    /// * `meta` - The metadata, such as flags, a version counter or a type tag.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the key doesn't exist (or is expired with `ttl` feature), and
+    /// nothing is stored.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"doc"), Bytes::from_static(b"v1"));
+    /// assert!(tree.set_meta(Bytes::from_static(b"doc"), 7));
+    ///
+    /// tree.set(Bytes::from_static(b"doc"), Bytes::from_static(b"v2"));
+    /// assert_eq!(tree.get_meta(Bytes::from_static(b"doc")), Some(7));
+    /// ```
+    pub fn set_meta(&mut self, key: Bytes, meta: u64) -> bool {
+        let Some(idx) = self.find_key_node(&key) else {
+            return false;
+        };
+        #[cfg(feature = "ttl")]
+        let now = self.now;
+        let node = self.get_node_mut(idx);
+        #[cfg(feature = "ttl")]
+        let has_value = node.get_value(now).is_some();
+        #[cfg(not(feature = "ttl"))]
+        let has_value = node.get_value().is_some();
+        if has_value {
+            node.meta = meta;
+        }
+        has_value
+    }
+
+    /// Returns the metadata of an entry, `0` if none was set.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the entry.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the key doesn't exist (or is expired with `ttl` feature).
+    pub fn get_meta(&self, key: Bytes) -> Option<u64> {
+        let node = self.get_node(self.find_key_node(&key)?);
+        #[cfg(feature = "ttl")]
+        let has_value = node.get_value(self.now).is_some();
+        #[cfg(not(feature = "ttl"))]
+        let has_value = node.get_value().is_some();
+        has_value.then_some(node.meta)
+    }
+}
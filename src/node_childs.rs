@@ -1,129 +1,423 @@
+use alloc::boxed::Box;
+
 use arrayvec::ArrayVec;
 
-pub(crate) const CHILDS_SIZE: usize = 10;
-const ASCII_MAX_CHAR: usize = 127;
-pub(crate) const HUGE_CHILDS_SIZE: usize = ASCII_MAX_CHAR - CHILDS_SIZE;
+/// Default capacity of the inline tier, the one every node starts in.
+pub const CHILDS_SIZE: usize = 4;
+const RADIX_COUNT: usize = u8::MAX as usize + 1;
+/// Capacity of the `Node16` tier.
+const NODE16_SIZE: usize = 16;
+/// Capacity of the `Node48` tier.
+const NODE48_SIZE: usize = 48;
+/// Empty slot of `Childs48::slots`.
+const NO_SLOT: u8 = u8::MAX;
+/// Empty slot of `Childs256::idxs`.
+const NO_CHILD: u32 = u32::MAX;
 
-#[repr(C, align(64))]
-pub(crate) struct Childs {
-    idxs: ArrayVec<u32, CHILDS_SIZE>,
-    radixs: ArrayVec<u8, CHILDS_SIZE>,
-    maybe_next_childs_idx: u32,
+/// Children of a node, in one of four tiers grown as children are added.
+///
+/// A node starts with up to `N` children inline, then the whole set moves to
+/// a sorted 16-entry tier, a 48-entry tier located through a radix index, and
+/// finally one slot per byte value, like the Node4/Node16/Node48/Node256 of
+/// ART. Tiers no larger than `N` are skipped. Removals demote the set once it
+/// fills three quarters of the smaller tier or less, so that one push and one
+/// remove around a boundary do not convert it back and forth. The grown tiers
+/// are boxed, so sparse nodes stay small.
+///
+/// Aligned to a cache line, so that every node of the slab starts on one and
+/// its children never straddle two lines.
+#[derive(Clone)]
+#[repr(align(64))]
+pub(crate) enum Childs<const N: usize> {
+    /// Up to `N` children in the node itself, sorted by radix
+    Inline(InlineChilds<N>),
+    /// Up to 16 children sorted by radix
+    Node16(Box<Childs16>),
+    /// Up to 48 children, located through a 256-byte radix index
+    Node48(Box<Childs48>),
+    /// One child slot per radix value
+    Node256(Box<Childs256>),
 }
+
 pub(crate) trait ChildAble {
     fn find(&self, radix: u8) -> Option<u32>;
     fn push(&mut self, radix: u8, idx: u32);
     fn remove(&mut self, radix: u8) -> Option<u32>;
     fn is_empty(&self) -> bool;
-    fn iter(&self) -> impl Iterator<Item = (u8, u32)>;
+    /// Children in ascending radix order, as `(radix, idx)`
+    fn iter(&self) -> impl DoubleEndedIterator<Item = (u8, u32)>;
+
+    /// Child with the smallest radix, as `(radix, idx)`
+    fn min_child(&self) -> Option<(u8, u32)> {
+        self.iter().next()
+    }
+
+    /// Child with the largest radix, as `(radix, idx)`
+    fn max_child(&self) -> Option<(u8, u32)> {
+        self.iter().next_back()
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct InlineChilds<const N: usize> {
+    idxs: ArrayVec<u32, N>,
+    /// Radixes in ascending order
+    radixs: ArrayVec<u8, N>,
+}
+
+#[derive(Clone)]
+pub(crate) struct Childs16 {
+    /// Radixes in ascending order, the lanes past `len` are unused
+    radixs: [u8; NODE16_SIZE],
+    idxs: [u32; NODE16_SIZE],
+    len: u8,
 }
 
-impl Default for Childs {
+#[derive(Clone)]
+pub(crate) struct Childs48 {
+    /// Position in `idxs` of the child of every radix, `NO_SLOT` if absent
+    slots: [u8; RADIX_COUNT],
+    idxs: ArrayVec<u32, NODE48_SIZE>,
+    /// Radix of every entry of `idxs`, to fix its slot when it is moved
+    radixs: ArrayVec<u8, NODE48_SIZE>,
+}
+
+#[derive(Clone)]
+pub(crate) struct Childs256 {
+    /// Child of every radix, `NO_CHILD` if absent
+    idxs: [u32; RADIX_COUNT],
+    len: usize,
+}
+
+/// Position of `radix` among the first `len` of 16 radixes, compared all at once.
+///
+/// The radixes are compared to `radix` in a single SSE2 instruction (always
+/// available on x86_64), the matching lanes being read from the byte mask.
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+fn simd_position(lanes: &[u8; 16], len: usize, radix: u8) -> Option<usize> {
+    use core::arch::x86_64::{
+        __m128i, _mm_cmpeq_epi8, _mm_loadu_si128, _mm_movemask_epi8, _mm_set1_epi8,
+    };
+
+    // SAFETY: SSE2 is enabled on every x86_64 target, `lanes` is 16 readable
+    // bytes and loadu has no alignment requirement
+    let mask = unsafe {
+        let lanes = _mm_loadu_si128(lanes.as_ptr().cast::<__m128i>());
+        _mm_movemask_epi8(_mm_cmpeq_epi8(lanes, _mm_set1_epi8(radix as i8))) as u32
+    };
+    // Unused lanes past the length may match
+    let mask = mask & ((1 << len) - 1);
+    (mask != 0).then(|| mask.trailing_zeros() as usize)
+}
+
+impl<const N: usize> Default for Childs<N> {
     fn default() -> Self {
-        Self {
-            maybe_next_childs_idx: u32::MAX,
-            idxs: ArrayVec::default(),
-            radixs: ArrayVec::default(),
-        }
+        Self::Inline(InlineChilds {
+            idxs: ArrayVec::new_const(),
+            radixs: ArrayVec::new_const(),
+        })
     }
 }
-impl ChildAble for Childs {
+
+impl<const N: usize> InlineChilds<N> {
+    /// Position of `radix` among the inline radixes
+    fn position(&self, radix: u8) -> Option<usize> {
+        self.radixs.iter().position(|&c| c == radix)
+    }
+
     fn find(&self, radix: u8) -> Option<u32> {
-        self.radixs
+        self.position(radix).map(|i| self.idxs[i])
+    }
+
+    /// Inserts a new radix, keeping the radixes sorted
+    fn push(&mut self, radix: u8, idx: u32) {
+        let pos = self.radixs.partition_point(|&r| r < radix);
+        debug_assert!(
+            self.radixs.get(pos) != Some(&radix),
+            "radix already has a child"
+        );
+        self.idxs.insert(pos, idx);
+        self.radixs.insert(pos, radix);
+    }
+
+    fn remove(&mut self, radix: u8) -> Option<u32> {
+        let pos = self.position(radix)?;
+        self.radixs.remove(pos);
+        Some(self.idxs.remove(pos))
+    }
+
+    fn iter(&self) -> impl DoubleEndedIterator<Item = (u8, u32)> {
+        self.radixs.iter().copied().zip(self.idxs.iter().copied())
+    }
+}
+
+impl Childs16 {
+    /// Position of `radix` among the sorted radixes
+    #[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+    fn position(&self, radix: u8) -> Option<usize> {
+        self.radixs[..self.len as usize].binary_search(&radix).ok()
+    }
+
+    /// Position of `radix` among the sorted radixes, see `simd_position`
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    fn position(&self, radix: u8) -> Option<usize> {
+        simd_position(&self.radixs, self.len as usize, radix)
+    }
+
+    fn find(&self, radix: u8) -> Option<u32> {
+        self.position(radix).map(|i| self.idxs[i])
+    }
+
+    /// Inserts a new radix, keeping the radixes sorted
+    fn push(&mut self, radix: u8, idx: u32) {
+        let len = self.len as usize;
+        let pos = self.radixs[..len].partition_point(|&r| r < radix);
+        debug_assert!(
+            self.radixs[..len].get(pos) != Some(&radix),
+            "radix already has a child"
+        );
+        self.radixs.copy_within(pos..len, pos + 1);
+        self.idxs.copy_within(pos..len, pos + 1);
+        self.radixs[pos] = radix;
+        self.idxs[pos] = idx;
+        self.len += 1;
+    }
+
+    fn remove(&mut self, radix: u8) -> Option<u32> {
+        let pos = self.position(radix)?;
+        let len = self.len as usize;
+        let removed = self.idxs[pos];
+        self.radixs.copy_within(pos + 1..len, pos);
+        self.idxs.copy_within(pos + 1..len, pos);
+        self.len -= 1;
+        Some(removed)
+    }
+
+    fn iter(&self) -> impl DoubleEndedIterator<Item = (u8, u32)> {
+        let len = self.len as usize;
+        self.radixs[..len]
             .iter()
-            .position(|&c| c == radix)
-            .map(|i| self.idxs[i])
+            .copied()
+            .zip(self.idxs[..len].iter().copied())
+    }
+}
+
+impl Childs48 {
+    fn find(&self, radix: u8) -> Option<u32> {
+        let slot = self.slots[radix as usize];
+        (slot != NO_SLOT).then(|| self.idxs[slot as usize])
     }
 
     fn push(&mut self, radix: u8, idx: u32) {
-        assert!(!self.is_full());
+        debug_assert!(
+            self.slots[radix as usize] == NO_SLOT,
+            "radix already has a child"
+        );
+        self.slots[radix as usize] = self.idxs.len() as u8;
         self.idxs.push(idx);
         self.radixs.push(radix);
     }
 
     fn remove(&mut self, radix: u8) -> Option<u32> {
-        let pos = self.radixs.iter().position(|&c| c == radix)?;
-        self.radixs.swap_remove(pos);
-        Some(self.idxs.swap_remove(pos))
+        let slot = self.slots[radix as usize];
+        if slot == NO_SLOT {
+            return None;
+        }
+        self.slots[radix as usize] = NO_SLOT;
+        self.radixs.swap_remove(slot as usize);
+        let removed = self.idxs.swap_remove(slot as usize);
+        // The last entry moved into the freed position
+        if let Some(&moved) = self.radixs.get(slot as usize) {
+            self.slots[moved as usize] = slot;
+        }
+        Some(removed)
     }
 
-    fn is_empty(&self) -> bool {
-        self.idxs.is_empty()
+    fn iter(&self) -> impl DoubleEndedIterator<Item = (u8, u32)> {
+        (0..=u8::MAX).filter_map(|radix| self.find(radix).map(|idx| (radix, idx)))
     }
+}
 
-    fn iter(&self) -> impl Iterator<Item = (u8, u32)> {
-        self.radixs.iter().copied().zip(self.idxs.iter().copied())
+impl Childs256 {
+    fn find(&self, radix: u8) -> Option<u32> {
+        let idx = self.idxs[radix as usize];
+        (idx != NO_CHILD).then_some(idx)
+    }
+
+    fn push(&mut self, radix: u8, idx: u32) {
+        let slot = &mut self.idxs[radix as usize];
+        debug_assert!(*slot == NO_CHILD, "radix already has a child");
+        *slot = idx;
+        self.len += 1;
+    }
+
+    fn remove(&mut self, radix: u8) -> Option<u32> {
+        let slot = &mut self.idxs[radix as usize];
+        if *slot == NO_CHILD {
+            return None;
+        }
+        self.len -= 1;
+        Some(core::mem::replace(slot, NO_CHILD))
+    }
+
+    fn iter(&self) -> impl DoubleEndedIterator<Item = (u8, u32)> {
+        (0..=u8::MAX)
+            .zip(self.idxs.iter().copied())
+            .filter(|&(_, idx)| idx != NO_CHILD)
     }
 }
 
-impl Childs {
-    pub(crate) fn get_next_idx(&self) -> Option<u32> {
-        if self.maybe_next_childs_idx == u32::MAX {
-            None
+impl<const N: usize> Childs<N> {
+    /// Smallest tier holding `len` children, filled with `children`
+    fn with_children(len: usize, children: impl Iterator<Item = (u8, u32)>) -> Self {
+        let mut childs = if len <= N {
+            Self::default()
+        } else if len <= NODE16_SIZE {
+            Self::Node16(Box::new(Childs16 {
+                radixs: [0; NODE16_SIZE],
+                idxs: [0; NODE16_SIZE],
+                len: 0,
+            }))
+        } else if len <= NODE48_SIZE {
+            Self::Node48(Box::new(Childs48 {
+                slots: [NO_SLOT; RADIX_COUNT],
+                idxs: ArrayVec::new_const(),
+                radixs: ArrayVec::new_const(),
+            }))
         } else {
-            Some(self.maybe_next_childs_idx)
+            Self::Node256(Box::new(Childs256 {
+                idxs: [NO_CHILD; RADIX_COUNT],
+                len: 0,
+            }))
+        };
+        for (radix, idx) in children {
+            childs.push_in_place(radix, idx);
         }
+        childs
     }
-    pub(crate) fn is_full(&self) -> bool {
-        self.idxs.is_full()
+
+    /// Number of children the current tier holds
+    fn capacity(&self) -> usize {
+        match self {
+            Self::Inline(_) => N,
+            Self::Node16(_) => NODE16_SIZE,
+            Self::Node48(_) => NODE48_SIZE,
+            Self::Node256(_) => RADIX_COUNT,
+        }
     }
-    pub(crate) fn set_new_childs(&mut self, idx: u32) {
-        assert!(self.maybe_next_childs_idx == u32::MAX);
-        self.maybe_next_childs_idx = idx
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::Inline(inline) => inline.idxs.len(),
+            Self::Node16(node) => node.len as usize,
+            Self::Node48(node) => node.idxs.len(),
+            Self::Node256(node) => node.len,
+        }
     }
-    /// Retourne (radix, idx) si exactement 1 enfant et pas de huge_childs
-    pub(crate) fn get_single_child(&self) -> Option<(u8, u32)> {
-        if self.idxs.len() == 1 && self.maybe_next_childs_idx == u32::MAX {
-            Some((self.radixs[0], self.idxs[0]))
-        } else {
-            None
+
+    /// Whether the children are still stored in the node itself
+    pub(crate) fn is_inline(&self) -> bool {
+        matches!(self, Self::Inline(_))
+    }
+
+    /// Heap bytes of the boxed tiers, outside of the node
+    pub(crate) fn heap_bytes(&self) -> usize {
+        match self {
+            Self::Inline(_) => 0,
+            Self::Node16(_) => core::mem::size_of::<Childs16>(),
+            Self::Node48(_) => core::mem::size_of::<Childs48>(),
+            Self::Node256(_) => core::mem::size_of::<Childs256>(),
         }
     }
-}
 
-#[derive(Clone, Copy)]
-#[repr(C, packed)]
-struct HugeChildRegistry {
-    radix: u8,
-    idx: u32,
-}
+    /// Rewrites the child indices
+    pub(crate) fn remap(&mut self, node_idx: impl Fn(u32) -> u32) {
+        let idxs = match self {
+            Self::Inline(inline) => inline.idxs.as_mut_slice(),
+            Self::Node16(node) => &mut node.idxs[..node.len as usize],
+            Self::Node48(node) => node.idxs.as_mut_slice(),
+            Self::Node256(node) => node.idxs.as_mut_slice(),
+        };
+        for idx in idxs.iter_mut().filter(|idx| **idx != NO_CHILD) {
+            *idx = node_idx(*idx);
+        }
+    }
 
-#[repr(align(64))]
-#[derive(Default)]
-pub(crate) struct HugeChilds {
-    entries: ArrayVec<HugeChildRegistry, HUGE_CHILDS_SIZE>,
-}
+    /// Pushes into the current tier, which must have room
+    fn push_in_place(&mut self, radix: u8, idx: u32) {
+        match self {
+            Self::Inline(inline) => inline.push(radix, idx),
+            Self::Node16(node) => node.push(radix, idx),
+            Self::Node48(node) => node.push(radix, idx),
+            Self::Node256(node) => node.push(radix, idx),
+        }
+    }
 
-impl HugeChilds {
-    pub(crate) fn new(radix: u8, idx: u32) -> Self {
-        let mut entries = ArrayVec::new_const();
-        entries.push(HugeChildRegistry { radix, idx });
-        Self { entries }
+    /// Moves the children to a smaller tier once few enough are left
+    fn shrink(&mut self) {
+        let capacity = self.capacity();
+        let smaller = [N, NODE16_SIZE, NODE48_SIZE]
+            .into_iter()
+            .filter(|&tier| tier < capacity)
+            .max();
+        let len = self.len();
+        if let Some(smaller) = smaller
+            && !self.is_inline()
+            && len <= smaller * 3 / 4
+        {
+            *self = Self::with_children(len, self.iter());
+        }
     }
 }
 
-impl ChildAble for HugeChilds {
+impl<const N: usize> ChildAble for Childs<N> {
     fn find(&self, radix: u8) -> Option<u32> {
-        self.entries
-            .iter()
-            .find(|e| e.radix == radix)
-            .map(|e| e.idx)
+        match self {
+            Self::Inline(inline) => inline.find(radix),
+            Self::Node16(node) => node.find(radix),
+            Self::Node48(node) => node.find(radix),
+            Self::Node256(node) => node.find(radix),
+        }
     }
 
+    /// Adds a child for a radix that has none, promoting a full tier first
     fn push(&mut self, radix: u8, idx: u32) {
-        self.entries.push(HugeChildRegistry { radix, idx });
+        let len = self.len();
+        assert!(len < RADIX_COUNT, "every radix already has a child");
+        if len == self.capacity() {
+            *self = Self::with_children(len + 1, self.iter());
+        }
+        self.push_in_place(radix, idx);
     }
 
     fn remove(&mut self, radix: u8) -> Option<u32> {
-        let pos = self.entries.iter().position(|e| e.radix == radix)?;
-        Some(self.entries.swap_remove(pos).idx)
+        let removed = match self {
+            Self::Inline(inline) => inline.remove(radix),
+            Self::Node16(node) => node.remove(radix),
+            Self::Node48(node) => node.remove(radix),
+            Self::Node256(node) => node.remove(radix),
+        }?;
+        self.shrink();
+        Some(removed)
     }
 
     fn is_empty(&self) -> bool {
-        self.entries.is_empty()
+        self.len() == 0
     }
 
-    fn iter(&self) -> impl Iterator<Item = (u8, u32)> {
-        self.entries.iter().map(|e| (e.radix, e.idx))
+    /// Children in ascending radix order, whatever the tier
+    fn iter(&self) -> impl DoubleEndedIterator<Item = (u8, u32)> {
+        let (inline, node16, node48, node256) = match self {
+            Self::Inline(inline) => (Some(inline.iter()), None, None, None),
+            Self::Node16(node) => (None, Some(node.iter()), None, None),
+            Self::Node48(node) => (None, None, Some(node.iter()), None),
+            Self::Node256(node) => (None, None, None, Some(node.iter())),
+        };
+        inline
+            .into_iter()
+            .flatten()
+            .chain(node16.into_iter().flatten())
+            .chain(node48.into_iter().flatten())
+            .chain(node256.into_iter().flatten())
     }
 }
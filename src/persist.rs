@@ -0,0 +1,189 @@
+//! Binary snapshot of the tree, enabled with the `persist` feature.
+//!
+//! # Format
+//!
+//! All integers are little-endian. A snapshot starts with a header:
+//!
+//! | Field   | Size | Content |
+//! |---------|------|---------|
+//! | magic   | 4    | `b"OXAR"` |
+//! | version | 1    | [`FORMAT_VERSION`], bumped on any incompatible change |
+//! | flags   | 1    | bit 0 set if records carry an expiration |
+//! | now     | 8    | TTL clock of the tree, `0` without the `ttl` feature |
+//!
+//! Then one record per entry, in ascending key order:
+//!
+//! | Field  | Size  | Content |
+//! |--------|-------|---------|
+//! | tag    | 1     | `1` for an entry |
+//! | key    | 4 + n | `u32` length, then the key bytes |
+//! | value  | 4 + n | `u32` length, then the value bytes |
+//! | expiry | 8     | only with flag bit 0: expiration timestamp, `u64::MAX` for none |
+//!
+//! The stream ends with a single `0` tag, so a truncated snapshot is detected.
+//! Slab indices are never written: loading rebuilds the tree with `set`, so a
+//! snapshot does not depend on the node layout.
+
+use alloc::vec::Vec;
+use std::io::{self, Read, Write};
+
+use bytes::Bytes;
+
+use crate::OxidArtN;
+use crate::export::write_chunk;
+
+/// Version of the snapshot format written by `serialize`.
+pub const FORMAT_VERSION: u8 = 1;
+
+const MAGIC: &[u8; 4] = b"OXAR";
+const FLAG_EXPIRY: u8 = 1;
+const TAG_END: u8 = 0;
+const TAG_ENTRY: u8 = 1;
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn read_array<R: Read, const L: usize>(r: &mut R) -> io::Result<[u8; L]> {
+    let mut buf = [0; L];
+    r.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// Reads a chunk written by `write_chunk`, without trusting its length to pre-allocate
+fn read_chunk<R: Read>(r: &mut R) -> io::Result<Bytes> {
+    let len = u32::from_le_bytes(read_array(r)?) as u64;
+    let mut buf = Vec::new();
+    r.take(len).read_to_end(&mut buf)?;
+    if buf.len() as u64 != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(Bytes::from(buf))
+}
+
+impl<const N: usize> OxidArtN<N> {
+    /// Writes a snapshot of every entry to `w`, see the module documentation for the format.
+    ///
+    /// Entries are streamed in ascending key order, rebuilding keys in a single
+    /// scratch buffer. With the `ttl` feature, expired entries are skipped, and
+    /// the expiration of the others is written along with the current clock.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"key"), Bytes::from_static(b"value"));
+    ///
+    /// let mut file = std::fs::File::create("tree.snapshot")?;
+    /// tree.serialize(&mut file)?;
+    /// ```
+    pub fn serialize<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(MAGIC)?;
+        #[cfg(feature = "ttl")]
+        w.write_all(&[FORMAT_VERSION, FLAG_EXPIRY])?;
+        #[cfg(not(feature = "ttl"))]
+        w.write_all(&[FORMAT_VERSION, 0])?;
+        #[cfg(feature = "ttl")]
+        w.write_all(&self.now.to_le_bytes())?;
+        #[cfg(not(feature = "ttl"))]
+        w.write_all(&0u64.to_le_bytes())?;
+
+        // Pre-order over children by ascending radix, `(node, radix, parent key len)`
+        let mut key = Vec::new();
+        let mut stack = alloc::vec![(self.root_idx, None, 0)];
+        while let Some((idx, radix, parent_len)) = stack.pop() {
+            let node = self.get_node(idx);
+            key.truncate(parent_len);
+            key.extend(radix);
+            key.extend_from_slice(&node.compression);
+
+            #[cfg(feature = "ttl")]
+            if let Some(val) = node.get_value(self.now) {
+                let expiry = node.val.as_ref().map_or(crate::NO_EXPIRY, |(_, ttl)| *ttl);
+                w.write_all(&[TAG_ENTRY])?;
+                write_chunk(w, &key)?;
+                write_chunk(w, val)?;
+                w.write_all(&expiry.to_le_bytes())?;
+            }
+            #[cfg(not(feature = "ttl"))]
+            if let Some(val) = node.get_value() {
+                w.write_all(&[TAG_ENTRY])?;
+                write_chunk(w, &key)?;
+                write_chunk(w, val)?;
+            }
+
+            for (radix, child_idx) in self.children(idx).rev() {
+                stack.push((child_idx, Some(radix), key.len()));
+            }
+        }
+
+        w.write_all(&[TAG_END])
+    }
+
+    /// Loads a tree from a snapshot written by `serialize`.
+    ///
+    /// Every entry is inserted with `set`, so the tree is as compact as if it
+    /// had been built by hand. With the `ttl` feature, expirations and the clock
+    /// are restored (call `set_now` afterwards to move the clock to the present),
+    /// and without it they are ignored, every entry being loaded without expiry.
+    ///
+    /// # Errors
+    ///
+    /// Any I/O error from `r`, `UnexpectedEof` for a truncated snapshot, and
+    /// `InvalidData` for a bad magic, an unknown format version or tag.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    ///
+    /// let mut file = std::fs::File::open("tree.snapshot")?;
+    /// let mut tree = OxidArt::deserialize(&mut file)?;
+    /// tree.set_now(now_secs());
+    /// ```
+    pub fn deserialize<R: Read>(r: &mut R) -> io::Result<Self> {
+        if &read_array::<_, 4>(r)? != MAGIC {
+            return Err(invalid_data("not an oxidart snapshot"));
+        }
+        let [version, flags] = read_array(r)?;
+        if version != FORMAT_VERSION {
+            return Err(invalid_data("unsupported snapshot format version"));
+        }
+        let has_expiry = flags & FLAG_EXPIRY != 0;
+        let now = u64::from_le_bytes(read_array(r)?);
+
+        let mut art = Self::new();
+        #[cfg(feature = "ttl")]
+        art.set_now(now);
+        #[cfg(not(feature = "ttl"))]
+        let _ = now;
+
+        loop {
+            match read_array::<_, 1>(r)? {
+                [TAG_END] => return Ok(art),
+                [TAG_ENTRY] => {}
+                _ => return Err(invalid_data("unknown snapshot record tag")),
+            }
+            let key = read_chunk(r)?;
+            let val = read_chunk(r)?;
+            // `u64::MAX` is also the tree's own "no expiry" value
+            let expiry = if has_expiry {
+                u64::from_le_bytes(read_array(r)?)
+            } else {
+                u64::MAX
+            };
+
+            #[cfg(feature = "ttl")]
+            let result = art.set_internal(key, expiry, val);
+            #[cfg(not(feature = "ttl"))]
+            let result = {
+                let _ = expiry;
+                art.set_internal(key, val)
+            };
+            result.map_err(|_| invalid_data("snapshot entry rejected by the tree"))?;
+        }
+    }
+}
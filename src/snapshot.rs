@@ -0,0 +1,104 @@
+//! Point-in-time read-only copies of a tree.
+
+use alloc::vec::Vec;
+
+use bytes::Bytes;
+
+use crate::{CHILDS_SIZE, Iter, OxidArtN, PrefixIter};
+
+/// Immutable copy of a tree taken at one point in time, see [`OxidArtN::snapshot`].
+///
+/// The snapshot owns its nodes and borrows nothing from the live tree, so the
+/// tree can be written to while the snapshot is read. It answers the reads of
+/// the tree as it was when the snapshot was taken. With the `ttl` feature its
+/// clock is frozen at the tree's `now` of that moment: an entry live then stays
+/// live in the snapshot.
+#[derive(Clone)]
+pub struct Snapshot<const N: usize = CHILDS_SIZE, V = Bytes> {
+    art: OxidArtN<N, V>,
+}
+
+impl<const N: usize, V: Clone> OxidArtN<N, V> {
+    /// Takes a read-only copy of the current contents.
+    ///
+    /// This is a full copy of the node slabs: O(n) time and as much node memory
+    /// as the tree itself. `Bytes` values are not copied, only their refcount is
+    /// bumped. The recency tracking of `with_max_entries` and the value pool are
+    /// not carried over. Cheaper snapshots sharing the unchanged nodes can
+    /// replace this copy later without changing the `Snapshot` API.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"config:mode"), Bytes::from_static(b"fast"));
+    ///
+    /// let snapshot = tree.snapshot();
+    /// tree.set(Bytes::from_static(b"config:mode"), Bytes::from_static(b"safe"));
+    ///
+    /// assert_eq!(snapshot.get(Bytes::from_static(b"config:mode")), Some(Bytes::from_static(b"fast")));
+    /// ```
+    pub fn snapshot(&self) -> Snapshot<N, V> {
+        // Not a `clone`, which would copy the recency list and the pool too
+        let art = OxidArtN {
+            map: self.map.clone(),
+            versions: self.versions.clone(),
+            fresh_version: self.fresh_version,
+            #[cfg(feature = "ttl")]
+            now: self.now,
+            root_idx: self.root_idx,
+            len: self.len,
+            lru: None,
+            value_pool: None,
+            max_key_len: self.max_key_len,
+        };
+        Snapshot { art }
+    }
+}
+
+impl<const N: usize, V: Clone> Snapshot<N, V> {
+    /// Returns the value of `key` when the snapshot was taken, see `OxidArt::get`.
+    pub fn get(&self, key: Bytes) -> Option<V> {
+        self.art.get_slice(&key)
+    }
+
+    /// Returns `true` if `key` was stored when the snapshot was taken.
+    pub fn contains_key(&self, key: Bytes) -> bool {
+        self.art.contains_key(key)
+    }
+
+    /// Returns the entries whose key starts with `prefix`, see `OxidArt::getn`.
+    pub fn getn(&self, prefix: Bytes) -> Vec<(Bytes, V)> {
+        self.art.getn(prefix)
+    }
+
+    /// Lazy iterator over the entries whose key starts with `prefix`, see
+    /// `OxidArt::prefix_iter`.
+    pub fn prefix_iter(&self, prefix: Bytes) -> PrefixIter<'_, N, V> {
+        self.art.prefix_iter(prefix)
+    }
+
+    /// Lazy iterator over all entries in ascending key order, see `OxidArt::iter`.
+    pub fn iter(&self) -> Iter<'_, N, V> {
+        self.art.iter()
+    }
+
+    /// Number of entries when the snapshot was taken, see `OxidArt::len`.
+    pub fn len(&self) -> usize {
+        self.art.len()
+    }
+
+    /// Returns `true` if the tree was empty when the snapshot was taken.
+    pub fn is_empty(&self) -> bool {
+        self.art.is_empty()
+    }
+
+    /// Timestamp the snapshot was taken at, against which expirations are checked.
+    #[cfg(feature = "ttl")]
+    pub fn now(&self) -> u64 {
+        self.art.now
+    }
+}
@@ -0,0 +1,462 @@
+//! Read-only statistics about the tree contents.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use bytes::Bytes;
+
+use crate::{Node, OxidArtN};
+
+/// Upper bounds (inclusive) of the value-size histogram buckets, in bytes.
+///
+/// Values larger than the last bound fall into the final bucket.
+pub const VALUE_SIZE_BUCKETS: [usize; 3] = [64, 256, 1024];
+
+/// Logical size of the data held by the tree, for capacity planning.
+///
+/// Unlike structural metrics, these numbers describe what the user stored:
+/// full reconstructed key lengths and value lengths.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SizeReport {
+    /// Number of stored values.
+    pub value_count: usize,
+    /// Sum of the full key lengths of every stored value.
+    pub total_key_bytes: usize,
+    /// Sum of the lengths of every stored value.
+    pub total_value_bytes: usize,
+    /// Value-size histogram: `0-64`, `65-256`, `257-1024`, `>1024` bytes.
+    pub value_size_histogram: [usize; 4],
+}
+
+impl SizeReport {
+    fn record(&mut self, key_len: usize, val_len: usize) {
+        self.value_count += 1;
+        self.total_key_bytes += key_len;
+        self.total_value_bytes += val_len;
+        let bucket = VALUE_SIZE_BUCKETS
+            .iter()
+            .position(|&max| val_len <= max)
+            .unwrap_or(VALUE_SIZE_BUCKETS.len());
+        self.value_size_histogram[bucket] += 1;
+    }
+}
+
+/// Copy of the shape of one node, see [`OxidArtN::node_info`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NodeInfo {
+    /// Compressed bytes of the node, the end of its key after the radix leading to it.
+    pub compression: Vec<u8>,
+    /// Whether the node stores a value (with `ttl`, possibly expired).
+    pub has_value: bool,
+    /// Radixes of the children, in ascending order.
+    pub child_radixes: Vec<u8>,
+}
+
+/// Node-level fragmentation of the tree, see [`OxidArtN::fragmentation`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Fragmentation {
+    /// Value-less nodes other than the root with exactly one child, which
+    /// `compact` would merge into their child.
+    pub collapsible_nodes: usize,
+    /// Nodes without a value (with `ttl`, expired values still count as values).
+    pub valueless_nodes: usize,
+    /// Number of nodes, including the root.
+    pub total_nodes: usize,
+}
+
+/// Approximate memory held by the tree, see [`OxidArtN::memory_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// Number of nodes, including the root.
+    pub node_count: usize,
+    /// Number of nodes whose children outgrew the inline tier into a boxed block.
+    pub grown_child_nodes: usize,
+    /// Number of node slots allocated in the node slab, used or not.
+    pub slab_capacity: usize,
+    /// Bytes allocated by the node slab, `capacity * slot size`.
+    pub slab_bytes: usize,
+    /// Heap bytes of the boxed child blocks, outside of the slab.
+    pub child_heap_bytes: usize,
+    /// Sum of the lengths of every stored value (with `ttl`, expired ones included).
+    pub estimated_value_bytes: usize,
+    /// Sum of the compression lengths of every node, inline or not.
+    pub estimated_compression_bytes: usize,
+    /// Number of nodes whose compression outgrew its inline buffer.
+    pub spilled_compressions: usize,
+    /// Heap bytes allocated by the spilled compressions.
+    pub spilled_compression_bytes: usize,
+}
+
+impl MemoryStats {
+    /// Estimate of the total footprint: the slab, the boxed child blocks, the
+    /// spilled compressions and the values.
+    pub fn estimated_total_bytes(&self) -> usize {
+        self.slab_bytes
+            + self.child_heap_bytes
+            + self.spilled_compression_bytes
+            + self.estimated_value_bytes
+    }
+}
+
+impl<const N: usize> OxidArtN<N> {
+    /// Computes the logical size of all stored keys and values in a single DFS.
+    ///
+    /// Key lengths are reconstructed from the compression segments plus one
+    /// byte per edge along each root-to-node path, without building the keys.
+    /// With the `ttl` feature, expired entries that have not been cleaned up
+    /// yet are still counted since they still occupy memory.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    ///
+    /// let report = tree.size_report();
+    /// assert_eq!(report.total_key_bytes, 6);
+    /// assert_eq!(report.total_value_bytes, 5);
+    /// ```
+    pub fn size_report(&self) -> SizeReport {
+        let mut report = SizeReport::default();
+        let mut stack = vec![(self.root_idx, 0usize)];
+
+        while let Some((node_idx, parent_len)) = stack.pop() {
+            let Some(node) = self.try_get_node(node_idx) else {
+                continue;
+            };
+            let key_len = parent_len + node.compression.len();
+
+            if let Some(val) = node.stored_value() {
+                report.record(key_len, val.len());
+            }
+
+            self.iter_all_children(node_idx, |_, child_idx| {
+                stack.push((child_idx, key_len + 1));
+            });
+        }
+
+        report
+    }
+
+    /// Estimates the memory held by the tree, scanning the slab once.
+    ///
+    /// Slab figures count allocated capacity, so they include the slots freed
+    /// by deletions until `shrink_to_fit` is called. Compressions are stored
+    /// inline in the node up to a fixed size, only the longer ones cost a heap
+    /// allocation.
+    /// Value bytes are summed per key: `Bytes` shared between keys (value
+    /// interning, clones of one buffer) are counted once for every key, and the
+    /// rest of a buffer a value was sliced from is not counted. With the `ttl`
+    /// feature, expired values that have not been cleaned up yet are counted.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    ///
+    /// let stats = tree.memory_stats();
+    /// assert_eq!(stats.estimated_value_bytes, 5);
+    /// assert!(stats.slab_capacity >= 1024);
+    /// ```
+    pub fn memory_stats(&self) -> MemoryStats {
+        let mut stats = MemoryStats {
+            node_count: self.map.len(),
+            slab_capacity: self.map.capacity(),
+            slab_bytes: self.map.capacity() * core::mem::size_of::<Node<N, Bytes>>(),
+            ..MemoryStats::default()
+        };
+
+        for (_, node) in self.map.iter() {
+            if !node.childs.is_inline() {
+                stats.grown_child_nodes += 1;
+                stats.child_heap_bytes += node.childs.heap_bytes();
+            }
+            if let Some(val) = node.stored_value() {
+                stats.estimated_value_bytes += val.len();
+            }
+            stats.estimated_compression_bytes += node.compression.len();
+            if node.compression.spilled() {
+                stats.spilled_compressions += 1;
+                stats.spilled_compression_bytes += node.compression.capacity();
+            }
+        }
+
+        stats
+    }
+}
+
+impl<const N: usize, V: Clone> OxidArtN<N, V> {
+    /// Returns the number of nodes in the tree, including the root.
+    ///
+    /// This is the slab length, so it is O(1). Compared to `value_count`, it
+    /// tells how well paths are compressed: a tree with many more nodes than
+    /// values is fragmented, and `compact` may reclaim some of them.
+    pub fn node_count(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns the number of nodes on the longest path from the root to a leaf, the root included.
+    ///
+    /// An empty tree has depth `1`, the root alone. Each lookup visits at most
+    /// this many nodes, so together with `node_count` it shows how well keys
+    /// are compressed: a split adds a level, a recompression removes one.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"user"), Bytes::from_static(b"1"));
+    /// tree.set(Bytes::from_static(b"uso"), Bytes::from_static(b"2"));
+    ///
+    /// // Root, then "us", then "er" or "o"
+    /// assert_eq!(tree.node_count(), 4);
+    /// assert_eq!(tree.depth(), 3);
+    /// ```
+    pub fn depth(&self) -> usize {
+        let mut max_depth = 0;
+        let mut stack = vec![(self.root_idx, 1)];
+        while let Some((idx, depth)) = stack.pop() {
+            max_depth = max_depth.max(depth);
+            self.iter_all_children(idx, |_, child_idx| stack.push((child_idx, depth + 1)));
+        }
+        max_depth
+    }
+
+    /// Measures how many nodes could be reclaimed by `compact`, in a single DFS.
+    ///
+    /// Deletions only recompress the nodes they touch, so value-less nodes with a
+    /// single child can be left behind. Such a node costs a slab slot and a hop on
+    /// every lookup going through it: if `collapsible_nodes > 0`, calling `compact`
+    /// will help. The root is never collapsible, since it always stays in place.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    ///
+    /// let tree = OxidArt::new();
+    /// let report = tree.fragmentation();
+    /// assert_eq!(report.total_nodes, 1);
+    /// assert_eq!(report.collapsible_nodes, 0);
+    /// ```
+    pub fn fragmentation(&self) -> Fragmentation {
+        let mut report = Fragmentation::default();
+        let mut stack = vec![self.root_idx];
+
+        while let Some(node_idx) = stack.pop() {
+            report.total_nodes += 1;
+            if self.get_node(node_idx).stored_value().is_none() {
+                report.valueless_nodes += 1;
+                if node_idx != self.root_idx && self.get_single_child(node_idx).is_some() {
+                    report.collapsible_nodes += 1;
+                }
+            }
+            self.iter_all_children(node_idx, |_, child_idx| stack.push(child_idx));
+        }
+
+        report
+    }
+
+    /// Returns the number of stored key-value pairs, in O(1).
+    ///
+    /// The count is maintained by every write and deletion: overwriting a key
+    /// leaves it unchanged, and a value stored under the empty key counts like
+    /// any other. With the `ttl` feature, expired values that have not been
+    /// cleaned up yet are counted, since they still hold their node.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+    /// tree.set(Bytes::from_static(b"a"), Bytes::from_static(b"2"));
+    /// assert_eq!(tree.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the tree stores no value, see `len`.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of nodes holding a value.
+    ///
+    /// This scans every node, so it is O(n), and always equals `len`.
+    /// With the `ttl` feature, expired values that have not been cleaned up
+    /// yet are counted, since they still hold their node.
+    pub fn value_count(&self) -> usize {
+        self.map
+            .iter()
+            .filter(|(_, node)| node.stored_value().is_some())
+            .count()
+    }
+
+    /// Counts the entries whose key starts with the given prefix, without collecting them.
+    ///
+    /// Walks to the prefix node like `getn`, then counts the values of its
+    /// subtree with an iterative DFS: no key is rebuilt and nothing is cloned.
+    /// With the `ttl` feature, expired entries are not counted, so the empty
+    /// prefix gives `len` only once expired values are cleaned up.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to match, empty to count every entry.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    /// tree.set(Bytes::from_static(b"user:2"), Bytes::from_static(b"bob"));
+    /// tree.set(Bytes::from_static(b"post:1"), Bytes::from_static(b"hello"));
+    ///
+    /// assert_eq!(tree.prefix_count(Bytes::from_static(b"user:")), 2);
+    /// assert_eq!(tree.prefix_count(Bytes::new()), 3);
+    /// ```
+    pub fn prefix_count(&self, prefix: Bytes) -> usize {
+        let Some(target) = self.find_prefix_target(&prefix) else {
+            return 0;
+        };
+
+        let mut count = 0;
+        let mut stack = vec![target.idx];
+        while let Some(node_idx) = stack.pop() {
+            let node = self.get_node(node_idx);
+            #[cfg(feature = "ttl")]
+            let has_value = node.get_value(self.now).is_some();
+            #[cfg(not(feature = "ttl"))]
+            let has_value = node.get_value().is_some();
+            count += usize::from(has_value);
+            self.iter_all_children(node_idx, |_, child_idx| stack.push(child_idx));
+        }
+        count
+    }
+
+    /// Estimates the number of entries under a prefix by sampling random paths.
+    ///
+    /// Exact counting (`prefix_count`) visits the whole subtree; this instead runs `sample_budget`
+    /// random walks from the prefix node down to a leaf, choosing a child
+    /// uniformly at each step. Each value met on a walk counts for the product of
+    /// the branching factors above it, and the walks are averaged (Knuth's
+    /// estimator). The estimate is unbiased and exact on uniformly shaped
+    /// subtrees, but its variance grows with the irregularity of the subtree.
+    ///
+    /// The walks use a fixed seed, so the same tree always gives the same
+    /// estimate. With the `ttl` feature, expired entries are not counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix to estimate.
+    /// * `sample_budget` - Number of random walks, at least one is performed.
+    ///
+    /// # Returns
+    ///
+    /// The rounded estimate, `0` if no key starts with the prefix.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// for i in 0..1000 {
+    ///     tree.set(Bytes::from(format!("user:{i:03}")), Bytes::from_static(b"x"));
+    /// }
+    ///
+    /// assert_eq!(tree.estimate_prefix_count(Bytes::from_static(b"user:"), 16), 1000);
+    /// ```
+    pub fn estimate_prefix_count(&self, prefix: Bytes, sample_budget: usize) -> usize {
+        let Some(target) = self.find_prefix_target(&prefix) else {
+            return 0;
+        };
+
+        // xorshift64, enough to pick children without a dependency
+        let mut rng_state: u64 = 0x9E37_79B9_7F4A_7C15;
+        let mut next_random = move || {
+            rng_state ^= rng_state << 13;
+            rng_state ^= rng_state >> 7;
+            rng_state ^= rng_state << 17;
+            rng_state
+        };
+
+        let walks = sample_budget.max(1);
+        let mut total = 0.0f64;
+        for _ in 0..walks {
+            let mut idx = target.idx;
+            let mut weight = 1.0f64;
+            loop {
+                let node = self.get_node(idx);
+                #[cfg(feature = "ttl")]
+                let has_value = node.get_value(self.now).is_some();
+                #[cfg(not(feature = "ttl"))]
+                let has_value = node.get_value().is_some();
+                if has_value {
+                    total += weight;
+                }
+
+                let children = self.all_children(idx);
+                if children.is_empty() {
+                    break;
+                }
+                weight *= children.len() as f64;
+                idx = children[(next_random() % children.len() as u64) as usize].1;
+            }
+        }
+
+        // Rounds half up, the estimate is never negative (`f64::round` needs std)
+        (total / walks as f64 + 0.5) as usize
+    }
+
+    /// Returns the shape of the node whose full key is exactly `key`.
+    ///
+    /// The node may be a structural one without value. Keys ending inside a
+    /// compression do not reach a node and give `None`. Everything is copied
+    /// out, so the snapshot does not borrow the tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Full key of the node.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    /// tree.set(Bytes::from_static(b"user:2"), Bytes::from_static(b"bob"));
+    ///
+    /// let info = tree.node_info(Bytes::from_static(b"user:")).unwrap();
+    /// assert!(!info.has_value);
+    /// assert_eq!(info.child_radixes, b"12");
+    /// ```
+    pub fn node_info(&self, key: Bytes) -> Option<NodeInfo> {
+        let idx = self.find_key_node(&key)?;
+        let node = self.get_node(idx);
+        Some(NodeInfo {
+            compression: node.compression.to_vec(),
+            has_value: node.stored_value().is_some(),
+            child_radixes: self.children(idx).map(|(radix, _)| radix).collect(),
+        })
+    }
+}
@@ -1,6 +1,8 @@
 use bytes::Bytes;
 
-use crate::OxidArt;
+use crate::{
+    Fragmentation, Handle, KeyError, NodeInfo, OxidArt, OxidArtBuilder, OxidArtError, OxidArtN,
+};
 
 #[test]
 fn test_get_set_basic() {
@@ -189,7 +191,7 @@ fn test_del_intermediate_node_with_children() {
 fn test_many_keys_same_prefix() {
     let mut art = OxidArt::new();
 
-    // Beaucoup de clés avec le même préfixe pour tester les huge_childs
+    // Beaucoup de clés avec le même préfixe pour tester les Node16/48/256
     for i in 0..20u8 {
         let key = Bytes::from(vec![b'x', i]);
         let val = Bytes::from(vec![i]);
@@ -410,7 +412,7 @@ fn test_getn_single_char_prefix() {
 fn test_getn_many_children() {
     let mut art = OxidArt::new();
 
-    // Plus de 10 enfants pour tester huge_childs
+    // Plus de 4 enfants pour tester les Node16/48/256
     for i in 0..20u8 {
         let key = Bytes::from(vec![b'x', b':', i]);
         let val = Bytes::from(vec![i]);
@@ -564,7 +566,7 @@ fn test_deln_with_nested_keys() {
 fn test_deln_many_children() {
     let mut art = OxidArt::new();
 
-    // Plus de 10 enfants pour tester huge_childs
+    // Plus de 4 enfants pour tester les Node16/48/256
     for i in 0..20u8 {
         let key = Bytes::from(vec![b'x', b':', i]);
         let val = Bytes::from(vec![i]);
@@ -718,4 +720,5548 @@ fn test_ttl_cleanup_on_expired_get() {
     );
 }
 
+// ============ Tests TTL bounds ============
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_ttl_huge_duration_never_expires() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(1_700_000_000);
+    art.set_ttl(
+        Bytes::from_static(b"max_secs"),
+        Duration::from_secs(u64::MAX),
+        Bytes::from_static(b"a"),
+    );
+    art.set_ttl(
+        Bytes::from_static(b"max"),
+        Duration::MAX,
+        Bytes::from_static(b"b"),
+    );
+    art.set_ttl(
+        Bytes::from_static(b"years"),
+        Duration::from_secs(u64::MAX - 10),
+        Bytes::from_static(b"c"),
+    );
+
+    art.set_now(u64::MAX - 1);
+    assert_eq!(
+        art.get(Bytes::from_static(b"max_secs")),
+        Some(Bytes::from_static(b"a"))
+    );
+    assert_eq!(
+        art.get(Bytes::from_static(b"max")),
+        Some(Bytes::from_static(b"b"))
+    );
+    assert_eq!(
+        art.get(Bytes::from_static(b"years")),
+        Some(Bytes::from_static(b"c"))
+    );
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_ttl_zero_duration_expires_immediately() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(100);
+    art.set_ttl(
+        Bytes::from_static(b"gone"),
+        Duration::ZERO,
+        Bytes::from_static(b"a"),
+    );
+    assert_eq!(art.get(Bytes::from_static(b"gone")), None);
+    assert!(art.getn(Bytes::new()).is_empty());
+    assert_eq!(art.node_count(), 1);
+
+    // An existing key is removed too
+    art.set(Bytes::from_static(b"key"), Bytes::from_static(b"b"));
+    art.set_ttl(
+        Bytes::from_static(b"key"),
+        Duration::ZERO,
+        Bytes::from_static(b"c"),
+    );
+    assert_eq!(art.get(Bytes::from_static(b"key")), None);
+    art.verify_invariants().unwrap();
+
+    // At time 0 as well
+    art.set_now(0);
+    art.set_ttl(
+        Bytes::from_static(b"key"),
+        Duration::ZERO,
+        Bytes::from_static(b"d"),
+    );
+    assert_eq!(art.get(Bytes::from_static(b"key")), None);
+}
+
+// ============ Tests statistiques ============
+
+#[test]
+fn test_size_report() {
+    let mut art = OxidArt::new();
+
+    art.set(Bytes::from_static(b""), Bytes::from(vec![0u8; 10]));
+    art.set(Bytes::from_static(b"user"), Bytes::from(vec![0u8; 100]));
+    art.set(Bytes::from_static(b"user:1"), Bytes::from(vec![0u8; 300]));
+    art.set(Bytes::from_static(b"uso"), Bytes::from(vec![0u8; 2000]));
+
+    let report = art.size_report();
+    assert_eq!(report.value_count, 4);
+    assert_eq!(report.total_key_bytes, 4 + 6 + 3);
+    assert_eq!(report.total_value_bytes, 10 + 100 + 300 + 2000);
+    assert_eq!(report.value_size_histogram, [1, 1, 1, 1]);
+}
+
+#[test]
+fn test_size_report_empty() {
+    let art = OxidArt::new();
+    assert_eq!(art.size_report(), crate::SizeReport::default());
+}
+
+// ============ Tests drain_prefix ============
+
+#[test]
+fn test_drain_prefix_basic() {
+    let mut art = OxidArt::new();
+
+    art.set(Bytes::from_static(b"job:1"), Bytes::from_static(b"a"));
+    art.set(Bytes::from_static(b"job:2"), Bytes::from_static(b"b"));
+    art.set(Bytes::from_static(b"jobless"), Bytes::from_static(b"c"));
+
+    let mut drained = art.drain_prefix(Bytes::from_static(b"job:"));
+    drained.sort();
+
+    assert_eq!(
+        drained,
+        vec![
+            (Bytes::from_static(b"job:1"), Bytes::from_static(b"a")),
+            (Bytes::from_static(b"job:2"), Bytes::from_static(b"b")),
+        ]
+    );
+    assert!(art.getn(Bytes::from_static(b"job:")).is_empty());
+    assert_eq!(
+        art.get(Bytes::from_static(b"jobless")),
+        Some(Bytes::from_static(b"c"))
+    );
+}
+
+#[test]
+fn test_drain_prefix_in_compression() {
+    let mut art = OxidArt::new();
+
+    art.set(Bytes::from_static(b"hello_world"), Bytes::from_static(b"1"));
+    art.set(Bytes::from_static(b"help"), Bytes::from_static(b"2"));
+
+    // "hello" ends inside the compression of "hello_world"
+    let drained = art.drain_prefix(Bytes::from_static(b"hello"));
+    assert_eq!(
+        drained,
+        vec![(Bytes::from_static(b"hello_world"), Bytes::from_static(b"1"))]
+    );
+
+    // Parent recompressed, remaining key still reachable
+    assert_eq!(
+        art.get(Bytes::from_static(b"help")),
+        Some(Bytes::from_static(b"2"))
+    );
+    assert!(art.drain_prefix(Bytes::from_static(b"nope")).is_empty());
+}
+
+#[test]
+fn test_drain_prefix_empty() {
+    let mut art = OxidArt::new();
+
+    art.set(Bytes::from_static(b""), Bytes::from_static(b"root"));
+    art.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+
+    let drained = art.drain_prefix(Bytes::from_static(b""));
+    assert_eq!(drained.len(), 2);
+    assert!(art.getn(Bytes::from_static(b"")).is_empty());
+}
+
+// ============ Tests invariants ============
+
+#[test]
+fn test_verify_invariants_after_operations() {
+    let mut art = OxidArt::new();
+    assert_eq!(art.verify_invariants(), Ok(()));
+
+    art.set(Bytes::from_static(b""), Bytes::from_static(b"root"));
+    for i in 0..40u8 {
+        art.set(Bytes::from(vec![b'k', i]), Bytes::from_static(b"v"));
+        art.set(Bytes::from(vec![i, b'x', b'y']), Bytes::from_static(b"v"));
+    }
+    art.set(Bytes::from_static(b"user"), Bytes::from_static(b"1"));
+    art.set(Bytes::from_static(b"uso"), Bytes::from_static(b"2"));
+    assert_eq!(art.verify_invariants(), Ok(()));
+
+    art.del(Bytes::from_static(b"uso"));
+    art.deln(Bytes::from_static(b"k"));
+    assert_eq!(art.verify_invariants(), Ok(()));
+
+    // Root with grown childs is fully reclaimed
+    art.deln(Bytes::from_static(b""));
+    assert_eq!(art.verify_invariants(), Ok(()));
+}
+
+#[test]
+fn test_verify_invariants_detects_collapsible_node() {
+    let mut art = OxidArt::new();
+
+    art.set(Bytes::from_static(b"user"), Bytes::from_static(b"1"));
+    art.set(Bytes::from_static(b"uso"), Bytes::from_static(b"2"));
+
+    // Corrupt the tree: remove the value-less intermediate's second child by hand
+    let us_idx = art.find(art.root_idx, b'u').unwrap();
+    let o_idx = art.find(us_idx, b'o').unwrap();
+    art.remove_child(us_idx, b'o');
+    art.map.remove(o_idx as usize);
+
+    assert!(art.verify_invariants().is_err());
+}
+
+#[test]
+fn test_compact_removes_dead_and_collapsible_nodes() {
+    let mut art = OxidArt::new();
+
+    // Corrupt a leaf by hand: "xa" is left without value
+    art.set(Bytes::from_static(b"xa"), Bytes::from_static(b"v"));
+    art.set(Bytes::from_static(b"xb"), Bytes::from_static(b"v"));
+    let x_idx = art.find(art.root_idx, b'x').unwrap();
+    let xa_idx = art.find(x_idx, b'a').unwrap();
+    art.get_node_mut(xa_idx).val = None;
+    art.len -= 1;
+    assert!(art.verify_invariants().is_err());
+
+    // Corrupt a chain by hand: "us" keeps a single child and no value
+    art.set(Bytes::from_static(b"user"), Bytes::from_static(b"1"));
+    art.set(Bytes::from_static(b"uso"), Bytes::from_static(b"2"));
+    let us_idx = art.find(art.root_idx, b'u').unwrap();
+    let o_idx = art.find(us_idx, b'o').unwrap();
+    art.remove_child(us_idx, b'o');
+    art.map.remove(o_idx as usize);
+    art.len -= 1;
+
+    art.compact();
+
+    assert_eq!(art.verify_invariants(), Ok(()));
+    assert_eq!(
+        art.get(Bytes::from_static(b"user")),
+        Some(Bytes::from_static(b"1"))
+    );
+    assert_eq!(
+        art.get(Bytes::from_static(b"xb")),
+        Some(Bytes::from_static(b"v"))
+    );
+    assert_eq!(art.getn(Bytes::from_static(b"")).len(), 2);
+}
+
+// ============ Tests get_with_key ============
+
+#[test]
+fn test_get_with_key() {
+    let mut art = OxidArt::new();
+
+    art.set(Bytes::from_static(b""), Bytes::from_static(b"root"));
+    art.set(Bytes::from_static(b"user"), Bytes::from_static(b"val_user"));
+    art.set(Bytes::from_static(b"uso"), Bytes::from_static(b"val_uso"));
+    art.set(Bytes::from_static(b"us"), Bytes::from_static(b"val_us"));
+
+    for key in [&b""[..], b"user", b"uso", b"us"] {
+        let (stored_key, _) = art.get_with_key(Bytes::copy_from_slice(key)).unwrap();
+        assert_eq!(stored_key, Bytes::copy_from_slice(key));
+    }
+    assert_eq!(
+        art.get_with_key(Bytes::from_static(b"uso")),
+        Some((Bytes::from_static(b"uso"), Bytes::from_static(b"val_uso")))
+    );
+
+    assert_eq!(art.get_with_key(Bytes::from_static(b"u")), None);
+    assert_eq!(art.get_with_key(Bytes::from_static(b"users")), None);
+    assert_eq!(art.get_with_key(Bytes::from_static(b"usa")), None);
+}
+
+// ============ Tests append ============
+
+#[test]
+fn test_append() {
+    let mut art = OxidArt::new();
+
+    assert_eq!(art.append(Bytes::from_static(b"log"), b"hello"), 5);
+    assert_eq!(art.append(Bytes::from_static(b"log"), b" world"), 11);
+    assert_eq!(
+        art.get(Bytes::from_static(b"log")),
+        Some(Bytes::from_static(b"hello world"))
+    );
+
+    // Shared static buffer is copied, not mutated
+    let original = Bytes::from_static(b"abc");
+    art.set(Bytes::from_static(b"logs"), original.clone());
+    assert_eq!(art.append(Bytes::from_static(b"logs"), b"def"), 6);
+    assert_eq!(original, Bytes::from_static(b"abc"));
+    assert_eq!(
+        art.get(Bytes::from_static(b"logs")),
+        Some(Bytes::from_static(b"abcdef"))
+    );
+
+    // Intermediate node without value gets created
+    assert_eq!(art.append(Bytes::from_static(b"lo"), b"x"), 1);
+    assert_eq!(
+        art.get(Bytes::from_static(b"lo")),
+        Some(Bytes::from_static(b"x"))
+    );
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_append_keeps_and_resets_ttl() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(0);
+
+    art.set_ttl(
+        Bytes::from_static(b"k"),
+        Duration::from_secs(10),
+        Bytes::from_static(b"a"),
+    );
+    assert_eq!(art.append(Bytes::from_static(b"k"), b"b"), 2);
+
+    // Expiration is kept by append
+    art.set_now(20);
+    assert_eq!(art.get(Bytes::from_static(b"k")), None);
+
+    // Expired entry is replaced as if absent
+    art.set_ttl(
+        Bytes::from_static(b"e"),
+        Duration::from_secs(10),
+        Bytes::from_static(b"old"),
+    );
+    art.set_now(40);
+    assert_eq!(art.append(Bytes::from_static(b"e"), b"new"), 3);
+    assert_eq!(
+        art.get(Bytes::from_static(b"e")),
+        Some(Bytes::from_static(b"new"))
+    );
+}
+
+// ============ Tests getrange ============
+
+#[test]
+fn test_getrange() {
+    let mut art = OxidArt::new();
+    let key = Bytes::from_static(b"blob");
+    art.set(key.clone(), Bytes::from_static(b"hello world"));
+
+    assert_eq!(
+        art.getrange(key.clone(), 0, 5),
+        Some(Bytes::from_static(b"hello"))
+    );
+    // end past the value is clamped
+    assert_eq!(
+        art.getrange(key.clone(), 6, 100),
+        Some(Bytes::from_static(b"world"))
+    );
+    // start past the value, or start > end, gives an empty slice
+    assert_eq!(art.getrange(key.clone(), 50, 60), Some(Bytes::new()));
+    assert_eq!(art.getrange(key.clone(), 4, 2), Some(Bytes::new()));
+
+    assert_eq!(art.getrange(Bytes::from_static(b"missing"), 0, 5), None);
+    assert_eq!(art.getrange(Bytes::from_static(b"bl"), 0, 5), None);
+}
+
+// ============ Tests get_siblings ============
+
+#[test]
+fn test_get_siblings() {
+    let mut art = OxidArt::new();
+
+    art.set(
+        Bytes::from_static(b"obj:123:name"),
+        Bytes::from_static(b"alice"),
+    );
+    art.set(
+        Bytes::from_static(b"obj:123:email"),
+        Bytes::from_static(b"a@b.c"),
+    );
+    art.set(
+        Bytes::from_static(b"obj:123:age"),
+        Bytes::from_static(b"42"),
+    );
+    art.set(Bytes::from_static(b"obj:123:"), Bytes::from_static(b"self"));
+    art.set(
+        Bytes::from_static(b"obj:124:name"),
+        Bytes::from_static(b"bob"),
+    );
+
+    let values = art.get_siblings(
+        Bytes::from_static(b"obj:123:"),
+        &[b"age", b"missing", b"name", b"", b"email", b"nam"],
+    );
+    assert_eq!(
+        values,
+        vec![
+            Some(Bytes::from_static(b"42")),
+            None,
+            Some(Bytes::from_static(b"alice")),
+            Some(Bytes::from_static(b"self")),
+            Some(Bytes::from_static(b"a@b.c")),
+            None,
+        ]
+    );
+
+    // Prefix ending within a compression
+    let values = art.get_siblings(Bytes::from_static(b"ob"), &[b"j:124:name", b"j:12"]);
+    assert_eq!(values, vec![Some(Bytes::from_static(b"bob")), None]);
+
+    // Empty prefix behaves like get
+    let values = art.get_siblings(Bytes::from_static(b""), &[b"obj:123:age"]);
+    assert_eq!(values, vec![Some(Bytes::from_static(b"42"))]);
+}
+
+#[test]
+fn test_get_siblings_missing_prefix() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"obj:1"), Bytes::from_static(b"v"));
+
+    let values = art.get_siblings(Bytes::from_static(b"other:"), &[b"1", b"2"]);
+    assert_eq!(values, vec![None, None]);
+}
+
+// ============ Tests root compressée ============
+
+#[test]
+fn test_root_recompresses_after_del() {
+    let mut art = OxidArt::new();
+
+    art.set(Bytes::from_static(b"apple"), Bytes::from_static(b"1"));
+    art.set(Bytes::from_static(b"banana"), Bytes::from_static(b"2"));
+    art.del(Bytes::from_static(b"banana"));
+
+    // The root absorbed its single child
+    assert_eq!(art.get_node(art.root_idx).compression.as_slice(), b"apple");
+    assert_eq!(art.verify_invariants(), Ok(()));
+    assert!(!art.root_has_value());
+
+    assert_eq!(
+        art.get(Bytes::from_static(b"apple")),
+        Some(Bytes::from_static(b"1"))
+    );
+    assert_eq!(art.get(Bytes::from_static(b"")), None);
+    assert_eq!(art.get(Bytes::from_static(b"app")), None);
+    assert_eq!(art.getn(Bytes::from_static(b"app")).len(), 1);
+    assert_eq!(art.getn(Bytes::from_static(b"")).len(), 1);
+    assert!(art.getn(Bytes::from_static(b"b")).is_empty());
+    assert_eq!(
+        art.get_with_key(Bytes::from_static(b"apple")),
+        Some((Bytes::from_static(b"apple"), Bytes::from_static(b"1")))
+    );
+}
+
+#[test]
+fn test_set_splits_compressed_root() {
+    let mut art = OxidArt::new();
+
+    art.set(Bytes::from_static(b"apple"), Bytes::from_static(b"1"));
+    art.set(Bytes::from_static(b"b"), Bytes::from_static(b"2"));
+    art.del(Bytes::from_static(b"b"));
+
+    // Empty key splits the root compression
+    art.set(Bytes::from_static(b""), Bytes::from_static(b"root"));
+    assert!(art.root_has_value());
+    // Divergent and extending keys
+    art.set(Bytes::from_static(b"apricot"), Bytes::from_static(b"3"));
+    art.set(Bytes::from_static(b"apples"), Bytes::from_static(b"4"));
+    assert_eq!(art.verify_invariants(), Ok(()));
+
+    let mut all = art.getn(Bytes::from_static(b""));
+    all.sort();
+    assert_eq!(
+        all,
+        vec![
+            (Bytes::from_static(b""), Bytes::from_static(b"root")),
+            (Bytes::from_static(b"apple"), Bytes::from_static(b"1")),
+            (Bytes::from_static(b"apples"), Bytes::from_static(b"4")),
+            (Bytes::from_static(b"apricot"), Bytes::from_static(b"3")),
+        ]
+    );
+}
+
+#[test]
+fn test_deln_on_compressed_root() {
+    let mut art = OxidArt::new();
+
+    art.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"a"));
+    art.set(Bytes::from_static(b"user:2"), Bytes::from_static(b"b"));
+    art.set(Bytes::from_static(b"post:1"), Bytes::from_static(b"c"));
+    assert_eq!(art.deln(Bytes::from_static(b"post")), 1);
+
+    // Root is now compressed as "user:"
+    assert_eq!(art.get_node(art.root_idx).compression.as_slice(), b"user:");
+    assert_eq!(art.deln(Bytes::from_static(b"x")), 0);
+    assert_eq!(art.deln(Bytes::from_static(b"user:1")), 1);
+    assert_eq!(art.verify_invariants(), Ok(()));
+
+    // Prefix ending within the root compression deletes everything
+    assert_eq!(art.deln(Bytes::from_static(b"us")), 1);
+    assert!(art.getn(Bytes::from_static(b"")).is_empty());
+    assert_eq!(art.verify_invariants(), Ok(()));
+
+    art.set(Bytes::from_static(b"new"), Bytes::from_static(b"d"));
+    assert_eq!(
+        art.get(Bytes::from_static(b"new")),
+        Some(Bytes::from_static(b"d"))
+    );
+    assert_eq!(art.verify_invariants(), Ok(()));
+}
+
+// ============ Tests into_sorted_vec ============
+
+#[test]
+fn test_into_sorted_vec() {
+    let mut art = OxidArt::new();
+    let mut keys: Vec<Vec<u8>> = vec![
+        b"".to_vec(),
+        b"user".to_vec(),
+        b"uso".to_vec(),
+        b"us".to_vec(),
+        b"apple".to_vec(),
+        b"application".to_vec(),
+        b"b".to_vec(),
+    ];
+    // Enough siblings to grow past the inline tier
+    for i in 0..30u8 {
+        keys.push(vec![b'z', b'~' - i]);
+    }
+    for key in &keys {
+        art.set(Bytes::from(key.clone()), Bytes::from(key.clone()));
+    }
+
+    let entries = art.into_sorted_vec();
+    keys.sort();
+    assert_eq!(entries.len(), keys.len());
+    for ((key, val), expected) in entries.iter().zip(&keys) {
+        assert_eq!(key, expected);
+        assert_eq!(val, expected);
+    }
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_into_sorted_vec_skips_expired() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(0);
+    art.set_ttl(
+        Bytes::from_static(b"a"),
+        Duration::from_secs(1),
+        Bytes::from_static(b"old"),
+    );
+    art.set(Bytes::from_static(b"b"), Bytes::from_static(b"kept"));
+    art.set_now(10);
+
+    assert_eq!(
+        art.into_sorted_vec(),
+        vec![(Bytes::from_static(b"b"), Bytes::from_static(b"kept"))]
+    );
+}
+
+// ============ Tests fold_prefix ============
+
+#[test]
+fn test_fold_prefix() {
+    let mut art = OxidArt::new();
+
+    art.set(
+        Bytes::from_static(b"metrics:req:a"),
+        Bytes::from_static(b"3"),
+    );
+    art.set(
+        Bytes::from_static(b"metrics:req:b"),
+        Bytes::from_static(b"4"),
+    );
+    art.set(
+        Bytes::from_static(b"metrics:req:"),
+        Bytes::from_static(b"10"),
+    );
+    art.set(
+        Bytes::from_static(b"metrics:err:a"),
+        Bytes::from_static(b"100"),
+    );
+
+    let sum = |acc: u64, _: &[u8], val: &Bytes| {
+        acc + std::str::from_utf8(val).unwrap().parse::<u64>().unwrap()
+    };
+    assert_eq!(
+        art.fold_prefix(Bytes::from_static(b"metrics:req:"), 0, sum),
+        17
+    );
+    assert_eq!(
+        art.fold_prefix(Bytes::from_static(b"metrics:"), 0, sum),
+        117
+    );
+    assert_eq!(art.fold_prefix(Bytes::from_static(b"nope"), 0, sum), 0);
+
+    // Keys are reconstructed in full
+    let mut keys = art.fold_prefix(
+        Bytes::from_static(b"metrics:r"),
+        Vec::new(),
+        |mut acc, key, _| {
+            acc.push(key.to_vec());
+            acc
+        },
+    );
+    keys.sort();
+    assert_eq!(
+        keys,
+        vec![
+            b"metrics:req:".to_vec(),
+            b"metrics:req:a".to_vec(),
+            b"metrics:req:b".to_vec()
+        ]
+    );
+}
+
+// ============ Tests write_entries ============
+
+#[cfg(feature = "std")]
+fn read_records(mut buf: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
+    let chunk = |buf: &mut &[u8]| {
+        let len = u32::from_le_bytes(buf[..4].try_into().unwrap()) as usize;
+        let bytes = buf[4..4 + len].to_vec();
+        *buf = &buf[4 + len..];
+        bytes
+    };
+    let mut records = Vec::new();
+    while !buf.is_empty() {
+        let key = chunk(&mut buf);
+        let val = chunk(&mut buf);
+        records.push((key, val));
+    }
+    records
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_write_entries() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"ab"), Bytes::from_static(b"1"));
+    art.set(Bytes::from_static(b"b"), Bytes::from_static(b"22"));
+    // Root is recompressed into "ab" once "b" is gone
+    art.del(Bytes::from_static(b"b"));
+    art.set(Bytes::from_static(b"abc"), Bytes::from_static(b""));
+    art.set(Bytes::from_static(b"abd"), Bytes::from_static(b"333"));
+
+    let mut out = Vec::new();
+    assert_eq!(art.write_entries(&mut out).unwrap(), 3);
+
+    let mut records = read_records(&out);
+    records.sort();
+    assert_eq!(
+        records,
+        vec![
+            (b"ab".to_vec(), b"1".to_vec()),
+            (b"abc".to_vec(), b"".to_vec()),
+            (b"abd".to_vec(), b"333".to_vec()),
+        ]
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_write_entries_empty_tree() {
+    let art = OxidArt::new();
+    let mut out = Vec::new();
+    assert_eq!(art.write_entries(&mut out).unwrap(), 0);
+    assert!(out.is_empty());
+}
+
+// ============ Tests remove_entry ============
+
+#[test]
+fn test_remove_entry() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    art.set(Bytes::from_static(b"user:12"), Bytes::from_static(b"bob"));
+    art.set(Bytes::from_static(b"user:2"), Bytes::from_static(b"carol"));
+
+    // Node with children keeps its place
+    assert_eq!(
+        art.remove_entry(Bytes::from_static(b"user:1")),
+        Some((Bytes::from_static(b"user:1"), Bytes::from_static(b"alice")))
+    );
+    // Leaf is freed
+    assert_eq!(
+        art.remove_entry(Bytes::from_static(b"user:2")),
+        Some((Bytes::from_static(b"user:2"), Bytes::from_static(b"carol")))
+    );
+    assert_eq!(art.remove_entry(Bytes::from_static(b"user:2")), None);
+    assert_eq!(art.remove_entry(Bytes::from_static(b"user:")), None);
+    assert_eq!(art.remove_entry(Bytes::from_static(b"missing")), None);
+
+    assert_eq!(art.get(Bytes::from_static(b"user:1")), None);
+    assert_eq!(
+        art.get(Bytes::from_static(b"user:12")),
+        Some(Bytes::from_static(b"bob"))
+    );
+    art.verify_invariants().unwrap();
+}
+
+#[test]
+fn test_remove_entry_empty_key() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b""), Bytes::from_static(b"root"));
+    art.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+
+    assert_eq!(
+        art.remove_entry(Bytes::from_static(b"")),
+        Some((Bytes::new(), Bytes::from_static(b"root")))
+    );
+    assert_eq!(
+        art.remove_entry(Bytes::from_static(b"a")),
+        Some((Bytes::from_static(b"a"), Bytes::from_static(b"1")))
+    );
+    art.verify_invariants().unwrap();
+}
+
+// ============ Tests inline capacity ============
+
+fn check_inline_capacity<const N: usize>() {
+    let mut art = OxidArtN::<N>::new();
+    // 60 children under the root and under "k", well past any inline capacity
+    for c in b'A'..b'A' + 60 {
+        art.set(Bytes::copy_from_slice(&[c]), Bytes::copy_from_slice(&[c]));
+        art.set(Bytes::copy_from_slice(&[b'k', c]), Bytes::from_static(b"v"));
+    }
+    art.verify_invariants().unwrap();
+
+    for c in b'A'..b'A' + 60 {
+        assert_eq!(
+            art.get(Bytes::copy_from_slice(&[c])),
+            Some(Bytes::copy_from_slice(&[c]))
+        );
+    }
+    assert_eq!(art.getn(Bytes::from_static(b"k")).len(), 61);
+
+    for c in b'A'..b'A' + 60 {
+        assert!(art.del(Bytes::copy_from_slice(&[b'k', c])).is_some());
+    }
+    art.verify_invariants().unwrap();
+    assert_eq!(art.getn(Bytes::from_static(b"")).len(), 60);
+}
+
+#[test]
+fn test_inline_capacity() {
+    check_inline_capacity::<1>();
+    check_inline_capacity::<4>();
+    check_inline_capacity::<32>();
+    check_inline_capacity::<64>();
+}
+
+// ============ Tests try_set ============
+
+#[test]
+fn test_try_set() {
+    let mut art = OxidArt::new();
+    assert_eq!(
+        art.try_set(Bytes::from_static(b"key"), Bytes::from_static(b"value")),
+        Ok(())
+    );
+    assert_eq!(
+        art.try_set(Bytes::from_static(b"key"), Bytes::from_static(b"value2")),
+        Ok(())
+    );
+    assert_eq!(
+        art.get(Bytes::from_static(b"key")),
+        Some(Bytes::from_static(b"value2"))
+    );
+
+    assert_eq!(
+        art.try_set(
+            Bytes::from_static("clé".as_bytes()),
+            Bytes::from_static(b"v")
+        ),
+        Ok(())
+    );
+    assert_eq!(art.getn(Bytes::from_static(b"")).len(), 2);
+    art.verify_invariants().unwrap();
+}
+
+#[test]
+fn test_try_set_every_radix_without_inline_slots() {
+    // Without inline slots, the grown tiers alone must hold every child
+    let mut art = OxidArtN::<0>::new();
+    for c in 0..=u8::MAX {
+        assert_eq!(
+            art.try_set(Bytes::copy_from_slice(&[c]), Bytes::from_static(b"v")),
+            Ok(())
+        );
+    }
+
+    // Updating or extending existing children still works
+    assert_eq!(
+        art.try_set(Bytes::from_static(b"a"), Bytes::from_static(b"new")),
+        Ok(())
+    );
+    assert_eq!(
+        art.try_set(Bytes::from_static(b"ab"), Bytes::from_static(b"v")),
+        Ok(())
+    );
+    assert_eq!(art.getn(Bytes::from_static(b"")).len(), 257);
+    art.verify_invariants().unwrap();
+}
+
+#[test]
+fn test_try_set_max_key_len() {
+    let mut art = OxidArt::builder().max_key_len(Some(8)).build();
+    assert_eq!(
+        art.try_set(Bytes::from_static(b"12345678"), Bytes::from_static(b"v")),
+        Ok(())
+    );
+    let err = art
+        .try_set(Bytes::from_static(b"123456789"), Bytes::from_static(b"v"))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        OxidArtError::InvalidKey(KeyError::TooLong { len: 9, max: 8 })
+    );
+    assert_eq!(
+        err.to_string(),
+        "invalid key: key of 9 bytes exceeds the maximum of 8"
+    );
+    assert_eq!(art.get(Bytes::from_static(b"123456789")), None);
+    assert_eq!(art.len(), 1);
+
+    // Only try_set validates, the limit survives a clone
+    let mut copy = art.clone();
+    copy.set(Bytes::from_static(b"123456789"), Bytes::from_static(b"v"));
+    assert_eq!(copy.len(), 2);
+    assert!(
+        copy.try_set(Bytes::from_static(b"abcdefghi"), Bytes::new())
+            .is_err()
+    );
+
+    let mut unbounded = OxidArt::new();
+    assert_eq!(
+        unbounded.try_set(Bytes::from(vec![b'k'; 4096]), Bytes::new()),
+        Ok(())
+    );
+}
+
+// ============ Tests from_sorted ============
+
+#[test]
+fn test_from_sorted() {
+    let mut keys: Vec<&[u8]> = vec![
+        b"", b"a", b"ab", b"abc", b"abd", b"abda", b"b", b"user:1", b"user:10", b"user:2",
+        b"user:20", b"user:3", b"usr",
+    ];
+    keys.sort();
+    let pairs = keys
+        .iter()
+        .map(|k| (Bytes::copy_from_slice(k), Bytes::copy_from_slice(k)));
+
+    let mut art = OxidArt::from_sorted(pairs);
+    art.verify_invariants().unwrap();
+    for k in &keys {
+        assert_eq!(
+            art.get(Bytes::copy_from_slice(k)),
+            Some(Bytes::copy_from_slice(k))
+        );
+    }
+    assert_eq!(art.getn(Bytes::from_static(b"")).len(), keys.len());
+    assert_eq!(art.getn(Bytes::from_static(b"user:")).len(), 5);
+    assert_eq!(art.get(Bytes::from_static(b"us")), None);
+
+    // The tree is a regular tree afterwards
+    art.set(Bytes::from_static(b"abb"), Bytes::from_static(b"x"));
+    assert_eq!(
+        art.del(Bytes::from_static(b"abd")),
+        Some(Bytes::from_static(b"abd"))
+    );
+    art.verify_invariants().unwrap();
+}
+
+#[test]
+fn test_from_sorted_duplicates() {
+    let pairs = vec![
+        (Bytes::from_static(b"key"), Bytes::from_static(b"1")),
+        (Bytes::from_static(b"key"), Bytes::from_static(b"2")),
+        (Bytes::from_static(b"keys"), Bytes::from_static(b"3")),
+    ];
+    let mut art = OxidArt::from_sorted(pairs.into_iter());
+    art.verify_invariants().unwrap();
+    assert_eq!(
+        art.get(Bytes::from_static(b"key")),
+        Some(Bytes::from_static(b"2"))
+    );
+    assert_eq!(
+        art.get(Bytes::from_static(b"keys")),
+        Some(Bytes::from_static(b"3"))
+    );
+}
+
+#[test]
+fn test_from_sorted_many_children() {
+    let pairs = (0..2000u32).map(|i| {
+        let key = Bytes::from(format!("{i:04}"));
+        (key.clone(), key)
+    });
+    let mut art = OxidArt::from_sorted(pairs);
+    art.verify_invariants().unwrap();
+    assert_eq!(art.getn(Bytes::from_static(b"")).len(), 2000);
+    assert_eq!(
+        art.get(Bytes::from_static(b"1234")),
+        Some(Bytes::from_static(b"1234"))
+    );
+}
+
+// ============ Tests prefix_exists ============
+
+#[test]
+fn test_prefix_exists() {
+    let mut art = OxidArt::new();
+    assert!(!art.prefix_exists(Bytes::from_static(b"")));
+
+    art.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    art.set(Bytes::from_static(b"user:2"), Bytes::from_static(b"bob"));
+
+    assert!(art.prefix_exists(Bytes::from_static(b"")));
+    assert!(art.prefix_exists(Bytes::from_static(b"us")));
+    assert!(art.prefix_exists(Bytes::from_static(b"user:")));
+    assert!(art.prefix_exists(Bytes::from_static(b"user:1")));
+    assert!(!art.prefix_exists(Bytes::from_static(b"user:3")));
+    assert!(!art.prefix_exists(Bytes::from_static(b"user:1:x")));
+    assert!(!art.prefix_exists(Bytes::from_static(b"post")));
+
+    art.deln(Bytes::from_static(b"user:"));
+    assert!(!art.prefix_exists(Bytes::from_static(b"u")));
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_prefix_exists_ignores_expired() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(100);
+    art.set_ttl(
+        Bytes::from_static(b"session:a"),
+        Duration::from_secs(10),
+        Bytes::from_static(b"x"),
+    );
+    art.set_ttl(
+        Bytes::from_static(b"session:b"),
+        Duration::from_secs(50),
+        Bytes::from_static(b"y"),
+    );
+
+    art.set_now(120);
+    assert!(art.prefix_exists(Bytes::from_static(b"session:")));
+    assert!(!art.prefix_exists(Bytes::from_static(b"session:a")));
+
+    art.set_now(200);
+    assert!(!art.prefix_exists(Bytes::from_static(b"session:")));
+}
+
+// ============ Tests node_count / value_count ============
+
+#[test]
+fn test_node_and_value_count() {
+    let mut art = OxidArt::new();
+    assert_eq!(art.node_count(), 1);
+    assert_eq!(art.value_count(), 0);
+
+    art.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"a"));
+    art.set(Bytes::from_static(b"user:2"), Bytes::from_static(b"b"));
+    art.set(Bytes::from_static(b"user"), Bytes::from_static(b"c"));
+    // root -> "user" -> ":" -> {"1", "2"}
+    assert_eq!(art.value_count(), 3);
+    assert_eq!(art.node_count(), 5);
+    assert!(art.node_count() > art.value_count());
+
+    art.del(Bytes::from_static(b"user:1"));
+    art.del(Bytes::from_static(b"user:2"));
+    assert_eq!(art.value_count(), 1);
+    assert_eq!(art.node_count(), 2);
+}
+
+// ============ Tests split with exact prefix keys ============
+
+fn check_prefix_chain(order: &[&'static [u8]]) {
+    let mut art = OxidArt::new();
+    for key in order {
+        art.set(Bytes::from_static(key), Bytes::from_static(key));
+        art.verify_invariants().unwrap();
+    }
+
+    for key in [&b"ab"[..], b"abc", b"abcd"] {
+        assert_eq!(
+            art.get(Bytes::from_static(key)),
+            Some(Bytes::from_static(key)),
+            "order {order:?}"
+        );
+    }
+    assert_eq!(art.get(Bytes::from_static(b"a")), None);
+    assert_eq!(art.get(Bytes::from_static(b"abcde")), None);
+
+    let mut found = art.getn(Bytes::from_static(b"ab"));
+    found.sort();
+    let expected: Vec<(Bytes, Bytes)> = [&b"ab"[..], b"abc", b"abcd"]
+        .iter()
+        .map(|k| (Bytes::from_static(k), Bytes::from_static(k)))
+        .collect();
+    assert_eq!(found, expected, "order {order:?}");
+    assert_eq!(art.getn(Bytes::from_static(b"abc")).len(), 2);
+    assert_eq!(art.getn(Bytes::from_static(b"abcd")).len(), 1);
+}
+
+#[test]
+fn test_split_exact_prefix_all_orders() {
+    let (ab, abc, abcd): (&[u8], &[u8], &[u8]) = (b"ab", b"abc", b"abcd");
+    for order in [
+        [ab, abc, abcd],
+        [ab, abcd, abc],
+        [abc, ab, abcd],
+        [abc, abcd, ab],
+        [abcd, ab, abc],
+        [abcd, abc, ab],
+    ] {
+        check_prefix_chain(&order);
+    }
+}
+
+#[test]
+fn test_split_value_on_intermediate_then_split_again() {
+    let mut art = OxidArt::new();
+    // "abcd" is a leaf, "ab" splits it and lands on the intermediate node
+    art.set(Bytes::from_static(b"abcd"), Bytes::from_static(b"1"));
+    art.set(Bytes::from_static(b"ab"), Bytes::from_static(b"2"));
+    // "ax" splits the intermediate "ab" node itself, which holds a value
+    art.set(Bytes::from_static(b"ax"), Bytes::from_static(b"3"));
+    art.verify_invariants().unwrap();
+
+    assert_eq!(
+        art.get(Bytes::from_static(b"ab")),
+        Some(Bytes::from_static(b"2"))
+    );
+    assert_eq!(
+        art.get(Bytes::from_static(b"abcd")),
+        Some(Bytes::from_static(b"1"))
+    );
+    assert_eq!(art.getn(Bytes::from_static(b"ab")).len(), 2);
+    assert_eq!(art.getn(Bytes::from_static(b"a")).len(), 3);
+}
+
+// ============ Tests LRU eviction ============
+
+#[test]
+fn test_lru_evicts_least_recently_used() {
+    let mut art = OxidArt::with_max_entries(3);
+    art.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+    art.set(Bytes::from_static(b"b"), Bytes::from_static(b"2"));
+    art.set(Bytes::from_static(b"c"), Bytes::from_static(b"3"));
+
+    // Refresh "a", so "b" becomes the least recently used
+    assert_eq!(
+        art.get(Bytes::from_static(b"a")),
+        Some(Bytes::from_static(b"1"))
+    );
+    art.set(Bytes::from_static(b"d"), Bytes::from_static(b"4"));
+    assert_eq!(art.get(Bytes::from_static(b"b")), None);
+    assert_eq!(art.getn(Bytes::from_static(b"")).len(), 3);
+
+    // Overwriting refreshes without growing, then "c" is the oldest
+    art.set(Bytes::from_static(b"a"), Bytes::from_static(b"11"));
+    art.set(Bytes::from_static(b"e"), Bytes::from_static(b"5"));
+    assert_eq!(art.get(Bytes::from_static(b"c")), None);
+    assert_eq!(
+        art.get(Bytes::from_static(b"a")),
+        Some(Bytes::from_static(b"11"))
+    );
+    art.verify_invariants().unwrap();
+}
+
+#[test]
+fn test_lru_deletions_free_slots() {
+    let mut art = OxidArt::with_max_entries(3);
+    art.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"a"));
+    art.set(Bytes::from_static(b"user:2"), Bytes::from_static(b"b"));
+    art.set(Bytes::from_static(b"post:1"), Bytes::from_static(b"c"));
+
+    assert_eq!(art.deln(Bytes::from_static(b"user:")), 2);
+    art.set(Bytes::from_static(b"x"), Bytes::from_static(b"1"));
+    art.set(Bytes::from_static(b"y"), Bytes::from_static(b"2"));
+    // Nothing evicted: the deleted keys no longer count
+    assert_eq!(
+        art.get(Bytes::from_static(b"post:1")),
+        Some(Bytes::from_static(b"c"))
+    );
+
+    art.del(Bytes::from_static(b"x"));
+    art.set(Bytes::from_static(b"z"), Bytes::from_static(b"3"));
+    assert_eq!(art.getn(Bytes::from_static(b"")).len(), 3);
+
+    // Full again: the next write evicts "y", accessed the longest ago
+    art.append(Bytes::from_static(b"post:1"), b"!");
+    art.set(Bytes::from_static(b"w"), Bytes::from_static(b"4"));
+    assert_eq!(art.get(Bytes::from_static(b"y")), None);
+    assert_eq!(
+        art.get(Bytes::from_static(b"post:1")),
+        Some(Bytes::from_static(b"c!"))
+    );
+    art.verify_invariants().unwrap();
+}
+
+#[test]
+fn test_unbounded_tree_never_evicts() {
+    let mut art = OxidArt::new();
+    for i in 0..1000u32 {
+        art.set(Bytes::from(format!("key:{i}")), Bytes::from_static(b"v"));
+    }
+    assert_eq!(art.getn(Bytes::from_static(b"")).len(), 1000);
+}
+
+// ============ Tests get_or_default ============
+
+#[test]
+fn test_get_or_default() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"key"), Bytes::from_static(b"value"));
+
+    assert_eq!(
+        art.get_or_default(Bytes::from_static(b"key")),
+        Bytes::from_static(b"value")
+    );
+    assert_eq!(art.get_or_default(Bytes::from_static(b"ke")), Bytes::new());
+    // The miss inserted an empty value
+    assert_eq!(art.get(Bytes::from_static(b"ke")), Some(Bytes::new()));
+    assert_eq!(art.getn(Bytes::from_static(b"")).len(), 2);
+    art.verify_invariants().unwrap();
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_get_or_default_replaces_expired() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(100);
+    art.set_ttl(
+        Bytes::from_static(b"key"),
+        Duration::from_secs(10),
+        Bytes::from_static(b"old"),
+    );
+    art.set_now(200);
+
+    assert_eq!(art.get_or_default(Bytes::from_static(b"key")), Bytes::new());
+    art.set_now(10_000);
+    assert_eq!(art.get(Bytes::from_static(b"key")), Some(Bytes::new()));
+}
+
+// ============ Tests now / advance ============
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_advance_clock() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    assert_eq!(art.now(), 0);
+    art.set_now(1000);
+    art.set_ttl(
+        Bytes::from_static(b"short"),
+        Duration::from_secs(10),
+        Bytes::from_static(b"a"),
+    );
+    art.set_ttl(
+        Bytes::from_static(b"long"),
+        Duration::from_secs(100),
+        Bytes::from_static(b"b"),
+    );
+
+    art.advance(10);
+    assert_eq!(art.now(), 1010);
+    assert_eq!(
+        art.get(Bytes::from_static(b"short")),
+        Some(Bytes::from_static(b"a"))
+    );
+
+    art.advance(1);
+    assert_eq!(art.get(Bytes::from_static(b"short")), None);
+    assert_eq!(
+        art.get(Bytes::from_static(b"long")),
+        Some(Bytes::from_static(b"b"))
+    );
+
+    art.advance(90);
+    assert_eq!(art.get(Bytes::from_static(b"long")), None);
+
+    art.advance(u64::MAX);
+    assert_eq!(art.now(), u64::MAX);
+}
+
+// ============ Tests for_each_prefix_mut ============
+
+#[test]
+fn test_for_each_prefix_mut() {
+    let mut art = OxidArt::new();
+    art.set(
+        Bytes::from_static(b"user:123"),
+        Bytes::from_static(b"profile"),
+    );
+    art.set(
+        Bytes::from_static(b"user:123:s1"),
+        Bytes::from_static(b"active"),
+    );
+    art.set(
+        Bytes::from_static(b"user:123:s2"),
+        Bytes::from_static(b"active"),
+    );
+    art.set(
+        Bytes::from_static(b"user:124:s1"),
+        Bytes::from_static(b"active"),
+    );
+
+    let mut touched = Vec::new();
+    art.for_each_prefix_mut(Bytes::from_static(b"user:123:"), |key, val| {
+        touched.push(key.to_vec());
+        *val = Bytes::from_static(b"stale");
+    });
+    touched.sort();
+    assert_eq!(
+        touched,
+        vec![b"user:123:s1".to_vec(), b"user:123:s2".to_vec()]
+    );
+
+    assert_eq!(
+        art.get(Bytes::from_static(b"user:123:s1")),
+        Some(Bytes::from_static(b"stale"))
+    );
+    assert_eq!(
+        art.get(Bytes::from_static(b"user:123:s2")),
+        Some(Bytes::from_static(b"stale"))
+    );
+    assert_eq!(
+        art.get(Bytes::from_static(b"user:123")),
+        Some(Bytes::from_static(b"profile"))
+    );
+    assert_eq!(
+        art.get(Bytes::from_static(b"user:124:s1")),
+        Some(Bytes::from_static(b"active"))
+    );
+
+    // Prefix ending inside a compression, and a missing prefix
+    let mut count = 0;
+    art.for_each_prefix_mut(Bytes::from_static(b"us"), |_, _| count += 1);
+    assert_eq!(count, 4);
+    art.for_each_prefix_mut(Bytes::from_static(b"post"), |_, _| unreachable!());
+}
+
+// ============ Tests split_off_prefix ============
+
+#[test]
+fn test_split_off_prefix() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    art.set(Bytes::from_static(b"user:2"), Bytes::from_static(b"bob"));
+    art.set(Bytes::from_static(b"user:2:x"), Bytes::from_static(b"x"));
+    art.set(Bytes::from_static(b"post:1"), Bytes::from_static(b"hello"));
+    // Many children, to move a grown child block too
+    for c in b'A'..b'z' {
+        art.set(
+            Bytes::copy_from_slice(&[b'u', b's', b'e', b'r', b':', c]),
+            Bytes::from_static(b"v"),
+        );
+    }
+
+    let mut users = art.split_off_prefix(Bytes::from_static(b"use"));
+    art.verify_invariants().unwrap();
+    users.verify_invariants().unwrap();
+
+    assert_eq!(art.getn(Bytes::from_static(b"")).len(), 1);
+    assert_eq!(
+        art.get(Bytes::from_static(b"post:1")),
+        Some(Bytes::from_static(b"hello"))
+    );
+    assert_eq!(art.get(Bytes::from_static(b"user:1")), None);
+
+    assert_eq!(
+        users.getn(Bytes::from_static(b"")).len(),
+        3 + (b'z' - b'A') as usize
+    );
+    assert_eq!(
+        users.get(Bytes::from_static(b"user:2:x")),
+        Some(Bytes::from_static(b"x"))
+    );
+    assert_eq!(users.getn(Bytes::from_static(b"user:2")).len(), 2);
+    assert_eq!(users.get(Bytes::from_static(b"post:1")), None);
+
+    // Both trees keep working independently
+    users.set(Bytes::from_static(b"usa"), Bytes::from_static(b"1"));
+    users.set(Bytes::from_static(b"x"), Bytes::from_static(b"2"));
+    art.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"new"));
+    users.verify_invariants().unwrap();
+    art.verify_invariants().unwrap();
+    assert_eq!(
+        users.get(Bytes::from_static(b"user:1")),
+        Some(Bytes::from_static(b"alice"))
+    );
+}
+
+#[test]
+fn test_split_off_prefix_empty_and_missing() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+    art.set(Bytes::from_static(b"b"), Bytes::from_static(b"2"));
+
+    let none = art.split_off_prefix(Bytes::from_static(b"c"));
+    assert!(none.getn(Bytes::from_static(b"")).is_empty());
+    assert_eq!(art.getn(Bytes::from_static(b"")).len(), 2);
+
+    let all = art.split_off_prefix(Bytes::from_static(b""));
+    assert!(art.getn(Bytes::from_static(b"")).is_empty());
+    assert_eq!(all.getn(Bytes::from_static(b"")).len(), 2);
+    art.verify_invariants().unwrap();
+    all.verify_invariants().unwrap();
+
+    art.set(Bytes::from_static(b"z"), Bytes::from_static(b"3"));
+    assert_eq!(art.getn(Bytes::from_static(b"")).len(), 1);
+}
+
+#[test]
+fn test_split_off_prefix_recompresses_donor_parent() {
+    let mut art = OxidArt::new();
+    art.set(
+        Bytes::from_static(b"shard:a:1"),
+        Bytes::from_static(b"kept"),
+    );
+    art.set(
+        Bytes::from_static(b"shard:b:1"),
+        Bytes::from_static(b"moved"),
+    );
+    art.set(
+        Bytes::from_static(b"shard:b:2"),
+        Bytes::from_static(b"moved"),
+    );
+    // Root, "shard:", "a:1", "b:" and its two leaves
+    assert_eq!(art.node_count(), 6);
+
+    let moved = art.split_off_prefix(Bytes::from_static(b"shard:b"));
+    // "shard:" lost its value-less fork and is merged with its last child
+    assert_eq!(art.node_count(), 2);
+    assert_eq!(
+        art.node_info(Bytes::from_static(b"shard:a:1"))
+            .unwrap()
+            .compression,
+        b"hard:a:1".to_vec()
+    );
+    art.verify_invariants().unwrap();
+
+    assert_eq!(moved.node_count(), 3);
+    assert_eq!(
+        moved.iter().map(|(key, _)| key).collect::<Vec<_>>(),
+        vec![
+            Bytes::from_static(b"shard:b:1"),
+            Bytes::from_static(b"shard:b:2")
+        ]
+    );
+    moved.verify_invariants().unwrap();
+}
+
+// ============ Tests iter_rev ============
+
+#[test]
+fn test_iter_rev() {
+    let mut art = OxidArt::new();
+    let keys: [&[u8]; 10] = [
+        b"",
+        b"a",
+        b"ab",
+        b"abc",
+        b"abd",
+        b"b",
+        b"event:001",
+        b"event:002",
+        b"event:010",
+        b"z",
+    ];
+    for key in keys {
+        art.set(Bytes::from_static(key), Bytes::from_static(key));
+    }
+    // Grow the root children past the inline tier
+    for c in b'0'..=b'9' {
+        art.set(Bytes::copy_from_slice(&[c]), Bytes::from_static(b"d"));
+    }
+
+    let rev: Vec<(Bytes, Bytes)> = art.iter_rev().collect();
+    let mut expected = art.getn(Bytes::from_static(b""));
+    expected.sort();
+    expected.reverse();
+    assert_eq!(rev, expected);
+    assert_eq!(rev[0].0, Bytes::from_static(b"z"));
+    assert_eq!(rev.last().unwrap().0, Bytes::new());
+
+    // Lazy: taking the first entries doesn't need the rest
+    let latest: Vec<Bytes> = art.iter_rev().skip(1).take(3).map(|(k, _)| k).collect();
+    assert_eq!(
+        latest,
+        vec![
+            Bytes::from_static(b"event:010"),
+            Bytes::from_static(b"event:002"),
+            Bytes::from_static(b"event:001"),
+        ]
+    );
+
+    assert_eq!(OxidArt::new().iter_rev().count(), 0);
+}
+
+// ============ Tests count_matching ============
+
+/// Naive reference glob matcher for `*`, `?` and literals
+fn naive_glob(pattern: &[u8], key: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => key.is_empty(),
+        Some((b'*', rest)) => (0..=key.len()).any(|i| naive_glob(rest, &key[i..])),
+        Some((b'?', rest)) => !key.is_empty() && naive_glob(rest, &key[1..]),
+        Some((&c, rest)) => key.first() == Some(&c) && naive_glob(rest, &key[1..]),
+    }
+}
+
+#[test]
+fn test_count_matching() {
+    let mut art = OxidArt::new();
+    let keys: Vec<&[u8]> = vec![
+        b"",
+        b"user:1:active",
+        b"user:2:active",
+        b"user:3:banned",
+        b"user:10:active",
+        b"user:",
+        b"users",
+        b"post:1",
+        b"post:2:active",
+        b"a*b",
+        b"a?b",
+    ];
+    for key in &keys {
+        art.set(Bytes::copy_from_slice(key), Bytes::from_static(b"v"));
+    }
+
+    for pattern in [
+        &b"*"[..],
+        b"",
+        b"user:*:active",
+        b"user:?:*",
+        b"*:active",
+        b"*act*",
+        b"post:?",
+        b"user:*",
+        b"user?",
+        b"u*s",
+        b"nothing*",
+        b"**",
+        b"*1*",
+    ] {
+        let expected = keys.iter().filter(|k| naive_glob(pattern, k)).count();
+        assert_eq!(
+            art.count_matching(pattern),
+            expected,
+            "pattern {:?}",
+            std::str::from_utf8(pattern)
+        );
+    }
+
+    // Classes and escapes
+    assert_eq!(art.count_matching(b"user:[12]:active"), 2);
+    assert_eq!(art.count_matching(b"user:[0-9]:*"), 3);
+    assert_eq!(art.count_matching(b"user:[^1]*"), 2);
+    assert_eq!(art.count_matching(b"a\\*b"), 1);
+    assert_eq!(art.count_matching(b"a\\?b"), 1);
+    assert_eq!(art.count_matching(b"a?b"), 2);
+    assert_eq!(art.count_matching(b"user[:s]*"), 6);
+    // Unclosed bracket is a literal
+    assert_eq!(art.count_matching(b"user:["), 0);
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_count_matching_ignores_expired() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(100);
+    art.set(Bytes::from_static(b"s:1"), Bytes::from_static(b"v"));
+    art.set_ttl(
+        Bytes::from_static(b"s:2"),
+        Duration::from_secs(1),
+        Bytes::from_static(b"v"),
+    );
+    assert_eq!(art.count_matching(b"s:*"), 2);
+    art.set_now(200);
+    assert_eq!(art.count_matching(b"s:*"), 1);
+}
+
+// ============ Tests builder ============
+
+#[test]
+fn test_builder() {
+    let mut art = OxidArtBuilder::new().capacity(16).build();
+    assert!(art.map.capacity() >= 16);
+    for i in 0..100u32 {
+        art.set(Bytes::from(format!("key:{i}")), Bytes::from_static(b"v"));
+    }
+    assert_eq!(art.getn(Bytes::from_static(b"")).len(), 100);
+
+    let mut bounded = OxidArt::builder().max_entries(Some(2)).build();
+    bounded.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+    bounded.set(Bytes::from_static(b"b"), Bytes::from_static(b"2"));
+    bounded.set(Bytes::from_static(b"c"), Bytes::from_static(b"3"));
+    assert_eq!(bounded.get(Bytes::from_static(b"a")), None);
+    assert_eq!(bounded.getn(Bytes::from_static(b"")).len(), 2);
+
+    let mut unbounded = OxidArt::builder()
+        .max_entries(Some(1))
+        .max_entries(None)
+        .build();
+    unbounded.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+    unbounded.set(Bytes::from_static(b"b"), Bytes::from_static(b"2"));
+    assert_eq!(unbounded.getn(Bytes::from_static(b"")).len(), 2);
+}
+
+// ============ Tests grown childs recompression ============
+
+/// "p" holds 5 children ('0'..='3' and 'A'), one more than fit inline
+fn overflowed_node() -> OxidArt {
+    let mut art = OxidArt::new();
+    for c in (b'0'..=b'3').chain([b'A']) {
+        art.set(
+            Bytes::copy_from_slice(&[b'p', c, b'x']),
+            Bytes::from_static(b"v"),
+        );
+    }
+    art.set(Bytes::from_static(b"q"), Bytes::from_static(b"v"));
+    assert_eq!(art.memory_stats().grown_child_nodes, 1);
+    art
+}
+
+#[test]
+fn test_recompress_single_child_in_grown_childs() {
+    let mut art = overflowed_node();
+    for c in b'0'..=b'3' {
+        art.del(Bytes::copy_from_slice(&[b'p', c, b'x']));
+    }
+
+    // "p" -> 'A' -> "x" collapsed into a single "pAx" leaf
+    art.verify_invariants().unwrap();
+    assert_eq!(art.memory_stats().grown_child_nodes, 0);
+    assert_eq!(art.node_count(), 3);
+    assert_eq!(
+        art.get(Bytes::from_static(b"pAx")),
+        Some(Bytes::from_static(b"v"))
+    );
+}
+
+#[test]
+fn test_remove_grown_children_frees_block() {
+    let mut art = overflowed_node();
+    // 4 children would fit inline, but they stay grown until 3 are left
+    art.del(Bytes::from_static(b"pAx"));
+    art.verify_invariants().unwrap();
+    assert_eq!(art.memory_stats().grown_child_nodes, 1);
+    art.del(Bytes::from_static(b"p0x"));
+    assert_eq!(art.memory_stats().grown_child_nodes, 0);
+
+    // Deleting everything under the node leaves no dead node behind
+    for c in b'1'..=b'3' {
+        art.del(Bytes::copy_from_slice(&[b'p', c, b'x']));
+    }
+    art.verify_invariants().unwrap();
+    // Root absorbed its last child "q"
+    assert_eq!(art.node_count(), 1);
+}
+
+#[test]
+fn test_grown_childs_migrate_back_inline() {
+    let mut art = OxidArt::new();
+    for c in b'a'..=b'o' {
+        art.set(Bytes::copy_from_slice(&[b'p', c]), Bytes::from_static(b"v"));
+    }
+    assert_eq!(art.memory_stats().grown_child_nodes, 1);
+
+    // 15 children: they stay grown until only 3 are left
+    for c in b'a'..=b'k' {
+        art.del(Bytes::copy_from_slice(&[b'p', c]));
+        assert_eq!(art.memory_stats().grown_child_nodes, 1);
+    }
+    art.del(Bytes::from_static(b"pl"));
+    assert_eq!(art.memory_stats().grown_child_nodes, 0);
+    art.verify_invariants().unwrap();
+    for c in b'm'..=b'o' {
+        assert_eq!(
+            art.get(Bytes::copy_from_slice(&[b'p', c])),
+            Some(Bytes::from_static(b"v"))
+        );
+    }
+
+    // Growing again fills the inline tier, then a new block
+    art.set(Bytes::from_static(b"pa"), Bytes::from_static(b"v"));
+    assert_eq!(art.memory_stats().grown_child_nodes, 0);
+    art.set(Bytes::from_static(b"pb"), Bytes::from_static(b"v"));
+    assert_eq!(art.memory_stats().grown_child_nodes, 1);
+    art.verify_invariants().unwrap();
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_expired_grown_child_recompresses() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(100);
+    for c in b'0'..=b'9' {
+        art.set_ttl(
+            Bytes::copy_from_slice(&[b'p', c]),
+            Duration::from_secs(1),
+            Bytes::from_static(b"v"),
+        );
+    }
+    art.set(Bytes::from_static(b"pA"), Bytes::from_static(b"v"));
+    art.set(Bytes::from_static(b"q"), Bytes::from_static(b"v"));
+
+    art.set_now(200);
+    for c in b'0'..=b'9' {
+        assert_eq!(art.get(Bytes::copy_from_slice(&[b'p', c])), None);
+    }
+    art.verify_invariants().unwrap();
+    assert_eq!(art.memory_stats().grown_child_nodes, 0);
+}
+
+// ============ Tests getn_multi ============
+
+#[test]
+fn test_getn_multi_matches_getn() {
+    let mut art = OxidArt::new();
+    for host in 0..5u32 {
+        for metric in ["cpu", "mem", "net", "disk:read", "disk:write"] {
+            art.set(
+                Bytes::from(format!("host:{host}:{metric}")),
+                Bytes::from(format!("{host}-{metric}")),
+            );
+        }
+    }
+    art.set(Bytes::from_static(b""), Bytes::from_static(b"root"));
+
+    let prefixes: Vec<Bytes> = [
+        &b"host:1:cpu"[..],
+        b"host:1:disk:",
+        b"host:1:disk:w",
+        b"host:1:",
+        b"host:2:mem",
+        b"host:",
+        b"",
+        b"host:9:",
+        b"ho",
+        b"host:3:disk:read:x",
+        b"host:3:di",
+        b"zzz",
+        b"host:4:net",
+    ]
+    .iter()
+    .map(|p| Bytes::copy_from_slice(p))
+    .collect();
+
+    let mut sorted = prefixes.clone();
+    sorted.sort();
+    for input in [prefixes, sorted] {
+        let results = art.getn_multi(&input);
+        assert_eq!(results.len(), input.len());
+        for (prefix, mut result) in input.iter().zip(results) {
+            let mut expected = art.getn(prefix.clone());
+            result.sort();
+            expected.sort();
+            assert_eq!(result, expected, "prefix {prefix:?}");
+        }
+    }
+
+    assert!(art.getn_multi(&[]).is_empty());
+}
+
+// ============ Tests prefix_node_exists ============
+
+#[test]
+fn test_prefix_node_exists() {
+    let mut art = OxidArt::new();
+    assert!(art.prefix_node_exists(Bytes::new()));
+    assert!(!art.prefix_exists(Bytes::new()));
+    assert!(!art.prefix_node_exists(Bytes::from_static(b"a")));
+
+    art.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"a"));
+    art.set(Bytes::from_static(b"user:2"), Bytes::from_static(b"b"));
+
+    // Value-less intermediate node "user:" and prefixes inside compressions
+    for prefix in [&b"u"[..], b"use", b"user:", b"user:1"] {
+        assert!(art.prefix_node_exists(Bytes::from_static(prefix)));
+    }
+    for prefix in [&b"x"[..], b"usx", b"user:3", b"user:1:"] {
+        assert!(!art.prefix_node_exists(Bytes::from_static(prefix)));
+    }
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_prefix_node_exists_with_expired_value() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(100);
+    art.set_ttl(
+        Bytes::from_static(b"marker"),
+        Duration::from_secs(1),
+        Bytes::from_static(b"v"),
+    );
+    art.set_now(200);
+
+    // The expired node is still there until cleaned up
+    assert!(art.prefix_node_exists(Bytes::from_static(b"mark")));
+    assert!(!art.prefix_exists(Bytes::from_static(b"mark")));
+}
+
+// ============ Tests estimate_prefix_count ============
+
+#[test]
+fn test_estimate_prefix_count_exact_on_uniform_subtree() {
+    let mut art = OxidArt::new();
+    for i in 0..1000 {
+        art.set(
+            Bytes::from(format!("user:{i:03}")),
+            Bytes::from_static(b"x"),
+        );
+    }
+    art.set(Bytes::from_static(b"other"), Bytes::from_static(b"x"));
+
+    assert_eq!(
+        art.estimate_prefix_count(Bytes::from_static(b"user:"), 1),
+        1000
+    );
+    assert_eq!(
+        art.estimate_prefix_count(Bytes::from_static(b"user:4"), 8),
+        100
+    );
+    assert_eq!(
+        art.estimate_prefix_count(Bytes::from_static(b"user:42"), 8),
+        10
+    );
+    assert_eq!(
+        art.estimate_prefix_count(Bytes::from_static(b"us"), 8),
+        1000
+    );
+}
+
+#[test]
+fn test_estimate_prefix_count_edge_cases() {
+    let mut art = OxidArt::new();
+    assert_eq!(art.estimate_prefix_count(Bytes::new(), 10), 0);
+
+    art.set(Bytes::from_static(b"key"), Bytes::from_static(b"x"));
+    assert_eq!(art.estimate_prefix_count(Bytes::from_static(b"key"), 0), 1);
+    assert_eq!(art.estimate_prefix_count(Bytes::from_static(b"ke"), 10), 1);
+    assert_eq!(art.estimate_prefix_count(Bytes::from_static(b"kex"), 10), 0);
+}
+
+#[test]
+fn test_estimate_prefix_count_irregular_subtree() {
+    let mut art = OxidArt::new();
+    // One dense branch and many tiny ones
+    for i in 0..500 {
+        art.set(Bytes::from(format!("k:a{i:03}")), Bytes::from_static(b"x"));
+    }
+    for c in b'b'..=b'z' {
+        art.set(Bytes::from(vec![b'k', b':', c]), Bytes::from_static(b"x"));
+    }
+    let exact = art.getn(Bytes::from_static(b"k:")).len();
+
+    let estimate = art.estimate_prefix_count(Bytes::from_static(b"k:"), 2000);
+    assert!(
+        estimate.abs_diff(exact) * 4 < exact,
+        "estimate {estimate} too far from {exact}"
+    );
+}
+
+// ============ Tests deln_where ============
+
+#[test]
+fn test_deln_where_removes_only_matching() {
+    let mut art = OxidArt::new();
+    for i in 0..200 {
+        let val = if i % 3 == 0 { "expired" } else { "active" };
+        art.set(Bytes::from(format!("user:123:{i}")), Bytes::from(val));
+    }
+    art.set(
+        Bytes::from_static(b"user:124:0"),
+        Bytes::from_static(b"expired"),
+    );
+
+    let count = art.deln_where(Bytes::from_static(b"user:123:"), |key, val| {
+        assert!(key.starts_with(b"user:123:"));
+        val == "expired"
+    });
+    assert_eq!(count, 67);
+    art.verify_invariants().unwrap();
+
+    let left = art.getn(Bytes::from_static(b"user:123:"));
+    assert_eq!(left.len(), 133);
+    assert!(left.iter().all(|(_, val)| val == "active"));
+    assert_eq!(
+        art.get(Bytes::from_static(b"user:124:0")),
+        Some(Bytes::from_static(b"expired"))
+    );
+}
+
+#[test]
+fn test_deln_where_recompresses() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"a"), Bytes::from_static(b"x"));
+    art.set(Bytes::from_static(b"ab"), Bytes::from_static(b"drop"));
+    art.set(Bytes::from_static(b"abc"), Bytes::from_static(b"x"));
+    art.set(Bytes::from_static(b"abd"), Bytes::from_static(b"drop"));
+    art.set(Bytes::from_static(b"b"), Bytes::from_static(b"drop"));
+
+    // Whole tree, with the keys passed to the predicate
+    let mut seen = Vec::new();
+    let count = art.deln_where(Bytes::new(), |key, val| {
+        seen.push(key.to_vec());
+        val == "drop"
+    });
+    assert_eq!(count, 3);
+    seen.sort();
+    assert_eq!(
+        seen,
+        vec![
+            b"a".to_vec(),
+            b"ab".to_vec(),
+            b"abc".to_vec(),
+            b"abd".to_vec(),
+            b"b".to_vec()
+        ]
+    );
+    art.verify_invariants().unwrap();
+    assert_eq!(art.node_count(), 2);
+
+    // Prefix ending inside a compression, removing the target itself
+    let count = art.deln_where(Bytes::from_static(b"ab"), |_, _| true);
+    assert_eq!(count, 1);
+    art.verify_invariants().unwrap();
+    assert_eq!(art.node_count(), 1);
+    assert_eq!(
+        art.get(Bytes::from_static(b"a")),
+        Some(Bytes::from_static(b"x"))
+    );
+
+    assert_eq!(art.deln_where(Bytes::new(), |_, _| true), 1);
+    art.verify_invariants().unwrap();
+    assert!(art.getn(Bytes::new()).is_empty());
+    assert_eq!(art.deln_where(Bytes::from_static(b"zz"), |_, _| true), 0);
+}
+
+#[test]
+fn test_deln_where_matches_del() {
+    let mut art = OxidArt::new();
+    let mut expected = OxidArt::new();
+    for i in 0..500u32 {
+        let key = Bytes::from(format!("k{}", i.wrapping_mul(2_654_435_761) % 1000));
+        let val = Bytes::from(format!("{}", i % 7));
+        art.set(key.clone(), val.clone());
+        expected.set(key, val);
+    }
+    for (key, val) in expected.getn(Bytes::from_static(b"k1")) {
+        if val == "3" || val == "5" {
+            expected.del(key);
+        }
+    }
+
+    art.deln_where(Bytes::from_static(b"k1"), |_, val| val == "3" || val == "5");
+    art.verify_invariants().unwrap();
+    let mut got = art.getn(Bytes::new());
+    let mut want = expected.getn(Bytes::new());
+    got.sort();
+    want.sort();
+    assert_eq!(got, want);
+}
+
+// ============ Tests first_n / last_n ============
+
+#[test]
+fn test_first_n_last_n() {
+    let mut art = OxidArt::new();
+    let keys: &[&[u8]] = &[b"", b"b", b"a", b"ab", b"abc", b"ba", b"c", b"aa"];
+    for key in keys {
+        art.set(Bytes::copy_from_slice(key), Bytes::copy_from_slice(key));
+    }
+    let mut sorted: Vec<Bytes> = keys.iter().map(|k| Bytes::copy_from_slice(k)).collect();
+    sorted.sort();
+
+    let first: Vec<Bytes> = art.first_n(3).into_iter().map(|(k, _)| k).collect();
+    assert_eq!(first, sorted[..3]);
+    let last: Vec<Bytes> = art.last_n(3).into_iter().map(|(k, _)| k).collect();
+    let mut expected_last = sorted[sorted.len() - 3..].to_vec();
+    expected_last.reverse();
+    assert_eq!(last, expected_last);
+
+    // Fewer entries than requested: everything is returned
+    let all: Vec<Bytes> = art.first_n(100).into_iter().map(|(k, _)| k).collect();
+    assert_eq!(all, sorted);
+    assert_eq!(art.last_n(100).len(), sorted.len());
+
+    assert!(art.first_n(0).is_empty());
+    assert!(art.last_n(0).is_empty());
+    assert!(OxidArt::new().first_n(5).is_empty());
+}
+
+#[test]
+fn test_first_n_with_root_compression() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"key:2"), Bytes::from_static(b"2"));
+    art.set(Bytes::from_static(b"key:1"), Bytes::from_static(b"1"));
+    art.del(Bytes::from_static(b"key:2"));
+    art.set(Bytes::from_static(b"key:10"), Bytes::from_static(b"10"));
+
+    let first = art.first_n(2);
+    assert_eq!(
+        first[0],
+        (Bytes::from_static(b"key:1"), Bytes::from_static(b"1"))
+    );
+    assert_eq!(
+        first[1],
+        (Bytes::from_static(b"key:10"), Bytes::from_static(b"10"))
+    );
+}
+
+// ============ Tests value interning ============
+
+#[test]
+fn test_value_interning_shares_buffers() {
+    let mut art = OxidArt::with_value_interning();
+    for i in 0..10 {
+        let state = if i % 2 == 0 { "active" } else { "inactive" };
+        art.set(
+            Bytes::from(format!("user:{i}")),
+            Bytes::from(state.to_string()),
+        );
+    }
+
+    let active = art.get(Bytes::from_static(b"user:0")).unwrap();
+    let inactive = art.get(Bytes::from_static(b"user:1")).unwrap();
+    for (key, val) in art.getn(Bytes::from_static(b"user:")) {
+        let shared = if val == "active" { &active } else { &inactive };
+        assert_eq!(val.as_ptr(), shared.as_ptr(), "{key:?} is not shared");
+    }
+    assert_ne!(active.as_ptr(), inactive.as_ptr());
+
+    // TTL writes go through the pool as well
+    #[cfg(feature = "ttl")]
+    {
+        art.set_ttl(
+            Bytes::from_static(b"session"),
+            std::time::Duration::from_secs(10),
+            Bytes::from("active".to_string()),
+        );
+        let val = art.get(Bytes::from_static(b"session")).unwrap();
+        assert_eq!(val.as_ptr(), active.as_ptr());
+    }
+}
+
+#[test]
+fn test_value_interning_disabled_by_default() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"a"), Bytes::from("same".to_string()));
+    art.set(Bytes::from_static(b"b"), Bytes::from("same".to_string()));
+    let a = art.get(Bytes::from_static(b"a")).unwrap();
+    let b = art.get(Bytes::from_static(b"b")).unwrap();
+    assert_ne!(a.as_ptr(), b.as_ptr());
+
+    let mut art = OxidArtBuilder::new().value_interning(true).build();
+    art.set(Bytes::from_static(b"a"), Bytes::from("same".to_string()));
+    art.set(Bytes::from_static(b"b"), Bytes::from("same".to_string()));
+    let a = art.get(Bytes::from_static(b"a")).unwrap();
+    let b = art.get(Bytes::from_static(b"b")).unwrap();
+    assert_eq!(a.as_ptr(), b.as_ptr());
+
+    // A shared value can still be appended to, without touching the other key
+    art.append(Bytes::from_static(b"a"), b"!");
+    assert_eq!(
+        art.get(Bytes::from_static(b"a")),
+        Some(Bytes::from_static(b"same!"))
+    );
+    assert_eq!(
+        art.get(Bytes::from_static(b"b")),
+        Some(Bytes::from_static(b"same"))
+    );
+}
+
+// ============ Tests reserve ============
+
+#[test]
+fn test_reserve_grows_slab() {
+    let mut art = OxidArt::new();
+    for i in 0..100 {
+        art.set(Bytes::from(format!("key:{i}")), Bytes::from_static(b"v"));
+    }
+    art.reserve(10_000);
+    let capacity = art.map.capacity();
+    assert!(capacity >= art.node_count() + 10_000);
+
+    // Filling the reservation does not reallocate
+    for i in 100..5000 {
+        art.set(Bytes::from(format!("key:{i}")), Bytes::from_static(b"v"));
+    }
+    assert_eq!(art.map.capacity(), capacity);
+    assert_eq!(art.getn(Bytes::from_static(b"key:")).len(), 5000);
+}
+
+// ============ Tests get_ancestors ============
+
+#[test]
+fn test_get_ancestors() {
+    let mut art = OxidArt::new();
+    for key in [&b"a"[..], b"a/b", b"a/b/c", b"a/x", b"a/b/cd"] {
+        art.set(Bytes::copy_from_slice(key), Bytes::copy_from_slice(key));
+    }
+
+    let keys = |art: &OxidArt, key: &'static [u8]| -> Vec<Bytes> {
+        art.get_ancestors(Bytes::from_static(key))
+            .into_iter()
+            .map(|(k, v)| {
+                assert_eq!(k, v);
+                k
+            })
+            .collect()
+    };
+    assert_eq!(keys(&art, b"a/b/c"), vec!["a", "a/b", "a/b/c"]);
+    assert_eq!(keys(&art, b"a/b/c/d"), vec!["a", "a/b", "a/b/c"]);
+    // "a/b/cd" is not a prefix of "a/b/ce"
+    assert_eq!(keys(&art, b"a/b/ce"), vec!["a", "a/b", "a/b/c"]);
+    assert_eq!(keys(&art, b"a/bz"), vec!["a", "a/b"]);
+    assert_eq!(keys(&art, b"a/c"), vec!["a"]);
+    // The query ends inside a compression
+    assert_eq!(keys(&art, b"a/"), vec!["a"]);
+    assert!(keys(&art, b"b").is_empty());
+    assert!(keys(&art, b"").is_empty());
+
+    art.set(Bytes::new(), Bytes::new());
+    assert_eq!(keys(&art, b"a/x"), vec!["", "a", "a/x"]);
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_get_ancestors_skips_expired() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(100);
+    art.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+    art.set_ttl(
+        Bytes::from_static(b"a/b"),
+        Duration::from_secs(5),
+        Bytes::from_static(b"2"),
+    );
+    art.set(Bytes::from_static(b"a/b/c"), Bytes::from_static(b"3"));
+    assert_eq!(art.get_ancestors(Bytes::from_static(b"a/b/c")).len(), 3);
+
+    art.set_now(200);
+    let chain = art.get_ancestors(Bytes::from_static(b"a/b/c"));
+    assert_eq!(chain.len(), 2);
+    assert_eq!(chain[1].0, Bytes::from_static(b"a/b/c"));
+}
+
+// ============ Tests truncate_to ============
+
+#[test]
+fn test_truncate_to_keeps_smallest_keys() {
+    let mut art = OxidArt::new();
+    for i in 0..50 {
+        art.set(
+            Bytes::from(format!("prio:{i:02}")),
+            Bytes::from_static(b"v"),
+        );
+    }
+
+    assert_eq!(art.truncate_to(50), 0);
+    assert_eq!(art.truncate_to(100), 0);
+    assert_eq!(art.truncate_to(10), 40);
+    art.verify_invariants().unwrap();
+
+    let keys: Vec<Bytes> = art.first_n(100).into_iter().map(|(k, _)| k).collect();
+    let expected: Vec<Bytes> = (0..10)
+        .map(|i| Bytes::from(format!("prio:{i:02}")))
+        .collect();
+    assert_eq!(keys, expected);
+
+    assert_eq!(art.truncate_to(0), 10);
+    art.verify_invariants().unwrap();
+    assert!(art.first_n(1).is_empty());
+    assert_eq!(art.node_count(), 1);
+}
+
+// ============ Tests value_len ============
+
+#[test]
+fn test_value_len() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    art.set(Bytes::from_static(b"user:2"), Bytes::from_static(b""));
+    art.set(Bytes::from_static(b"user:10"), Bytes::from(vec![0u8; 4096]));
+
+    assert_eq!(art.value_len(Bytes::from_static(b"user:1")), Some(5));
+    assert_eq!(art.value_len(Bytes::from_static(b"user:2")), Some(0));
+    assert_eq!(art.value_len(Bytes::from_static(b"user:10")), Some(4096));
+    // Structural node without value, and missing keys
+    assert_eq!(art.value_len(Bytes::from_static(b"user:")), None);
+    assert_eq!(art.value_len(Bytes::from_static(b"user:3")), None);
+    assert_eq!(art.value_len(Bytes::new()), None);
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_value_len_expired() {
+    let mut art = OxidArt::new();
+    art.set_now(10);
+    art.set_ttl(
+        Bytes::from_static(b"tmp"),
+        std::time::Duration::from_secs(1),
+        Bytes::from_static(b"abc"),
+    );
+    assert_eq!(art.value_len(Bytes::from_static(b"tmp")), Some(3));
+    art.set_now(20);
+    assert_eq!(art.value_len(Bytes::from_static(b"tmp")), None);
+}
+
+// ============ Tests chain recompression ============
+
+#[test]
+fn test_delete_collapses_chain() {
+    let mut art = OxidArt::new();
+    for key in [&b"a"[..], b"ab", b"abc", b"abcd"] {
+        art.set(Bytes::copy_from_slice(key), Bytes::copy_from_slice(key));
+    }
+    for key in [&b"ab"[..], b"abc", b"abcd"] {
+        art.del(Bytes::copy_from_slice(key));
+        art.verify_invariants().unwrap();
+    }
+    // Only the root and "a" are left
+    assert_eq!(art.node_count(), 2);
+    assert_eq!(
+        art.get(Bytes::from_static(b"a")),
+        Some(Bytes::from_static(b"a"))
+    );
+}
+
+#[test]
+fn test_recompress_absorbs_whole_chain() {
+    let mut art = OxidArt::new();
+    for key in [&b"x"[..], b"a", b"ab", b"abc", b"abcd"] {
+        art.set(Bytes::copy_from_slice(key), Bytes::copy_from_slice(key));
+    }
+    // Turn "ab" and "abc" into a value-less single-child chain below "a"
+    for key in [&b"ab"[..], b"abc"] {
+        let idx = art.find_key_node(key).unwrap();
+        art.get_node_mut(idx).val = None;
+        art.len -= 1;
+    }
+    assert!(art.verify_invariants().is_err());
+
+    // Deleting "a" collapses "a" -> "ab" -> "abc" -> "abcd" into one node
+    art.del(Bytes::from_static(b"a"));
+    art.verify_invariants().unwrap();
+    assert_eq!(art.node_count(), 3);
+    assert_eq!(
+        art.get(Bytes::from_static(b"abcd")),
+        Some(Bytes::from_static(b"abcd"))
+    );
+    assert_eq!(art.get(Bytes::from_static(b"ab")), None);
+}
+
+// ============ Tests node_info ============
+
+#[test]
+fn test_node_info() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"a"));
+    art.set(Bytes::from_static(b"user:2"), Bytes::from_static(b"b"));
+    art.set(Bytes::from_static(b"user:10"), Bytes::from_static(b"c"));
+
+    assert_eq!(
+        art.node_info(Bytes::new()),
+        Some(NodeInfo {
+            compression: Vec::new(),
+            has_value: false,
+            child_radixes: b"u".to_vec(),
+        })
+    );
+    assert_eq!(
+        art.node_info(Bytes::from_static(b"user:")),
+        Some(NodeInfo {
+            compression: b"ser:".to_vec(),
+            has_value: false,
+            child_radixes: b"12".to_vec(),
+        })
+    );
+    let leaf = art.node_info(Bytes::from_static(b"user:1")).unwrap();
+    assert!(leaf.has_value);
+    assert!(leaf.compression.is_empty());
+    assert_eq!(leaf.child_radixes, b"0");
+
+    // Inside a compression, or past the tree
+    assert_eq!(art.node_info(Bytes::from_static(b"use")), None);
+    assert_eq!(art.node_info(Bytes::from_static(b"user:3")), None);
+}
+
+#[test]
+fn test_node_info_sees_grown_childs() {
+    let mut art = OxidArt::new();
+    for c in b'a'..=b'z' {
+        art.set(Bytes::from(vec![b'k', c]), Bytes::from_static(b"v"));
+    }
+    let info = art.node_info(Bytes::from_static(b"k")).unwrap();
+    assert_eq!(info.child_radixes, (b'a'..=b'z').collect::<Vec<u8>>());
+}
+
+// ============ Tests getn_depth ============
+
+fn depth_keys(entries: Vec<(Bytes, Bytes)>) -> Vec<Bytes> {
+    let keys: Vec<Bytes> = entries.into_iter().map(|(k, _)| k).collect();
+    assert!(keys.is_sorted(), "entries out of key order: {keys:?}");
+    keys
+}
+
+#[test]
+fn test_getn_depth_counts_bytes() {
+    let mut art = OxidArt::new();
+    for key in [&b"id:"[..], b"id:7", b"id:42", b"id:123", b"ids"] {
+        art.set(Bytes::copy_from_slice(key), Bytes::from_static(b"v"));
+    }
+
+    assert_eq!(
+        depth_keys(art.getn_depth(Bytes::from_static(b"id:"), 0)),
+        vec!["id:"]
+    );
+    assert_eq!(
+        depth_keys(art.getn_depth(Bytes::from_static(b"id:"), 2)),
+        vec!["id:", "id:42", "id:7"]
+    );
+    assert_eq!(art.getn_depth(Bytes::from_static(b"id:"), 10).len(), 4);
+    // Prefix ending inside a compression
+    assert_eq!(
+        depth_keys(art.getn_depth(Bytes::from_static(b"id:1"), 1)),
+        Vec::<Bytes>::new()
+    );
+    assert_eq!(
+        depth_keys(art.getn_depth(Bytes::from_static(b"id:1"), 2)),
+        vec!["id:123"]
+    );
+    assert_eq!(
+        depth_keys(art.getn_depth(Bytes::from_static(b"i"), 2)),
+        vec!["id:", "ids"]
+    );
+    assert!(art.getn_depth(Bytes::from_static(b"x"), 5).is_empty());
+}
+
+#[test]
+fn test_getn_depth_key_order() {
+    let mut art = OxidArt::new();
+    for key in [&b""[..], b"b", b"\0", b"ba", b"\0\0\0"] {
+        art.set(Bytes::copy_from_slice(key), Bytes::from_static(b"v"));
+    }
+    assert_eq!(
+        depth_keys(art.getn_depth(Bytes::new(), 2)),
+        vec![&b""[..], b"\0", b"b", b"ba"]
+    );
+    assert_eq!(
+        depth_keys(art.getn_depth_by(Bytes::new(), b'a', 1)),
+        vec![&b""[..], b"\0", b"\0\0\0", b"b"]
+    );
+}
+
+#[test]
+fn test_getn_depth_by_separator() {
+    let mut art = OxidArt::new();
+    for key in [
+        &b"dir/"[..],
+        b"dir/a",
+        b"dir/a/",
+        b"dir/a/b",
+        b"dir/a/b/c",
+        b"dir/long-name",
+        b"dir/x/y",
+        b"dirt",
+    ] {
+        art.set(Bytes::copy_from_slice(key), Bytes::from_static(b"v"));
+    }
+    let listing = |depth| depth_keys(art.getn_depth_by(Bytes::from_static(b"dir/"), b'/', depth));
+
+    assert_eq!(listing(0), vec!["dir/"]);
+    assert_eq!(listing(1), vec!["dir/", "dir/a", "dir/long-name"]);
+    assert_eq!(
+        listing(2),
+        vec![
+            "dir/",
+            "dir/a",
+            "dir/a/",
+            "dir/a/b",
+            "dir/long-name",
+            "dir/x/y"
+        ]
+    );
+    assert_eq!(listing(3).len(), 7);
+}
+
+// ============ Tests children_segments ============
+
+#[test]
+fn test_children_segments() {
+    let mut art = OxidArt::new();
+    for key in [
+        &b"a"[..],
+        b"a/",
+        b"a/b",
+        b"a/b/c",
+        b"a/b/d/e",
+        b"a/bc/x",
+        b"a/f",
+        b"a//g",
+        b"ab/z",
+        b"top",
+    ] {
+        art.set(Bytes::copy_from_slice(key), Bytes::from_static(b"v"));
+    }
+    let segments = |prefix: &'static [u8]| -> Vec<Bytes> {
+        art.children_segments(Bytes::from_static(prefix), b'/')
+    };
+
+    assert_eq!(segments(b"a/"), vec!["", "b", "bc", "f"]);
+    // Not separator-terminated: listed as "a/", "ab/z" is not under it
+    assert_eq!(segments(b"a"), segments(b"a/"));
+    assert_eq!(segments(b"a/b"), vec!["c", "d"]);
+    assert_eq!(segments(b""), vec!["a", "ab", "top"]);
+    assert!(segments(b"a/f").is_empty());
+    assert!(segments(b"missing").is_empty());
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_children_segments_skips_expired() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(100);
+    art.set(Bytes::from_static(b"d/live/1"), Bytes::from_static(b"v"));
+    art.set_ttl(
+        Bytes::from_static(b"d/gone/1"),
+        Duration::from_secs(1),
+        Bytes::from_static(b"v"),
+    );
+    art.set_ttl(
+        Bytes::from_static(b"d/file"),
+        Duration::from_secs(1),
+        Bytes::from_static(b"v"),
+    );
+    assert_eq!(
+        art.children_segments(Bytes::from_static(b"d"), b'/').len(),
+        3
+    );
+
+    art.set_now(200);
+    assert_eq!(
+        art.children_segments(Bytes::from_static(b"d"), b'/'),
+        vec!["live"]
+    );
+}
+
+// ============ Tests set_observed ============
+
+#[test]
+fn test_set_observed() {
+    let mut art = OxidArt::new();
+    let mut seen = Vec::new();
+
+    art.set_observed(
+        Bytes::from_static(b"user:1"),
+        Bytes::from_static(b"a"),
+        |prev| seen.push(prev),
+    );
+    art.set_observed(
+        Bytes::from_static(b"user:1"),
+        Bytes::from_static(b"b"),
+        |prev| seen.push(prev),
+    );
+    // Fresh insert on a split, and on a value-less intermediate node
+    art.set_observed(
+        Bytes::from_static(b"user:2"),
+        Bytes::from_static(b"c"),
+        |prev| seen.push(prev),
+    );
+    art.set_observed(
+        Bytes::from_static(b"user:"),
+        Bytes::from_static(b"d"),
+        |prev| seen.push(prev),
+    );
+    art.set_observed(
+        Bytes::from_static(b"us"),
+        Bytes::from_static(b"e"),
+        |prev| seen.push(prev),
+    );
+
+    assert_eq!(
+        seen,
+        vec![None, Some(Bytes::from_static(b"a")), None, None, None]
+    );
+    assert_eq!(
+        art.get(Bytes::from_static(b"user:1")),
+        Some(Bytes::from_static(b"b"))
+    );
+    assert_eq!(
+        art.get(Bytes::from_static(b"us")),
+        Some(Bytes::from_static(b"e"))
+    );
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_set_observed_expired_previous() {
+    let mut art = OxidArt::new();
+    art.set_now(10);
+    art.set_ttl(
+        Bytes::from_static(b"k"),
+        std::time::Duration::from_secs(1),
+        Bytes::from_static(b"old"),
+    );
+    art.set_now(20);
+
+    let mut prev = Some(Bytes::new());
+    art.set_observed(Bytes::from_static(b"k"), Bytes::from_static(b"new"), |p| {
+        prev = p
+    });
+    assert_eq!(prev, None);
+}
+
+// ============ Tests randomized against BTreeMap ============
+
+/// Applies seeded random `set`/`del`/`getn`/`deln` to the tree and a `BTreeMap`,
+/// checking after each operation that they agree and the invariants hold
+fn check_against_btree_map(seed: u64, alphabet: u8, steps: usize) {
+    use std::collections::BTreeMap;
+
+    let mut state = seed;
+    let mut rnd = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    let mut art = OxidArt::new();
+    let mut model: BTreeMap<Vec<u8>, Bytes> = BTreeMap::new();
+    let in_prefix = |model: &BTreeMap<Vec<u8>, Bytes>, prefix: &[u8]| -> Vec<(Bytes, Bytes)> {
+        model
+            .iter()
+            .filter(|(key, _)| key.starts_with(prefix))
+            .map(|(key, val)| (Bytes::from(key.clone()), val.clone()))
+            .collect()
+    };
+
+    for step in 0..steps {
+        let len = (rnd() % 6) as usize;
+        let key: Vec<u8> = (0..len)
+            .map(|_| b'a' + (rnd() % alphabet as u64) as u8)
+            .collect();
+        let op = rnd() % 10;
+        let context = format!("seed {seed} step {step} op {op} key {key:?}");
+
+        match op {
+            0..=4 => {
+                let val = Bytes::from(format!("{step}"));
+                art.set(Bytes::from(key.clone()), val.clone());
+                model.insert(key.clone(), val);
+            }
+            5..=6 => {
+                assert_eq!(
+                    art.del(Bytes::from(key.clone())),
+                    model.remove(&key),
+                    "{context}"
+                );
+            }
+            7 => {
+                let removed = in_prefix(&model, &key);
+                for (k, _) in &removed {
+                    model.remove(k.as_ref());
+                }
+                assert_eq!(
+                    art.deln(Bytes::from(key.clone())),
+                    removed.len(),
+                    "{context}"
+                );
+            }
+            _ => {
+                let mut entries = art.getn(Bytes::from(key.clone()));
+                entries.sort();
+                assert_eq!(entries, in_prefix(&model, &key), "{context}");
+            }
+        }
+
+        if let Err(e) = art.verify_invariants() {
+            panic!("{context}: {e}");
+        }
+        assert_eq!(
+            art.get(Bytes::from(key.clone())),
+            model.get(&key).cloned(),
+            "{context}"
+        );
+        let mut all = art.getn(Bytes::new());
+        all.sort();
+        assert_eq!(all, in_prefix(&model, b""), "{context}");
+    }
+}
+
+#[test]
+fn test_randomized_small_alphabet() {
+    // Few distinct bytes: many shared prefixes, splits and recompressions
+    for seed in 1..=40 {
+        check_against_btree_map(seed * 0x9E37_79B9, 2, 300);
+    }
+}
+
+#[test]
+fn test_randomized_wide_alphabet() {
+    // Many distinct bytes: nodes grow past the inline tier
+    for seed in 1..=20 {
+        check_against_btree_map(seed * 0x85EB_CA6B, 26, 500);
+    }
+}
+
+// ============ Tests prefix_min / prefix_max ============
+
+#[test]
+fn test_prefix_min_max() {
+    let mut art = OxidArt::new();
+    for key in [
+        &b"series:1:"[..],
+        b"series:1:100",
+        b"series:1:099",
+        b"series:1:2",
+        b"series:2:000",
+        b"series:10:5",
+    ] {
+        art.set(Bytes::copy_from_slice(key), Bytes::copy_from_slice(key));
+    }
+    let min = |prefix: &'static [u8]| art.prefix_min(Bytes::from_static(prefix)).map(|(k, _)| k);
+    let max = |prefix: &'static [u8]| art.prefix_max(Bytes::from_static(prefix)).map(|(k, _)| k);
+
+    // The prefix node's own value is the minimum
+    assert_eq!(min(b"series:1:"), Some(Bytes::from_static(b"series:1:")));
+    assert_eq!(max(b"series:1:"), Some(Bytes::from_static(b"series:1:2")));
+    assert_eq!(
+        min(b"series:1:0"),
+        Some(Bytes::from_static(b"series:1:099"))
+    );
+    // Prefix ending inside a compression
+    assert_eq!(min(b"series:2"), Some(Bytes::from_static(b"series:2:000")));
+    assert_eq!(
+        max(b"series:2:0"),
+        Some(Bytes::from_static(b"series:2:000"))
+    );
+    // b'0' sorts before b':'
+    assert_eq!(min(b""), Some(Bytes::from_static(b"series:10:5")));
+    assert_eq!(max(b""), Some(Bytes::from_static(b"series:2:000")));
+    assert_eq!(max(b"series:10"), Some(Bytes::from_static(b"series:10:5")));
+    assert_eq!(min(b"series:3"), None);
+    assert_eq!(max(b"x"), None);
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_prefix_min_skips_expired() {
+    let mut art = OxidArt::new();
+    art.set_now(10);
+    art.set_ttl(
+        Bytes::from_static(b"ts:1"),
+        std::time::Duration::from_secs(1),
+        Bytes::from_static(b"old"),
+    );
+    art.set(Bytes::from_static(b"ts:2"), Bytes::from_static(b"new"));
+    art.set_now(20);
+
+    let (key, _) = art.prefix_min(Bytes::from_static(b"ts:")).unwrap();
+    assert_eq!(key, Bytes::from_static(b"ts:2"));
+}
+
+// ============ Tests BTreeMap conversions ============
+
+#[test]
+fn test_btree_map_round_trip() {
+    use std::collections::BTreeMap;
+
+    let mut map: BTreeMap<Vec<u8>, Bytes> = BTreeMap::new();
+    for i in 0..300u32 {
+        let key = format!("k:{}", i.wrapping_mul(2_654_435_761) % 1000).into_bytes();
+        map.insert(key, Bytes::from(format!("{i}")));
+    }
+    map.insert(Vec::new(), Bytes::from_static(b"root"));
+
+    let art = OxidArt::from(map.clone());
+    art.verify_invariants().unwrap();
+    assert_eq!(art.to_btree_map(), map);
+
+    // Same contents as an incrementally built tree
+    let mut built = OxidArt::new();
+    for (key, val) in &map {
+        built.set(Bytes::from(key.clone()), val.clone());
+    }
+    assert_eq!(built.to_btree_map(), map);
+
+    assert!(OxidArt::new().to_btree_map().is_empty());
+    let empty = OxidArt::from(BTreeMap::new());
+    assert!(empty.getn(Bytes::new()).is_empty());
+}
+
+// ============ Tests metadata ============
+
+#[cfg(feature = "metadata")]
+#[test]
+fn test_meta_preserved_across_overwrites() {
+    let mut art = OxidArt::new();
+    assert!(!art.set_meta(Bytes::from_static(b"doc"), 1));
+    assert_eq!(art.get_meta(Bytes::from_static(b"doc")), None);
+
+    art.set(Bytes::from_static(b"doc"), Bytes::from_static(b"v1"));
+    assert_eq!(art.get_meta(Bytes::from_static(b"doc")), Some(0));
+    assert!(art.set_meta(Bytes::from_static(b"doc"), 42));
+    art.set(Bytes::from_static(b"doc"), Bytes::from_static(b"v2"));
+    art.append(Bytes::from_static(b"doc"), b"!");
+    assert_eq!(art.get_meta(Bytes::from_static(b"doc")), Some(42));
+
+    // Splits and recompression move the metadata with the value
+    art.set(Bytes::from_static(b"do"), Bytes::from_static(b"x"));
+    art.set(Bytes::from_static(b"dog"), Bytes::from_static(b"y"));
+    assert_eq!(art.get_meta(Bytes::from_static(b"doc")), Some(42));
+    assert_eq!(art.get_meta(Bytes::from_static(b"do")), Some(0));
+    art.set_meta(Bytes::from_static(b"do"), 5);
+    art.del(Bytes::from_static(b"dog"));
+    art.del(Bytes::from_static(b"do"));
+    assert_eq!(art.get_meta(Bytes::from_static(b"doc")), Some(42));
+
+    // Structural nodes have no metadata
+    assert!(!art.set_meta(Bytes::from_static(b"d"), 1));
+}
+
+#[cfg(feature = "metadata")]
+#[test]
+fn test_meta_dropped_with_entry() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+    art.set(Bytes::from_static(b"ab"), Bytes::from_static(b"2"));
+    art.set(Bytes::from_static(b"ac"), Bytes::from_static(b"3"));
+    art.set_meta(Bytes::from_static(b"a"), 9);
+
+    // "a" keeps its node, which must not hand the metadata to a new value
+    art.del(Bytes::from_static(b"a"));
+    art.set(Bytes::from_static(b"a"), Bytes::from_static(b"new"));
+    assert_eq!(art.get_meta(Bytes::from_static(b"a")), Some(0));
+
+    art.set_meta(Bytes::from_static(b"ab"), 3);
+    art.deln_where(Bytes::from_static(b"ab"), |_, _| true);
+    art.set(Bytes::from_static(b"ab"), Bytes::from_static(b"again"));
+    assert_eq!(art.get_meta(Bytes::from_static(b"ab")), Some(0));
+}
+
+#[cfg(all(feature = "metadata", feature = "ttl"))]
+#[test]
+fn test_meta_dropped_on_expiry() {
+    let mut art = OxidArt::new();
+    art.set_now(10);
+    art.set_ttl(
+        Bytes::from_static(b"k"),
+        std::time::Duration::from_secs(1),
+        Bytes::from_static(b"v"),
+    );
+    art.set(Bytes::from_static(b"kk"), Bytes::from_static(b"v"));
+    art.set_meta(Bytes::from_static(b"k"), 7);
+
+    art.set_now(20);
+    assert_eq!(art.get_meta(Bytes::from_static(b"k")), None);
+    assert!(!art.set_meta(Bytes::from_static(b"k"), 8));
+    // Overwriting an expired value starts fresh
+    art.set(Bytes::from_static(b"k"), Bytes::from_static(b"w"));
+    assert_eq!(art.get_meta(Bytes::from_static(b"k")), Some(0));
+}
+
+// ============ Tests clear_prefix_values ============
+
+#[test]
+fn test_clear_prefix_values_keeps_structure() {
+    let mut art = OxidArt::new();
+    let keys: Vec<Bytes> = (0..100).map(|i| Bytes::from(format!("slot:{i}"))).collect();
+    for key in &keys {
+        art.set(key.clone(), Bytes::from_static(b"old"));
+    }
+    art.set(Bytes::from_static(b"other"), Bytes::from_static(b"kept"));
+    let nodes = art.node_count();
+
+    assert_eq!(art.clear_prefix_values(Bytes::from_static(b"slot:")), 100);
+    assert_eq!(art.node_count(), nodes);
+    assert!(art.getn(Bytes::from_static(b"slot:")).is_empty());
+    assert_eq!(
+        art.get(Bytes::from_static(b"other")),
+        Some(Bytes::from_static(b"kept"))
+    );
+    assert_eq!(art.clear_prefix_values(Bytes::from_static(b"slot:")), 0);
+
+    // Repopulating creates no node and restores the invariants
+    for key in &keys {
+        art.set(key.clone(), Bytes::from_static(b"new"));
+    }
+    assert_eq!(art.node_count(), nodes);
+    art.verify_invariants().unwrap();
+    assert_eq!(art.getn(Bytes::from_static(b"slot:")).len(), 100);
+}
+
+#[test]
+fn test_clear_prefix_values_then_compact() {
+    let mut art = OxidArt::new();
+    for key in [&b"a"[..], b"ab", b"abc", b"abd", b"b"] {
+        art.set(Bytes::copy_from_slice(key), Bytes::from_static(b"v"));
+    }
+    assert_eq!(art.clear_prefix_values(Bytes::from_static(b"a")), 4);
+    assert!(art.verify_invariants().is_err());
+    assert_eq!(art.get(Bytes::from_static(b"abc")), None);
+
+    art.set(Bytes::from_static(b"abd"), Bytes::from_static(b"back"));
+    art.compact();
+    art.verify_invariants().unwrap();
+    let mut entries = art.getn(Bytes::new());
+    entries.sort();
+    assert_eq!(
+        entries,
+        vec![
+            (Bytes::from_static(b"abd"), Bytes::from_static(b"back")),
+            (Bytes::from_static(b"b"), Bytes::from_static(b"v")),
+        ]
+    );
+}
+
+// ============ Tests closest ============
+
+#[test]
+fn test_closest() {
+    let mut art = OxidArt::new();
+    assert_eq!(art.closest(Bytes::from_static(b"a")), None);
+
+    for key in [
+        &b"config:retries"[..],
+        b"config:timeout",
+        b"config:tls",
+        b"cache",
+        b"zeta",
+    ] {
+        art.set(Bytes::copy_from_slice(key), Bytes::copy_from_slice(key));
+    }
+    let closest = |key: &'static [u8]| art.closest(Bytes::from_static(key)).map(|(k, _)| k);
+
+    // Exact match
+    assert_eq!(closest(b"cache"), Some(Bytes::from_static(b"cache")));
+    // Near misses
+    assert_eq!(
+        closest(b"config:timout"),
+        Some(Bytes::from_static(b"config:timeout"))
+    );
+    assert_eq!(
+        closest(b"config:tlz"),
+        Some(Bytes::from_static(b"config:tls"))
+    );
+    // Ties on "config:t" go to the smallest key
+    assert_eq!(
+        closest(b"config:tx"),
+        Some(Bytes::from_static(b"config:timeout"))
+    );
+    // Diverging inside a compression, or query ending inside one
+    assert_eq!(closest(b"cachx"), Some(Bytes::from_static(b"cache")));
+    assert_eq!(closest(b"con"), Some(Bytes::from_static(b"config:retries")));
+    // Query extending a stored key
+    assert_eq!(closest(b"zetas"), Some(Bytes::from_static(b"zeta")));
+    // Nothing shared: the smallest key overall
+    assert_eq!(closest(b"q"), Some(Bytes::from_static(b"cache")));
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_closest_skips_expired() {
+    let mut art = OxidArt::new();
+    art.set_now(10);
+    art.set_ttl(
+        Bytes::from_static(b"user:alice"),
+        std::time::Duration::from_secs(1),
+        Bytes::from_static(b"a"),
+    );
+    art.set(Bytes::from_static(b"user:bob"), Bytes::from_static(b"b"));
+    art.set(Bytes::from_static(b"admin"), Bytes::from_static(b"c"));
+
+    let (key, _) = art.closest(Bytes::from_static(b"user:alicia")).unwrap();
+    assert_eq!(key, Bytes::from_static(b"user:alice"));
+
+    art.set_now(20);
+    let (key, _) = art.closest(Bytes::from_static(b"user:alicia")).unwrap();
+    assert_eq!(key, Bytes::from_static(b"user:bob"));
+    let (key, _) = art.closest(Bytes::from_static(b"user:alice")).unwrap();
+    assert_eq!(key, Bytes::from_static(b"user:bob"));
+}
+
+// ============ Tests deln_observed ============
+
+#[test]
+fn test_deln_observed_reports_every_removed_entry() {
+    let mut art = OxidArt::new();
+    let mut expected = Vec::new();
+    // Enough siblings to grow past the inline tier
+    for i in 0..40u8 {
+        let key = format!("page:{}", (b'!' + i) as char);
+        art.set(Bytes::from(key.clone()), Bytes::from(format!("v{i}")));
+        expected.push((key.into_bytes(), Bytes::from(format!("v{i}"))));
+    }
+    art.set(Bytes::from_static(b"page:"), Bytes::from_static(b"index"));
+    art.set(
+        Bytes::from_static(b"page:!/deep/er"),
+        Bytes::from_static(b"deep"),
+    );
+    art.set(Bytes::from_static(b"other"), Bytes::from_static(b"kept"));
+    expected.push((b"page:".to_vec(), Bytes::from_static(b"index")));
+    expected.push((b"page:!/deep/er".to_vec(), Bytes::from_static(b"deep")));
+    expected.sort();
+
+    let mut removed = Vec::new();
+    let count = art.deln_observed(Bytes::from_static(b"page"), |key, val| {
+        removed.push((key.to_vec(), val.clone()))
+    });
+    removed.sort();
+
+    assert_eq!(count, expected.len());
+    assert_eq!(removed, expected);
+    assert!(art.getn(Bytes::from_static(b"page")).is_empty());
+    assert_eq!(
+        art.get(Bytes::from_static(b"other")),
+        Some(Bytes::from_static(b"kept"))
+    );
+    art.verify_invariants().unwrap();
+}
+
+#[test]
+fn test_deln_observed_matches_deln() {
+    let keys: &[&[u8]] = &[b"a", b"ab", b"abc", b"abd", b"b", b"ba", b""];
+    for prefix in [&b""[..], b"a", b"ab", b"abc", b"b", b"c", b"abx"] {
+        let mut plain = OxidArt::new();
+        let mut observed = OxidArt::new();
+        for &key in keys {
+            plain.set(Bytes::copy_from_slice(key), Bytes::copy_from_slice(key));
+            observed.set(Bytes::copy_from_slice(key), Bytes::copy_from_slice(key));
+        }
+
+        let mut calls = 0;
+        let count = observed.deln_observed(Bytes::copy_from_slice(prefix), |key, val| {
+            assert!(key.starts_with(prefix));
+            assert_eq!(key, val.as_ref());
+            calls += 1;
+        });
+        assert_eq!(count, plain.deln(Bytes::copy_from_slice(prefix)));
+        assert_eq!(calls, count);
+        assert_eq!(
+            observed.getn(Bytes::new()),
+            plain.getn(Bytes::new()),
+            "prefix {prefix:?}"
+        );
+        observed.verify_invariants().unwrap();
+    }
+}
+
+// ============ Tests fragmentation ============
+
+#[test]
+fn test_fragmentation_reports_collapsible_nodes() {
+    let mut art = OxidArt::new();
+    assert_eq!(
+        art.fragmentation(),
+        Fragmentation {
+            collapsible_nodes: 0,
+            valueless_nodes: 1,
+            total_nodes: 1,
+        }
+    );
+
+    art.set(Bytes::from_static(b"xa"), Bytes::from_static(b"v"));
+    art.set(Bytes::from_static(b"xb"), Bytes::from_static(b"v"));
+    art.set(Bytes::from_static(b"user"), Bytes::from_static(b"1"));
+    art.set(Bytes::from_static(b"uso"), Bytes::from_static(b"2"));
+    // Root, "x", "xa", "xb", "us", "user", "uso"
+    assert_eq!(
+        art.fragmentation(),
+        Fragmentation {
+            collapsible_nodes: 0,
+            valueless_nodes: 3,
+            total_nodes: 7,
+        }
+    );
+
+    // Corrupt a chain by hand: "us" keeps a single child and no value
+    let us_idx = art.find(art.root_idx, b'u').unwrap();
+    let o_idx = art.find(us_idx, b'o').unwrap();
+    art.remove_child(us_idx, b'o');
+    art.map.remove(o_idx as usize);
+    let report = art.fragmentation();
+    assert_eq!(report.collapsible_nodes, 1);
+    assert_eq!(report.total_nodes, art.node_count());
+
+    // "us" is merged into "user"
+    art.compact();
+    assert_eq!(
+        art.fragmentation(),
+        Fragmentation {
+            collapsible_nodes: 0,
+            valueless_nodes: 2,
+            total_nodes: 5,
+        }
+    );
+}
+
+#[test]
+fn test_fragmentation_counts_grown_childs_and_skips_root() {
+    let mut art = OxidArt::new();
+    // A single child under the root is not collapsible
+    art.set(Bytes::from_static(b"k"), Bytes::from_static(b"v"));
+    assert_eq!(art.fragmentation().collapsible_nodes, 0);
+
+    for i in 0..40u8 {
+        art.set(Bytes::from(vec![b'n', b'!' + i]), Bytes::from_static(b"v"));
+    }
+    let report = art.fragmentation();
+    assert_eq!(report.total_nodes, art.node_count());
+    // Root and "n"; "n" has 40 children, in a Node48
+    assert_eq!(report.valueless_nodes, 2);
+    assert_eq!(report.collapsible_nodes, 0);
+}
+
+// ============ Tests handles ============
+
+#[test]
+fn test_handle_survives_unrelated_mutations() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"admin"), Bytes::from_static(b"root"));
+    let handle = art.set_indexed(Bytes::from_static(b"order:42"), Bytes::from_static(b"paid"));
+    for i in 0..50 {
+        art.set(Bytes::from(format!("user:{i}")), Bytes::from_static(b"x"));
+    }
+    art.deln(Bytes::from_static(b"user:"));
+    assert_eq!(art.get_at(handle), Some(&Bytes::from_static(b"paid")));
+
+    // Updates of the key itself keep the handle
+    art.set(
+        Bytes::from_static(b"order:42"),
+        Bytes::from_static(b"shipped"),
+    );
+    assert_eq!(art.get_at(handle), Some(&Bytes::from_static(b"shipped")));
+
+    art.del(Bytes::from_static(b"order:42"));
+    assert_eq!(art.get_at(handle), None);
+}
+
+#[test]
+fn test_handle_stale_after_split_or_reuse() {
+    let mut art = OxidArt::new();
+    let handle: Handle = art.set_indexed(Bytes::from_static(b"abcd"), Bytes::from_static(b"1"));
+
+    // Splitting "abcd" moves its value to a new node, then "ab" takes the old one
+    art.set(Bytes::from_static(b"abx"), Bytes::from_static(b"2"));
+    art.set(Bytes::from_static(b"ab"), Bytes::from_static(b"3"));
+    assert!(art.get_node(handle.index()).val.is_some());
+    assert_eq!(art.get_at(handle), None);
+
+    // A freed slot reused by another key
+    let handle = art.set_indexed(Bytes::from_static(b"zz"), Bytes::from_static(b"4"));
+    art.del(Bytes::from_static(b"zz"));
+    art.set(Bytes::from_static(b"q"), Bytes::from_static(b"5"));
+    assert!(art.try_get_node(handle.index()).is_some());
+    assert_eq!(art.get_at(handle), None);
+
+    // Merging into the parent once it loses its value
+    let handle = art.set_indexed(Bytes::from_static(b"abxy"), Bytes::from_static(b"6"));
+    art.del(Bytes::from_static(b"abx"));
+    assert_eq!(
+        art.get(Bytes::from_static(b"abxy")),
+        Some(Bytes::from_static(b"6"))
+    );
+    assert_eq!(art.get_at(handle), None);
+}
+
+#[test]
+fn test_handle_stale_after_root_key_reset() {
+    // "apple" ends up compressed in the root, which then goes back to the empty key
+    let setup = |art: &mut OxidArt| {
+        art.set(Bytes::from_static(b"apple"), Bytes::from_static(b"1"));
+        art.set(Bytes::from_static(b"banana"), Bytes::from_static(b"2"));
+        art.del(Bytes::from_static(b"banana"));
+        art.set_indexed(Bytes::from_static(b"apple"), Bytes::from_static(b"1"))
+    };
+
+    let mut art = OxidArt::new();
+    let handle = setup(&mut art);
+    art.del(Bytes::from_static(b"apple"));
+    art.set(Bytes::new(), Bytes::from_static(b"EMPTY"));
+    assert_eq!(art.get_at(handle), None);
+
+    let mut art = OxidArt::new();
+    let handle = setup(&mut art);
+    assert_eq!(art.deln(Bytes::from_static(b"ap")), 1);
+    art.set(Bytes::new(), Bytes::from_static(b"EMPTY"));
+    assert_eq!(art.get_at(handle), None);
+
+    let mut art = OxidArt::new();
+    let handle = setup(&mut art);
+    assert_eq!(art.deln_observed(Bytes::from_static(b"ap"), |_, _| {}), 1);
+    art.set(Bytes::new(), Bytes::from_static(b"EMPTY"));
+    assert_eq!(art.get_at(handle), None);
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_handle_skips_expired_value() {
+    let mut art = OxidArt::new();
+    art.set_now(10);
+    let handle = art.set_indexed(Bytes::from_static(b"k"), Bytes::from_static(b"v"));
+    art.set_ttl(
+        Bytes::from_static(b"k"),
+        std::time::Duration::from_secs(5),
+        Bytes::from_static(b"v"),
+    );
+    assert_eq!(art.get_at(handle), Some(&Bytes::from_static(b"v")));
+    art.set_now(20);
+    assert_eq!(art.get_at(handle), None);
+}
+
+// ============ Tests iter ============
+
+#[test]
+fn test_iter_ascending_with_root_and_grown_childs() {
+    let mut art = OxidArt::new();
+    assert_eq!(art.iter().next(), None);
+
+    art.set(Bytes::new(), Bytes::from_static(b"root"));
+    for i in (0..60u8).rev() {
+        art.set(Bytes::from(vec![b'!' + i]), Bytes::from(vec![i]));
+        art.set(Bytes::from(vec![b'!' + i, b'x']), Bytes::from(vec![i]));
+    }
+
+    let mut iter = art.iter();
+    assert_eq!(
+        iter.next(),
+        Some((Bytes::new(), Bytes::from_static(b"root")))
+    );
+    let rest: Vec<(Bytes, Bytes)> = iter.collect();
+    assert_eq!(rest.len(), 120);
+
+    let mut expected = art.getn(Bytes::new());
+    expected.sort();
+    let all: Vec<(Bytes, Bytes)> = art.iter().collect();
+    assert_eq!(all, expected);
+}
+
+#[test]
+fn test_iter_is_lazy() {
+    let mut art = OxidArt::new();
+    for i in 0..1000 {
+        art.set(Bytes::from(format!("k:{i:04}")), Bytes::from_static(b"v"));
+    }
+    let first: Vec<Bytes> = art.iter().take(3).map(|(key, _)| key).collect();
+    assert_eq!(
+        first,
+        vec![
+            Bytes::from_static(b"k:0000"),
+            Bytes::from_static(b"k:0001"),
+            Bytes::from_static(b"k:0002"),
+        ]
+    );
+}
+
+// ============ Tests len ============
+
+#[test]
+fn test_len_tracks_inserts_overwrites_and_deletes() {
+    let mut art = OxidArt::new();
+    assert_eq!(art.len(), 0);
+    assert!(art.is_empty());
+
+    art.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"a"));
+    art.set(Bytes::from_static(b"user:2"), Bytes::from_static(b"b"));
+    art.set(Bytes::from_static(b"user:"), Bytes::from_static(b"split"));
+    art.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"c"));
+    art.set(Bytes::new(), Bytes::from_static(b"root"));
+    assert_eq!(art.len(), 4);
+    assert!(!art.is_empty());
+
+    assert!(art.del(Bytes::from_static(b"user:3")).is_none());
+    art.del(Bytes::from_static(b"user:"));
+    assert_eq!(art.len(), 3);
+
+    assert_eq!(art.deln(Bytes::from_static(b"user:")), 2);
+    assert_eq!(art.len(), 1);
+    art.del(Bytes::new());
+    assert!(art.is_empty());
+}
+
+#[test]
+fn test_len_matches_value_count_after_bulk_operations() {
+    let mut art = OxidArt::new();
+    for i in 0..200 {
+        art.set(
+            Bytes::from(format!("k:{:02}:{}", i % 20, i)),
+            Bytes::from_static(b"v"),
+        );
+    }
+    assert_eq!(art.len(), 200);
+
+    art.deln_where(Bytes::from_static(b"k:0"), |key, _| key.ends_with(b"1"));
+    assert_eq!(art.len(), art.value_count());
+    art.drain_prefix(Bytes::from_static(b"k:1"));
+    assert_eq!(art.len(), art.value_count());
+    let moved = art.split_off_prefix(Bytes::from_static(b"k:05"));
+    assert_eq!(moved.len(), 10);
+    assert_eq!(art.len(), art.value_count());
+    art.verify_invariants().unwrap();
+    // Nodes are kept, only the values go
+    art.clear_prefix_values(Bytes::from_static(b"k:06"));
+    assert_eq!(art.len(), art.value_count());
+
+    let everything = art.split_off_prefix(Bytes::new());
+    assert!(art.is_empty());
+    assert_eq!(everything.len(), everything.value_count());
+
+    let sorted = OxidArt::from_sorted(
+        [&b"a"[..], b"a", b"ab", b"b"]
+            .into_iter()
+            .map(|key| (Bytes::copy_from_slice(key), Bytes::from_static(b"v"))),
+    );
+    assert_eq!(sorted.len(), 3);
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_len_counts_expired_until_cleaned_up() {
+    let mut art = OxidArt::new();
+    art.set_now(10);
+    art.set_ttl(
+        Bytes::from_static(b"session"),
+        std::time::Duration::from_secs(1),
+        Bytes::from_static(b"s"),
+    );
+    art.set(Bytes::from_static(b"sess"), Bytes::from_static(b"kept"));
+    assert_eq!(art.len(), 2);
+
+    art.set_now(20);
+    assert_eq!(art.len(), 2);
+    assert_eq!(art.get(Bytes::from_static(b"session")), None);
+    assert_eq!(art.len(), 1);
+    art.verify_invariants().unwrap();
+}
+
+// ============ Tests 8-bit keys ============
+
+#[test]
+fn test_full_byte_keys() {
+    let mut art = OxidArt::new();
+    let keys: [&[u8]; 8] = [
+        &[0x00],
+        &[0x00, 0x00],
+        &[0x7F],
+        &[0x80],
+        &[0x80, 0xFF, 0x00],
+        &[0xFF],
+        &[0xFF, 0xFF],
+        "clé:été".as_bytes(),
+    ];
+    for key in keys {
+        art.set(Bytes::copy_from_slice(key), Bytes::copy_from_slice(key));
+    }
+    for key in keys {
+        assert_eq!(
+            art.get(Bytes::copy_from_slice(key)),
+            Some(Bytes::copy_from_slice(key))
+        );
+    }
+    art.verify_invariants().unwrap();
+
+    // Bytewise order, as for `[u8]`
+    let mut sorted = keys.to_vec();
+    sorted.sort();
+    let iterated: Vec<Bytes> = art.iter().map(|(key, _)| key).collect();
+    assert_eq!(iterated, sorted);
+
+    assert_eq!(art.getn(Bytes::from_static(&[0x80])).len(), 2);
+    assert_eq!(art.getn(Bytes::from_static(&[0xFF])).len(), 2);
+    assert_eq!(art.getn(Bytes::from_static(&[0x00])).len(), 2);
+    assert_eq!(art.getn(Bytes::from_static("clé".as_bytes())).len(), 1);
+
+    assert_eq!(art.deln(Bytes::from_static(&[0xFF])), 2);
+    assert_eq!(
+        art.del(Bytes::from_static(&[0x00])),
+        Some(Bytes::from_static(&[0x00]))
+    );
+    assert_eq!(art.len(), 5);
+    art.verify_invariants().unwrap();
+}
+
+#[test]
+fn test_every_byte_as_radix() {
+    let mut art = OxidArt::new();
+    for b in (0..=u8::MAX).rev() {
+        art.set(Bytes::from(vec![b'p', b]), Bytes::from(vec![b]));
+    }
+    art.verify_invariants().unwrap();
+    assert_eq!(art.getn(Bytes::from_static(b"p")).len(), 256);
+    for b in 0..=u8::MAX {
+        assert_eq!(
+            art.get(Bytes::from(vec![b'p', b])),
+            Some(Bytes::from(vec![b]))
+        );
+    }
+    for b in (0..=u8::MAX).step_by(2) {
+        art.del(Bytes::from(vec![b'p', b]));
+    }
+    assert_eq!(art.getn(Bytes::from_static(b"p")).len(), 128);
+    art.verify_invariants().unwrap();
+}
+
+// ============ Tests contains_key ============
+
+#[test]
+fn test_contains_key() {
+    let mut art = OxidArt::new();
+    assert!(!art.contains_key(Bytes::new()));
+
+    art.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    art.set(Bytes::from_static(b"user:12"), Bytes::from_static(b"bob"));
+    assert!(art.contains_key(Bytes::from_static(b"user:1")));
+    assert!(art.contains_key(Bytes::from_static(b"user:12")));
+    // Prefixes, extensions and diverging keys
+    assert!(!art.contains_key(Bytes::from_static(b"user:")));
+    assert!(!art.contains_key(Bytes::from_static(b"user:123")));
+    assert!(!art.contains_key(Bytes::from_static(b"user:2")));
+    assert!(!art.contains_key(Bytes::new()));
+
+    art.set(Bytes::new(), Bytes::from_static(b"root"));
+    assert!(art.contains_key(Bytes::new()));
+    art.del(Bytes::from_static(b"user:1"));
+    assert!(!art.contains_key(Bytes::from_static(b"user:1")));
+    assert!(art.contains_key(Bytes::from_static(b"user:12")));
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_contains_key_expired() {
+    let mut art = OxidArt::new();
+    art.set_now(10);
+    art.set_ttl(
+        Bytes::from_static(b"session"),
+        std::time::Duration::from_secs(5),
+        Bytes::from_static(b"s"),
+    );
+    assert!(art.contains_key(Bytes::from_static(b"session")));
+    art.set_now(20);
+    assert!(!art.contains_key(Bytes::from_static(b"session")));
+    // Not cleaned up, unlike `get`
+    assert_eq!(art.len(), 1);
+}
+
+// ============ Tests slice lookups ============
+
+#[test]
+fn test_slice_lookups_match_bytes_lookups() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    art.set(Bytes::from_static(b"user:12"), Bytes::from_static(b"bob"));
+    art.set(Bytes::from_static(b"post:1"), Bytes::from_static(b"hello"));
+
+    let buffer = String::from("GET user:1 HTTP/1.1");
+    let key = &buffer.as_bytes()[4..10];
+    assert_eq!(art.get_slice(key), Some(Bytes::from_static(b"alice")));
+    assert!(art.contains_key_slice(key));
+    assert_eq!(art.get_slice(b"user:"), None);
+    assert!(!art.contains_key_slice(b"user:2"));
+
+    for prefix in [&b""[..], b"user:", b"user:1", b"p", b"x"] {
+        assert_eq!(
+            art.getn_slice(prefix),
+            art.getn(Bytes::copy_from_slice(prefix))
+        );
+    }
+    assert_eq!(art.getn_slice("user:".as_bytes()).len(), 2);
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_get_slice_expired() {
+    let mut art = OxidArt::new();
+    art.set_now(10);
+    art.set_ttl(
+        Bytes::from_static(b"k"),
+        std::time::Duration::from_secs(1),
+        Bytes::from_static(b"v"),
+    );
+    assert_eq!(art.get_slice(b"k"), Some(Bytes::from_static(b"v")));
+    art.set_now(20);
+    assert_eq!(art.get_slice(b"k"), None);
+    assert_eq!(art.len(), 1);
+}
+
+// ============ Tests FromIterator / Extend ============
+
+#[test]
+fn test_collect_and_extend() {
+    let pairs = (0..10_000u32).map(|i| {
+        (
+            Bytes::from(format!("item:{:05}", i.wrapping_mul(7919) % 10_000)),
+            Bytes::from(i.to_string()),
+        )
+    });
+    let mut art: OxidArt = pairs.collect();
+    assert_eq!(art.len(), 10_000);
+
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    for _ in 0..200 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let i = (state % 10_000) as u32;
+        let key = Bytes::from(format!("item:{:05}", i.wrapping_mul(7919) % 10_000));
+        assert_eq!(art.get(key), Some(Bytes::from(i.to_string())));
+    }
+    art.verify_invariants().unwrap();
+
+    art.extend([
+        (
+            Bytes::from_static(b"item:00000"),
+            Bytes::from_static(b"new"),
+        ),
+        (Bytes::from_static(b"extra"), Bytes::from_static(b"1")),
+    ]);
+    assert_eq!(art.len(), 10_001);
+    assert_eq!(
+        art.get(Bytes::from_static(b"item:00000")),
+        Some(Bytes::from_static(b"new"))
+    );
+    art.verify_invariants().unwrap();
+}
+
+// ============ Tests longest_prefix_match ============
+
+#[test]
+fn test_longest_prefix_match() {
+    let mut routes = OxidArt::new();
+    assert_eq!(routes.longest_prefix_match(Bytes::from_static(b"/")), None);
+
+    routes.set(Bytes::from_static(b"/api/"), Bytes::from_static(b"api"));
+    routes.set(Bytes::from_static(b"/api/v2/"), Bytes::from_static(b"v2"));
+    routes.set(
+        Bytes::from_static(b"/api/v2/admin"),
+        Bytes::from_static(b"admin"),
+    );
+    let matched = |key: &'static [u8]| routes.longest_prefix_match(Bytes::from_static(key));
+
+    assert_eq!(
+        matched(b"/api/v2/users"),
+        Some((Bytes::from_static(b"/api/v2/"), Bytes::from_static(b"v2")))
+    );
+    // Exact match
+    assert_eq!(
+        matched(b"/api/v2/"),
+        Some((Bytes::from_static(b"/api/v2/"), Bytes::from_static(b"v2")))
+    );
+    // Diverging in the middle of "admin"
+    assert_eq!(
+        matched(b"/api/v2/adm"),
+        Some((Bytes::from_static(b"/api/v2/"), Bytes::from_static(b"v2")))
+    );
+    assert_eq!(
+        matched(b"/api/v1/users"),
+        Some((Bytes::from_static(b"/api/"), Bytes::from_static(b"api")))
+    );
+    assert_eq!(matched(b"/ap"), None);
+    assert_eq!(matched(b"/web"), None);
+}
+
+#[test]
+fn test_longest_prefix_match_root_and_expired() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::new(), Bytes::from_static(b"default"));
+    art.set(Bytes::from_static(b"10.0."), Bytes::from_static(b"lan"));
+    assert_eq!(
+        art.longest_prefix_match(Bytes::from_static(b"192.168.0.1")),
+        Some((Bytes::new(), Bytes::from_static(b"default")))
+    );
+
+    #[cfg(feature = "ttl")]
+    {
+        art.set_now(10);
+        art.set_ttl(
+            Bytes::from_static(b"10.0.0."),
+            std::time::Duration::from_secs(1),
+            Bytes::from_static(b"tmp"),
+        );
+        assert_eq!(
+            art.longest_prefix_match(Bytes::from_static(b"10.0.0.7")),
+            Some((Bytes::from_static(b"10.0.0."), Bytes::from_static(b"tmp")))
+        );
+        art.set_now(20);
+        assert_eq!(
+            art.longest_prefix_match(Bytes::from_static(b"10.0.0.7")),
+            Some((Bytes::from_static(b"10.0."), Bytes::from_static(b"lan")))
+        );
+    }
+}
+
+// ============ Tests get_or_insert_with ============
+
+#[test]
+fn test_get_or_insert_with() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"user:10"), Bytes::from_static(b"a"));
+
+    let mut calls = 0;
+    // Miss on a path split, then in a fresh leaf, then on an intermediate node
+    for key in [&b"user:11"[..], b"post:1", b"user:1"] {
+        let val = art.get_or_insert_with(Bytes::copy_from_slice(key), || {
+            calls += 1;
+            Bytes::copy_from_slice(key)
+        });
+        assert_eq!(val, Bytes::copy_from_slice(key));
+    }
+    assert_eq!(calls, 3);
+
+    // Hits never call the closure
+    for key in [&b"user:10"[..], b"user:11", b"post:1", b"user:1"] {
+        let val = art.get_or_insert_with(Bytes::copy_from_slice(key), || unreachable!());
+        assert_eq!(Some(val), art.get(Bytes::copy_from_slice(key)));
+    }
+    assert_eq!(art.len(), 4);
+    art.verify_invariants().unwrap();
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_get_or_insert_with_replaces_expired() {
+    let mut art = OxidArt::new();
+    art.set_now(10);
+    art.set_ttl(
+        Bytes::from_static(b"k"),
+        std::time::Duration::from_secs(1),
+        Bytes::from_static(b"old"),
+    );
+    art.set_now(20);
+    let val = art.get_or_insert_with(Bytes::from_static(b"k"), || Bytes::from_static(b"new"));
+    assert_eq!(val, Bytes::from_static(b"new"));
+    // Stored without expiration
+    art.set_now(u64::MAX - 1);
+    assert_eq!(
+        art.get(Bytes::from_static(b"k")),
+        Some(Bytes::from_static(b"new"))
+    );
+    assert_eq!(art.len(), 1);
+}
+
+// ============ Tests update ============
+
+#[test]
+fn test_update_in_place() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"counter"), Bytes::from_static(b"1"));
+    art.set(
+        Bytes::from_static(b"counter:total"),
+        Bytes::from_static(b"x"),
+    );
+
+    assert!(art.update(Bytes::from_static(b"counter"), |val| {
+        let n: u32 = std::str::from_utf8(val).unwrap().parse().unwrap();
+        *val = Bytes::from((n + 1).to_string());
+    }));
+    assert_eq!(
+        art.get(Bytes::from_static(b"counter")),
+        Some(Bytes::from_static(b"2"))
+    );
+
+    // Absent keys, including a value-less prefix node
+    assert!(!art.update(Bytes::from_static(b"count"), |_| unreachable!()));
+    assert!(!art.update(Bytes::from_static(b"missing"), |_| unreachable!()));
+    assert_eq!(art.len(), 2);
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_update_keeps_ttl_and_skips_expired() {
+    let mut art = OxidArt::new();
+    art.set_now(10);
+    art.set_ttl(
+        Bytes::from_static(b"k"),
+        std::time::Duration::from_secs(5),
+        Bytes::from_static(b"a"),
+    );
+    assert!(art.update(Bytes::from_static(b"k"), |val| {
+        *val = Bytes::from_static(b"b")
+    }));
+    art.set_now(14);
+    assert_eq!(
+        art.get(Bytes::from_static(b"k")),
+        Some(Bytes::from_static(b"b"))
+    );
+    art.set_now(16);
+    assert!(!art.update(Bytes::from_static(b"k"), |_| unreachable!()));
+}
+
+// ============ Tests range ============
+
+#[test]
+fn test_range_bounds() {
+    let mut art = OxidArt::new();
+    for i in 0..3000 {
+        art.set(
+            Bytes::from(format!("user:{i:04}")),
+            Bytes::from_static(b"x"),
+        );
+    }
+    art.set(Bytes::from_static(b"user:"), Bytes::from_static(b"dir"));
+
+    let users = art.range(
+        Bytes::from_static(b"user:1000"),
+        Bytes::from_static(b"user:2000"),
+    );
+    assert_eq!(users.len(), 1000);
+    assert_eq!(users[0].0, Bytes::from_static(b"user:1000"));
+    assert_eq!(users[999].0, Bytes::from_static(b"user:1999"));
+
+    // Bounds that are not stored keys
+    let some = art.range(
+        Bytes::from_static(b"user:0998a"),
+        Bytes::from_static(b"user:1001a"),
+    );
+    let keys: Vec<Bytes> = some.into_iter().map(|(key, _)| key).collect();
+    assert_eq!(
+        keys,
+        vec![
+            Bytes::from_static(b"user:0999"),
+            Bytes::from_static(b"user:1000"),
+            Bytes::from_static(b"user:1001"),
+        ]
+    );
+
+    // Empty intervals
+    let k = Bytes::from_static(b"user:0042");
+    assert!(art.range(k.clone(), k.clone()).is_empty());
+    assert!(art.range(Bytes::from_static(b"user:2"), k).is_empty());
+    assert!(
+        art.range(Bytes::from_static(b"v"), Bytes::from_static(b"z"))
+            .is_empty()
+    );
+    assert!(art.range(Bytes::new(), Bytes::from_static(b"a")).is_empty());
+
+    // The whole tree, the prefix node included
+    assert_eq!(
+        art.range(Bytes::new(), Bytes::from_static(b"zzz")).len(),
+        3001
+    );
+    assert_eq!(
+        art.range_iter(Bytes::new(), Bytes::from_static(b"zzz"))
+            .next(),
+        Some((Bytes::from_static(b"user:"), Bytes::from_static(b"dir")))
+    );
+}
+
+#[test]
+fn test_range_grown_childs_against_btree_map() {
+    use std::collections::BTreeMap;
+
+    let mut art = OxidArt::new();
+    let mut model = BTreeMap::new();
+    let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+    for _ in 0..2000 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        // Up to 4 bytes over a 64-byte alphabet, so nodes grow into every tier
+        let len = (state % 5) as usize;
+        let key: Vec<u8> = (0..len)
+            .map(|i| b'0' + ((state >> (8 + 6 * i)) % 64) as u8)
+            .collect();
+        art.set(Bytes::from(key.clone()), Bytes::from(key.clone()));
+        model.insert(key.clone(), Bytes::from(key));
+    }
+
+    let bounds: Vec<&[u8]> = vec![
+        b"", b"0", b"5", b"5A", b"9zz", b"A", b"Z0", b"ZZZZZ", b"p", b"\x7f",
+    ];
+    for &start in &bounds {
+        for &end in &bounds {
+            let expected: Vec<(Bytes, Bytes)> = if start < end {
+                model
+                    .range(start.to_vec()..end.to_vec())
+                    .map(|(key, val)| (Bytes::from(key.clone()), val.clone()))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            assert_eq!(
+                art.range(Bytes::copy_from_slice(start), Bytes::copy_from_slice(end)),
+                expected,
+                "range {start:?}..{end:?}"
+            );
+        }
+    }
+}
+
+// ============ Tests sorted getn ============
+
+#[test]
+fn test_getn_is_sorted() {
+    let mut art = OxidArt::new();
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    for _ in 0..3000 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        // Shuffled insertion order over the whole byte range, growing into every tier
+        let key = [b'k', (state >> 8) as u8, (state >> 16) as u8 % 16];
+        art.set(Bytes::copy_from_slice(&key), Bytes::from_static(b"v"));
+    }
+
+    let all = art.getn(Bytes::new());
+    assert!(all.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    assert_eq!(all, art.iter().collect::<Vec<_>>());
+
+    let sub = art.getn(Bytes::from_static(b"k\x80"));
+    assert!(!sub.is_empty());
+    assert!(sub.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+    let keys = art.fold_prefix(Bytes::new(), Vec::new(), |mut keys, key, _| {
+        keys.push(Bytes::copy_from_slice(key));
+        keys
+    });
+    assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+// ============ Tests min_key / max_key ============
+
+#[test]
+fn test_min_max_key() {
+    let mut art = OxidArt::new();
+    assert_eq!(art.min_key(), None);
+    assert_eq!(art.max_key(), None);
+
+    // Enough radixes under "q:" to grow past the inline tier, in shuffled order
+    for i in [7u8, 200, 3, 99, 255, 42, 0, 18, 150, 61, 77, 5, 250, 1] {
+        let key = [b'q', b':', i, b'x'];
+        art.set(Bytes::copy_from_slice(&key), Bytes::copy_from_slice(&[i]));
+    }
+    assert_eq!(art.min_key(), Some(Bytes::from_static(b"q:\x00x")));
+    assert_eq!(
+        art.max_key(),
+        Some((Bytes::from_static(b"q:\xffx"), Bytes::from_static(b"\xff")))
+    );
+
+    // A value on an inner node is the minimum of its subtree
+    art.set(Bytes::from_static(b"q:"), Bytes::from_static(b"dir"));
+    assert_eq!(art.min_key(), Some(Bytes::from_static(b"q:")));
+
+    // The root value is the minimum of the tree
+    art.set(Bytes::new(), Bytes::from_static(b"root"));
+    assert_eq!(art.min_key(), Some(Bytes::new()));
+    assert_eq!(art.max_key().unwrap().0, Bytes::from_static(b"q:\xffx"));
+
+    // A lone root value is both bounds
+    let mut art = OxidArt::new();
+    art.set(Bytes::new(), Bytes::from_static(b"root"));
+    assert_eq!(art.min_key(), Some(Bytes::new()));
+    assert_eq!(
+        art.max_key(),
+        Some((Bytes::new(), Bytes::from_static(b"root")))
+    );
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_min_max_key_skip_expired() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(100);
+    art.set_ttl(
+        Bytes::from_static(b"a"),
+        Duration::from_secs(10),
+        Bytes::from_static(b"1"),
+    );
+    art.set(Bytes::from_static(b"m"), Bytes::from_static(b"2"));
+    art.set_ttl(
+        Bytes::from_static(b"z"),
+        Duration::from_secs(10),
+        Bytes::from_static(b"3"),
+    );
+    assert_eq!(art.min_key(), Some(Bytes::from_static(b"a")));
+
+    art.set_now(200);
+    assert_eq!(art.min_key(), Some(Bytes::from_static(b"m")));
+    assert_eq!(art.max_key().unwrap().0, Bytes::from_static(b"m"));
+}
+
+// ============ Tests floor / ceiling ============
+
+#[test]
+fn test_floor_ceiling() {
+    let mut art = OxidArt::new();
+    assert_eq!(art.floor(Bytes::from_static(b"x")), None);
+    assert_eq!(art.ceiling(Bytes::from_static(b"x")), None);
+
+    for ts in [
+        "ts:1700000000",
+        "ts:1700000060",
+        "ts:1700000120",
+        "ts:17000001",
+    ] {
+        art.set(Bytes::from(ts), Bytes::from(ts));
+    }
+    let key = |k: &'static str| Bytes::from_static(k.as_bytes());
+    let floor = |art: &OxidArt, k| art.floor(key(k)).map(|(key, _)| key);
+    let ceiling = |art: &OxidArt, k| art.ceiling(key(k)).map(|(key, _)| key);
+
+    // Exact matches
+    assert_eq!(floor(&art, "ts:1700000060"), Some(key("ts:1700000060")));
+    assert_eq!(ceiling(&art, "ts:1700000060"), Some(key("ts:1700000060")));
+
+    // Between two entries
+    assert_eq!(floor(&art, "ts:1700000059"), Some(key("ts:1700000000")));
+    assert_eq!(ceiling(&art, "ts:1700000059"), Some(key("ts:1700000060")));
+    assert_eq!(floor(&art, "ts:1700000099"), Some(key("ts:1700000060")));
+    assert_eq!(ceiling(&art, "ts:1700000099"), Some(key("ts:17000001")));
+
+    // A stored key that prefixes the bound
+    assert_eq!(floor(&art, "ts:170000010"), Some(key("ts:17000001")));
+    assert_eq!(floor(&art, "ts:1700000100"), Some(key("ts:17000001")));
+    assert_eq!(ceiling(&art, "ts:1700000100"), Some(key("ts:1700000120")));
+
+    // Outside the full range
+    assert_eq!(floor(&art, "ts:"), None);
+    assert_eq!(ceiling(&art, "ts:"), Some(key("ts:1700000000")));
+    assert_eq!(floor(&art, "ts:9"), Some(key("ts:1700000120")));
+    assert_eq!(ceiling(&art, "ts:9"), None);
+    assert_eq!(floor(&art, ""), None);
+    assert_eq!(ceiling(&art, "u"), None);
+}
+
+#[test]
+fn test_floor_ceiling_against_btree_map() {
+    use std::collections::BTreeMap;
+
+    let mut art = OxidArt::new();
+    let mut model = BTreeMap::new();
+    let mut state = 0x1234_5678_9abc_def1_u64;
+    for _ in 0..1500 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let len = (state % 4) as usize;
+        let key: Vec<u8> = (0..len)
+            .map(|i| ((state >> (8 + 8 * i)) % 40) as u8 * 6)
+            .collect();
+        art.set(Bytes::from(key.clone()), Bytes::from(key.clone()));
+        model.insert(key, ());
+    }
+
+    for probe in 0..2000u64 {
+        let probe = probe.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        let len = (probe % 5) as usize;
+        let key: Vec<u8> = (0..len).map(|i| (probe >> (8 * i + 3)) as u8).collect();
+        let floor = model
+            .range(..=key.clone())
+            .next_back()
+            .map(|(k, _)| Bytes::from(k.clone()));
+        let ceiling = model
+            .range(key.clone()..)
+            .next()
+            .map(|(k, _)| Bytes::from(k.clone()));
+        assert_eq!(
+            art.floor(Bytes::from(key.clone())).map(|(k, _)| k),
+            floor,
+            "floor {key:?}"
+        );
+        assert_eq!(
+            art.ceiling(Bytes::from(key.clone())).map(|(k, _)| k),
+            ceiling,
+            "ceiling {key:?}"
+        );
+    }
+}
+
+// ============ Tests clone ============
+
+#[test]
+fn test_clone_is_independent() {
+    let mut art = OxidArt::new();
+    for i in 0..500 {
+        art.set(
+            Bytes::from(format!("key:{i:03}")),
+            Bytes::from(format!("v{i}")),
+        );
+    }
+    let handle = art.set_indexed(Bytes::from_static(b"handle"), Bytes::from_static(b"h"));
+    let mut snapshot = art.clone();
+
+    art.set(
+        Bytes::from_static(b"key:000"),
+        Bytes::from_static(b"changed"),
+    );
+    art.deln(Bytes::from_static(b"key:1"));
+    art.set(Bytes::from_static(b"new"), Bytes::from_static(b"n"));
+    art.del(Bytes::from_static(b"handle"));
+
+    assert_eq!(snapshot.len(), 501);
+    assert_eq!(
+        snapshot.get(Bytes::from_static(b"key:000")),
+        Some(Bytes::from_static(b"v0"))
+    );
+    assert_eq!(
+        snapshot.get(Bytes::from_static(b"key:150")),
+        Some(Bytes::from_static(b"v150"))
+    );
+    assert_eq!(snapshot.get(Bytes::from_static(b"new")), None);
+    assert_eq!(snapshot.getn(Bytes::from_static(b"key:1")).len(), 100);
+    // Slots are cloned in place, so handles stay valid on the clone
+    assert_eq!(snapshot.get_at(handle), Some(&Bytes::from_static(b"h")));
+    assert_eq!(art.get_at(handle), None);
+    snapshot.verify_invariants().unwrap();
+
+    assert_eq!(art.len(), 401);
+    art.verify_invariants().unwrap();
+}
+
+// ============ Tests clear ============
+
+#[test]
+fn test_clear_keeps_capacity() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::new(), Bytes::from_static(b"root"));
+    // Enough root children to grow it into a Node256
+    for i in 0..=255u8 {
+        for j in 0..16u8 {
+            art.set(
+                Bytes::copy_from_slice(&[i, j, b'x']),
+                Bytes::from_static(b"v"),
+            );
+        }
+    }
+    let handle = art.set_indexed(Bytes::from_static(b"handle"), Bytes::from_static(b"h"));
+    assert!(art.memory_stats().grown_child_nodes > 0);
+    let node_capacity = art.map.capacity();
+
+    art.clear();
+    assert!(art.is_empty());
+    assert_eq!(art.node_count(), 1);
+    assert_eq!(art.memory_stats().grown_child_nodes, 0);
+    assert_eq!(art.get(Bytes::new()), None);
+    assert!(art.getn(Bytes::new()).is_empty());
+    assert_eq!(art.get_at(handle), None);
+    assert_eq!(art.map.capacity(), node_capacity);
+    art.verify_invariants().unwrap();
+
+    // The tree is usable again, the new keys reusing the old slots
+    art.set(Bytes::from_static(b"handle"), Bytes::from_static(b"new"));
+    assert_eq!(art.get_at(handle), None);
+    assert_eq!(
+        art.get(Bytes::from_static(b"handle")),
+        Some(Bytes::from_static(b"new"))
+    );
+    assert_eq!(art.len(), 1);
+    assert_eq!(art.map.capacity(), node_capacity);
+    art.verify_invariants().unwrap();
+}
+
+#[test]
+fn test_clear_resets_lru() {
+    let mut art = OxidArt::with_max_entries(2);
+    art.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+    art.set(Bytes::from_static(b"b"), Bytes::from_static(b"2"));
+    art.clear();
+
+    art.set(Bytes::from_static(b"c"), Bytes::from_static(b"3"));
+    art.set(Bytes::from_static(b"d"), Bytes::from_static(b"4"));
+    assert_eq!(art.len(), 2);
+    assert_eq!(
+        art.get(Bytes::from_static(b"c")),
+        Some(Bytes::from_static(b"3"))
+    );
+}
+
+// ============ Tests memory_stats ============
+
+#[test]
+fn test_memory_stats() {
+    let mut art = OxidArt::new();
+    let empty = art.memory_stats();
+    assert_eq!(empty.node_count, 1);
+    assert_eq!(empty.grown_child_nodes, 0);
+    assert_eq!(empty.child_heap_bytes, 0);
+    assert_eq!(empty.estimated_value_bytes, 0);
+    assert!(empty.slab_capacity >= 1024);
+    assert!(empty.slab_bytes > 0);
+
+    art.set(Bytes::from_static(b"a"), Bytes::from_static(b"12345"));
+    let long_key = [b'b'; 64];
+    art.set(
+        Bytes::copy_from_slice(&long_key),
+        Bytes::from_static(b"xyz"),
+    );
+    for i in 0..=255u8 {
+        art.set(Bytes::copy_from_slice(&[b'c', i]), Bytes::new());
+    }
+
+    let stats = art.memory_stats();
+    assert_eq!(stats.node_count, art.node_count());
+    assert!(stats.grown_child_nodes >= 1);
+    assert!(stats.child_heap_bytes > 0);
+    assert_eq!(stats.estimated_value_bytes, 8);
+    // "a" has no compression, the long key keeps 63 bytes after its radix
+    assert_eq!(stats.estimated_compression_bytes, 63);
+    assert_eq!(stats.spilled_compressions, 1);
+    assert!(stats.spilled_compression_bytes >= 63);
+    assert!(stats.estimated_total_bytes() > stats.slab_bytes);
+}
+
+// ============ Tests shrink_to_fit ============
+
+#[test]
+fn test_shrink_to_fit_after_bulk_delete() {
+    let mut art = OxidArt::new();
+    for i in 0..10_000 {
+        art.set(
+            Bytes::from(format!("key:{i:05}")),
+            Bytes::from(format!("v{i}")),
+        );
+    }
+    // Root children grown into a Node256, to remap its links too
+    for i in 0..=255u8 {
+        art.set(
+            Bytes::copy_from_slice(&[i, b'x', b'y']),
+            Bytes::copy_from_slice(&[i]),
+        );
+    }
+    let handle = art.set_indexed(Bytes::from_static(b"key:09999"), Bytes::from_static(b"h"));
+    // Last slot of the slab, past the end once compacted
+    let deleted = art.set_indexed(
+        Bytes::from_static(b"key:08999:tail"),
+        Bytes::from_static(b"t"),
+    );
+    for i in 0..9 {
+        art.deln(Bytes::from(format!("key:0{i}")));
+    }
+    let before = art.memory_stats();
+    let expected = art.getn(Bytes::new());
+    assert_eq!(expected.len(), 1000 + 256);
+
+    art.shrink_to_fit();
+    let after = art.memory_stats();
+    assert!(after.slab_capacity < before.slab_capacity);
+    assert!(after.slab_capacity < 5000);
+    assert_eq!(after.node_count, before.node_count);
+    assert_eq!(after.grown_child_nodes, before.grown_child_nodes);
+    assert_eq!(art.getn(Bytes::new()), expected);
+    assert_eq!(art.len(), expected.len());
+    assert_eq!(art.get_at(handle), None);
+    assert_eq!(art.versions.len(), art.map.len());
+    assert!(art.versions.capacity() < 5000);
+    assert!(deleted.index() as usize >= art.map.len());
+    art.verify_invariants().unwrap();
+
+    // The tree keeps working on the compact slabs
+    art.set(
+        Bytes::from_static(b"key:00001"),
+        Bytes::from_static(b"back"),
+    );
+    art.del(Bytes::from_static(b"key:09999"));
+    assert_eq!(
+        art.get(Bytes::from_static(b"key:00001")),
+        Some(Bytes::from_static(b"back"))
+    );
+    assert_eq!(
+        art.get(Bytes::from_static(b"\x80xy")),
+        Some(Bytes::from_static(b"\x80"))
+    );
+    art.verify_invariants().unwrap();
+    let handle = art.set_indexed(Bytes::from_static(b"fresh"), Bytes::from_static(b"f"));
+    assert_eq!(art.get_at(handle), Some(&Bytes::from_static(b"f")));
+
+    // Slots handed out again past the truncation don't revive old handles
+    for i in 0..10_000 {
+        art.set(Bytes::from(format!("new:{i:05}")), Bytes::from_static(b"n"));
+    }
+    assert!(art.try_get_node(deleted.index()).is_some());
+    assert_eq!(art.get_at(deleted), None);
+}
+
+// ============ Tests persist ============
+
+#[cfg(feature = "persist")]
+#[test]
+fn test_serialize_roundtrip() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::new(), Bytes::from_static(b"root"));
+    for i in 0..=255u8 {
+        art.set(
+            Bytes::copy_from_slice(&[i]),
+            Bytes::copy_from_slice(&[i, i]),
+        );
+        art.set(Bytes::copy_from_slice(&[i, 0, i]), Bytes::new());
+    }
+    for i in 0..1000 {
+        art.set(
+            Bytes::from(format!("user:{i}")),
+            Bytes::from(format!("name{i}")),
+        );
+    }
+
+    let mut snapshot = Vec::new();
+    art.serialize(&mut snapshot).unwrap();
+    assert_eq!(&snapshot[..5], b"OXAR\x01");
+
+    let mut loaded = OxidArt::deserialize(&mut snapshot.as_slice()).unwrap();
+    assert_eq!(loaded.len(), art.len());
+    assert_eq!(
+        loaded.iter().collect::<Vec<_>>(),
+        art.iter().collect::<Vec<_>>()
+    );
+    assert_eq!(loaded.get(Bytes::new()), Some(Bytes::from_static(b"root")));
+    loaded.verify_invariants().unwrap();
+
+    // An empty tree is just the header and the end tag
+    let mut empty = Vec::new();
+    OxidArt::new().serialize(&mut empty).unwrap();
+    assert_eq!(empty.len(), 4 + 1 + 1 + 8 + 1);
+    assert!(
+        OxidArt::deserialize(&mut empty.as_slice())
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[cfg(all(feature = "persist", feature = "ttl"))]
+#[test]
+fn test_serialize_keeps_expirations() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(1000);
+    art.set(Bytes::from_static(b"forever"), Bytes::from_static(b"1"));
+    art.set_ttl(
+        Bytes::from_static(b"soon"),
+        Duration::from_secs(10),
+        Bytes::from_static(b"2"),
+    );
+    art.set_ttl(
+        Bytes::from_static(b"gone"),
+        Duration::from_secs(1),
+        Bytes::from_static(b"3"),
+    );
+    art.set_now(1005);
+
+    let mut snapshot = Vec::new();
+    art.serialize(&mut snapshot).unwrap();
+    let mut loaded = OxidArt::deserialize(&mut snapshot.as_slice()).unwrap();
+    assert_eq!(loaded.now(), 1005);
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded.get(Bytes::from_static(b"gone")), None);
+    assert_eq!(
+        loaded.get(Bytes::from_static(b"soon")),
+        Some(Bytes::from_static(b"2"))
+    );
+
+    loaded.set_now(1011);
+    assert_eq!(loaded.get(Bytes::from_static(b"soon")), None);
+    assert_eq!(
+        loaded.get(Bytes::from_static(b"forever")),
+        Some(Bytes::from_static(b"1"))
+    );
+}
+
+#[cfg(feature = "persist")]
+#[test]
+fn test_deserialize_rejects_bad_input() {
+    use std::io::ErrorKind;
+
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"key"), Bytes::from_static(b"value"));
+    let mut snapshot = Vec::new();
+    art.serialize(&mut snapshot).unwrap();
+
+    let load = |bytes: &[u8]| {
+        OxidArt::deserialize(&mut &bytes[..])
+            .map(|_| ())
+            .unwrap_err()
+            .kind()
+    };
+    assert_eq!(load(b"NOPE\x01\x00"), ErrorKind::InvalidData);
+
+    let mut future = snapshot.clone();
+    future[4] = 2;
+    assert_eq!(load(&future), ErrorKind::InvalidData);
+
+    let mut bad_tag = snapshot.clone();
+    bad_tag[14] = 7;
+    assert_eq!(load(&bad_tag), ErrorKind::InvalidData);
+
+    // Every strict prefix is truncated, including a missing end tag
+    for len in 0..snapshot.len() {
+        assert_eq!(
+            load(&snapshot[..len]),
+            ErrorKind::UnexpectedEof,
+            "prefix of {len} bytes"
+        );
+    }
+}
+
+// ============ Tests prefix_count ============
+
+#[test]
+fn test_prefix_count() {
+    let mut art = OxidArt::new();
+    assert_eq!(art.prefix_count(Bytes::new()), 0);
+
+    art.set(Bytes::new(), Bytes::from_static(b"root"));
+    for i in 0..300 {
+        art.set(Bytes::from(format!("user:{i}")), Bytes::from_static(b"u"));
+    }
+    art.set(Bytes::from_static(b"user"), Bytes::from_static(b"dir"));
+    art.set(Bytes::from_static(b"post:1"), Bytes::from_static(b"p"));
+
+    assert_eq!(art.prefix_count(Bytes::new()), art.len());
+    assert_eq!(art.prefix_count(Bytes::new()), 303);
+    assert_eq!(art.prefix_count(Bytes::from_static(b"user")), 301);
+    assert_eq!(art.prefix_count(Bytes::from_static(b"user:")), 300);
+    // Prefix ending inside a compression
+    assert_eq!(art.prefix_count(Bytes::from_static(b"us")), 301);
+    assert_eq!(art.prefix_count(Bytes::from_static(b"user:2")), 111);
+    assert_eq!(art.prefix_count(Bytes::from_static(b"user:299")), 1);
+    assert_eq!(art.prefix_count(Bytes::from_static(b"user:3000")), 0);
+    assert_eq!(art.prefix_count(Bytes::from_static(b"x")), 0);
+    for prefix in ["user:1", "post", "u"] {
+        let prefix = Bytes::from(prefix);
+        assert_eq!(art.prefix_count(prefix.clone()), art.getn(prefix).len());
+    }
+}
+
+// ============ Tests getn_paged ============
+
+#[test]
+fn test_getn_paged() {
+    let mut art = OxidArt::new();
+    for i in 0..25 {
+        art.set(
+            Bytes::from(format!("item:{i:02}")),
+            Bytes::from(format!("{i}")),
+        );
+    }
+    art.set(Bytes::from_static(b"other"), Bytes::from_static(b"o"));
+    let prefix = Bytes::from_static(b"item:");
+    let all = art.getn(prefix.clone());
+
+    let mut pages = Vec::new();
+    let mut offset = 0;
+    loop {
+        let (page, has_next) = art.getn_paged(prefix.clone(), offset, 10);
+        offset += page.len();
+        pages.push(page);
+        if !has_next {
+            break;
+        }
+    }
+    assert_eq!(
+        pages.iter().map(Vec::len).collect::<Vec<_>>(),
+        vec![10, 10, 5]
+    );
+    assert_eq!(pages.concat(), all);
+
+    // An exactly full last page has no next one
+    let (page, has_next) = art.getn_paged(prefix.clone(), 15, 10);
+    assert_eq!(page.len(), 10);
+    assert!(!has_next);
+    assert_eq!(page[0].0, Bytes::from_static(b"item:15"));
+
+    assert_eq!(art.getn_paged(prefix.clone(), 25, 10), (Vec::new(), false));
+    assert_eq!(art.getn_paged(prefix.clone(), 0, 0), (Vec::new(), true));
+    assert_eq!(art.getn_paged(prefix.clone(), 0, usize::MAX).0, all);
+    assert_eq!(
+        art.getn_paged(Bytes::from_static(b"none"), 0, 10),
+        (Vec::new(), false)
+    );
+}
+
+// ============ Tests get_many ============
+
+#[test]
+fn test_get_many_matches_get_slice() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::new(), Bytes::from_static(b"root"));
+    for field in ["name", "email", "phone", "e", "em"] {
+        art.set(Bytes::from(format!("user:42:{field}")), Bytes::from(field));
+    }
+    art.set(
+        Bytes::from_static(b"user:7:name"),
+        Bytes::from_static(b"bob"),
+    );
+
+    let keys: Vec<Bytes> = [
+        "user:42:phone",
+        "user:42:name",
+        "user:42:",
+        "user:42:emai",
+        "user:42:email",
+        "user:42:email2",
+        "",
+        "user:7:name",
+        "user:42:name",
+        "zzz",
+        "user:42:e",
+    ]
+    .into_iter()
+    .map(Bytes::from)
+    .collect();
+    let expected: Vec<Option<Bytes>> = keys.iter().map(|key| art.get_slice(key)).collect();
+    assert_eq!(art.get_many(&keys), expected);
+    assert_eq!(expected.iter().filter(|val| val.is_some()).count(), 7);
+    assert!(art.get_many(&[]).is_empty());
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_get_many_skips_expired() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(100);
+    art.set_ttl(
+        Bytes::from_static(b"k:1"),
+        Duration::from_secs(5),
+        Bytes::from_static(b"1"),
+    );
+    art.set(Bytes::from_static(b"k:2"), Bytes::from_static(b"2"));
+    art.set_now(200);
+    assert_eq!(
+        art.get_many(&[Bytes::from_static(b"k:2"), Bytes::from_static(b"k:1")]),
+        vec![Some(Bytes::from_static(b"2")), None]
+    );
+}
+
+// ============ Tests merge ============
+
+#[test]
+fn test_merge_disjoint_and_overlapping() {
+    let mut left = OxidArt::new();
+    let mut right = OxidArt::new();
+    for i in 0..100 {
+        left.set(Bytes::from(format!("left:{i}")), Bytes::from_static(b"l"));
+        right.set(Bytes::from(format!("right:{i}")), Bytes::from_static(b"r"));
+    }
+    right.set(Bytes::new(), Bytes::from_static(b"root"));
+    let mut calls = 0;
+    left.merge(right, |_, existing, _| {
+        calls += 1;
+        existing
+    });
+    assert_eq!(calls, 0);
+    assert_eq!(left.len(), 201);
+    assert_eq!(left.get(Bytes::new()), Some(Bytes::from_static(b"root")));
+    assert_eq!(
+        left.get(Bytes::from_static(b"right:42")),
+        Some(Bytes::from_static(b"r"))
+    );
+    left.verify_invariants().unwrap();
+
+    // Fully overlapping, the resolver sees the key and both values
+    let mut a = OxidArt::new();
+    let mut b = OxidArt::new();
+    for i in 0..100u32 {
+        a.set(Bytes::from(format!("k:{i}")), Bytes::from(i.to_string()));
+        b.set(
+            Bytes::from(format!("k:{i}")),
+            Bytes::from((i * 10).to_string()),
+        );
+    }
+    a.set(Bytes::new(), Bytes::from_static(b"1"));
+    b.set(Bytes::new(), Bytes::from_static(b"2"));
+    a.merge(b, |key, existing, incoming| {
+        let sum: u32 = [existing, incoming]
+            .iter()
+            .map(|val| std::str::from_utf8(val).unwrap().parse::<u32>().unwrap())
+            .sum();
+        assert!(key.is_empty() || key.starts_with(b"k:"));
+        Bytes::from(sum.to_string())
+    });
+    assert_eq!(a.len(), 101);
+    assert_eq!(
+        a.get(Bytes::from_static(b"k:7")),
+        Some(Bytes::from_static(b"77"))
+    );
+    assert_eq!(a.get(Bytes::new()), Some(Bytes::from_static(b"3")));
+    a.verify_invariants().unwrap();
+}
+
+#[test]
+fn test_merge_last_write_wins() {
+    let mut base = OxidArt::new();
+    base.set(
+        Bytes::from_static(b"cfg:timeout"),
+        Bytes::from_static(b"30"),
+    );
+    base.set(Bytes::from_static(b"cfg:retries"), Bytes::from_static(b"3"));
+    let mut update = OxidArt::new();
+    update.set(
+        Bytes::from_static(b"cfg:timeout"),
+        Bytes::from_static(b"60"),
+    );
+    update.set(Bytes::from_static(b"cfg:region"), Bytes::from_static(b"eu"));
+
+    base.merge(update, |_, _, incoming| incoming);
+    assert_eq!(
+        base.getn(Bytes::from_static(b"cfg:")),
+        vec![
+            (Bytes::from_static(b"cfg:region"), Bytes::from_static(b"eu")),
+            (Bytes::from_static(b"cfg:retries"), Bytes::from_static(b"3")),
+            (
+                Bytes::from_static(b"cfg:timeout"),
+                Bytes::from_static(b"60")
+            ),
+        ]
+    );
+    base.merge(OxidArt::new(), |_, _, incoming| incoming);
+    assert_eq!(base.len(), 3);
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_merge_expirations() {
+    use std::time::Duration;
+
+    let mut base = OxidArt::new();
+    base.set_now(100);
+    base.set_ttl(
+        Bytes::from_static(b"dead"),
+        Duration::from_secs(1),
+        Bytes::from_static(b"old"),
+    );
+    base.set_ttl(
+        Bytes::from_static(b"kept"),
+        Duration::from_secs(50),
+        Bytes::from_static(b"a"),
+    );
+    let mut other = OxidArt::new();
+    other.set_now(100);
+    other.set(Bytes::from_static(b"dead"), Bytes::from_static(b"new"));
+    other.set(Bytes::from_static(b"kept"), Bytes::from_static(b"b"));
+    other.set_ttl(
+        Bytes::from_static(b"short"),
+        Duration::from_secs(20),
+        Bytes::from_static(b"s"),
+    );
+    base.set_now(110);
+
+    base.merge(other, |_, existing, incoming| {
+        assert_eq!(existing, Bytes::from_static(b"a"));
+        incoming
+    });
+    // The expired entry is replaced as if absent
+    assert_eq!(
+        base.get(Bytes::from_static(b"dead")),
+        Some(Bytes::from_static(b"new"))
+    );
+    assert_eq!(
+        base.get(Bytes::from_static(b"kept")),
+        Some(Bytes::from_static(b"b"))
+    );
+    base.set_now(125);
+    assert_eq!(base.get(Bytes::from_static(b"short")), None);
+    base.set_now(151);
+    assert_eq!(base.get(Bytes::from_static(b"kept")), None);
+    assert_eq!(
+        base.get(Bytes::from_static(b"dead")),
+        Some(Bytes::from_static(b"new"))
+    );
+}
+
+// ============ Tests retain ============
+
+#[test]
+fn test_retain() {
+    let mut art = OxidArt::with_max_entries(10_000);
+    art.set(Bytes::new(), Bytes::from_static(b"root"));
+    for i in 0..1000u32 {
+        art.set(
+            Bytes::from(format!("key:{i}")),
+            Bytes::from((i % 3).to_string()),
+        );
+    }
+
+    let removed = art.retain(|key, val| key.is_empty() || val != "0");
+    assert_eq!(removed, 334);
+    assert_eq!(art.len(), 667);
+    assert_eq!(art.get(Bytes::new()), Some(Bytes::from_static(b"root")));
+    assert_eq!(art.get(Bytes::from_static(b"key:3")), None);
+    assert_eq!(
+        art.get(Bytes::from_static(b"key:4")),
+        Some(Bytes::from_static(b"1"))
+    );
+    art.verify_invariants().unwrap();
+
+    assert_eq!(art.retain(|_, _| true), 0);
+    assert_eq!(art.retain(|_, _| false), 667);
+    assert!(art.is_empty());
+    assert_eq!(art.node_count(), 1);
+    art.verify_invariants().unwrap();
+}
+
+// ============ Tests remaining_ttl ============
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_remaining_ttl() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(1000);
+    art.set(Bytes::from_static(b"forever"), Bytes::from_static(b"v"));
+    art.set_ttl(
+        Bytes::from_static(b"session"),
+        Duration::from_secs(60),
+        Bytes::from_static(b"v"),
+    );
+    // Inner node without value on the path of "session:x"
+    art.set(Bytes::from_static(b"session:x"), Bytes::from_static(b"v"));
+
+    assert_eq!(
+        art.remaining_ttl(Bytes::from_static(b"forever")),
+        Some(Duration::MAX)
+    );
+    assert_eq!(
+        art.remaining_ttl(Bytes::from_static(b"session")),
+        Some(Duration::from_secs(60))
+    );
+    assert_eq!(art.remaining_ttl(Bytes::from_static(b"session:")), None);
+    assert_eq!(art.remaining_ttl(Bytes::from_static(b"absent")), None);
+
+    art.advance(60);
+    assert_eq!(
+        art.remaining_ttl(Bytes::from_static(b"session")),
+        Some(Duration::ZERO)
+    );
+    art.advance(1);
+    assert_eq!(art.remaining_ttl(Bytes::from_static(b"session")), None);
+    assert_eq!(
+        art.remaining_ttl(Bytes::from_static(b"forever")),
+        Some(Duration::MAX)
+    );
+}
+
+// ============ Tests persist / expire ============
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_persist_and_expire() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(1000);
+    art.set_ttl(
+        Bytes::from_static(b"session"),
+        Duration::from_secs(60),
+        Bytes::from_static(b"s"),
+    );
+    art.set(Bytes::from_static(b"token"), Bytes::from_static(b"t"));
+
+    assert!(art.persist(Bytes::from_static(b"session")));
+    assert!(!art.persist(Bytes::from_static(b"session")));
+    assert!(!art.persist(Bytes::from_static(b"token")));
+    assert!(!art.persist(Bytes::from_static(b"absent")));
+    art.advance(120);
+    assert_eq!(
+        art.get(Bytes::from_static(b"session")),
+        Some(Bytes::from_static(b"s"))
+    );
+
+    assert!(art.expire(Bytes::from_static(b"token"), Duration::from_secs(30)));
+    assert_eq!(
+        art.remaining_ttl(Bytes::from_static(b"token")),
+        Some(Duration::from_secs(30))
+    );
+    // Shortening an expiration, the value is kept
+    assert!(art.expire(Bytes::from_static(b"token"), Duration::from_secs(5)));
+    assert_eq!(
+        art.get(Bytes::from_static(b"token")),
+        Some(Bytes::from_static(b"t"))
+    );
+    art.advance(6);
+    assert_eq!(art.remaining_ttl(Bytes::from_static(b"token")), None);
+    // An expired key can be neither persisted nor extended
+    assert!(!art.persist(Bytes::from_static(b"token")));
+    assert!(!art.expire(Bytes::from_static(b"token"), Duration::from_secs(30)));
+    assert!(!art.expire(Bytes::from_static(b"absent"), Duration::from_secs(30)));
+
+    assert!(art.expire(Bytes::from_static(b"session"), Duration::MAX));
+    assert_eq!(
+        art.remaining_ttl(Bytes::from_static(b"session")),
+        Some(Duration::MAX)
+    );
+    assert!(art.expire(Bytes::from_static(b"session"), Duration::ZERO));
+    assert_eq!(art.get(Bytes::from_static(b"session")), None);
+    art.verify_invariants().unwrap();
+}
+
+// ============ Tests evict_all_expired ============
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_evict_all_expired() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::with_max_entries(10_000);
+    art.set_now(1000);
+    art.set_ttl(
+        Bytes::new(),
+        Duration::from_secs(1),
+        Bytes::from_static(b"root"),
+    );
+    for i in 0..1000u64 {
+        let key = Bytes::from(format!("k:{i}"));
+        if i % 4 == 0 {
+            art.set(key, Bytes::from_static(b"forever"));
+        } else {
+            art.set_ttl(
+                key,
+                Duration::from_secs(i % 4 * 10),
+                Bytes::from_static(b"v"),
+            );
+        }
+    }
+    assert_eq!(art.evict_all_expired(), 0);
+    assert_eq!(art.len(), 1001);
+
+    // Expires at 1010 are still live at 1010
+    art.set_now(1010);
+    assert_eq!(art.evict_all_expired(), 1);
+    art.set_now(1011);
+    assert_eq!(art.evict_all_expired(), 250);
+    art.verify_invariants().unwrap();
+
+    art.set_now(2000);
+    assert_eq!(art.evict_all_expired(), 500);
+    assert_eq!(art.len(), 250);
+    assert_eq!(art.evict_all_expired(), 0);
+    assert_eq!(
+        art.get(Bytes::from_static(b"k:8")),
+        Some(Bytes::from_static(b"forever"))
+    );
+    art.verify_invariants().unwrap();
+}
+
+// ============ Tests iter_with_ttl ============
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_iter_with_ttl() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(1000);
+    art.set(Bytes::from_static(b"config"), Bytes::from_static(b"v1"));
+    art.set_ttl(
+        Bytes::from_static(b"session:1"),
+        Duration::from_secs(60),
+        Bytes::from_static(b"a"),
+    );
+    art.set_ttl(
+        Bytes::from_static(b"session:2"),
+        Duration::from_secs(5),
+        Bytes::from_static(b"b"),
+    );
+    art.set_ttl(
+        Bytes::from_static(b"session:3"),
+        Duration::from_secs(10),
+        Bytes::from_static(b"c"),
+    );
+    art.advance(10);
+
+    let entries: Vec<_> = art.iter_with_ttl().collect();
+    assert_eq!(
+        entries,
+        vec![
+            (
+                Bytes::from_static(b"config"),
+                Bytes::from_static(b"v1"),
+                None
+            ),
+            (
+                Bytes::from_static(b"session:1"),
+                Bytes::from_static(b"a"),
+                Some(Duration::from_secs(50))
+            ),
+            (
+                Bytes::from_static(b"session:3"),
+                Bytes::from_static(b"c"),
+                Some(Duration::ZERO)
+            ),
+        ]
+    );
+    let keys: Vec<_> = art.iter().map(|(key, _)| key).collect();
+    let ttl_keys: Vec<_> = art.iter_with_ttl().map(|(key, _, _)| key).collect();
+    assert_eq!(keys, ttl_keys);
+}
+
+// ============ Tests sorted grown childs ============
+
+#[test]
+fn test_grown_childs_stay_sorted() {
+    use crate::node_childs::ChildAble;
+
+    let mut art = OxidArt::new();
+    // Scattered insertion order over every byte value
+    let radixes: Vec<u8> = (0..=255u8).map(|i| i.wrapping_mul(167)).collect();
+    for &radix in &radixes {
+        art.set(
+            Bytes::copy_from_slice(&[b'w', radix]),
+            Bytes::from_static(b"v"),
+        );
+    }
+    art.verify_invariants().unwrap();
+    for &radix in radixes.iter().step_by(3) {
+        art.del(Bytes::copy_from_slice(&[b'w', radix]));
+    }
+    art.verify_invariants().unwrap();
+
+    let childs = &art.get_node(art.find_key_node(b"w").unwrap()).childs;
+    assert!(!childs.is_inline());
+    let sorted: Vec<u8> = childs.iter().map(|(radix, _)| radix).collect();
+    assert!(sorted.is_sorted());
+    for (radix, idx) in childs.iter() {
+        assert_eq!(childs.find(radix), Some(idx));
+    }
+
+    let keys: Vec<Bytes> = art.iter().map(|(key, _)| key).collect();
+    assert_eq!(keys.len(), 256 - 86);
+    assert!(keys.is_sorted());
+}
+
+// ============ Tests inline radix search ============
+
+#[test]
+fn test_inline_childs_find_every_position() {
+    // Radix 0 included, it must not match the unused inline slots
+    let radixes = [7u8, 0, 255, 1, b'a', 128, 2, 3, 4];
+    let mut art = OxidArtN::<16>::new();
+    art.set(Bytes::from_static(&[0, 0]), Bytes::from_static(b"deep"));
+    for (i, &radix) in radixes.iter().enumerate() {
+        art.set(
+            Bytes::copy_from_slice(&[b'x', radix]),
+            Bytes::from(i.to_string()),
+        );
+        for (j, &inserted) in radixes[..=i].iter().enumerate() {
+            assert_eq!(
+                art.get(Bytes::copy_from_slice(&[b'x', inserted])),
+                Some(Bytes::from(j.to_string()))
+            );
+        }
+        for &absent in &radixes[i + 1..] {
+            assert_eq!(art.get(Bytes::copy_from_slice(&[b'x', absent])), None);
+        }
+    }
+    assert_eq!(
+        art.del(Bytes::copy_from_slice(&[b'x', 0])),
+        Some(Bytes::from_static(b"1"))
+    );
+    assert_eq!(art.get(Bytes::copy_from_slice(&[b'x', 0])), None);
+    assert_eq!(
+        art.get(Bytes::copy_from_slice(&[b'x', 4])),
+        Some(Bytes::from_static(b"8"))
+    );
+    art.verify_invariants().unwrap();
+}
+
+// ============ Tests adaptive childs ============
+
+/// Tier of the children of "w", and their count
+fn childs_tier<const N: usize>(art: &OxidArtN<N>) -> (&'static str, usize) {
+    use crate::node_childs::{ChildAble, Childs};
+
+    let childs = &art.get_node(art.find_key_node(b"w").unwrap()).childs;
+    let name = match childs {
+        Childs::Inline(_) => "inline",
+        Childs::Node16(_) => "node16",
+        Childs::Node48(_) => "node48",
+        Childs::Node256(_) => "node256",
+    };
+    // Every tier iterates in ascending radix order, both ways
+    let radixes: Vec<u8> = childs.iter().map(|(radix, _)| radix).collect();
+    assert!(radixes.is_sorted());
+    assert!(
+        childs
+            .iter()
+            .rev()
+            .map(|(radix, _)| radix)
+            .eq(radixes.iter().rev().copied())
+    );
+    (name, radixes.len())
+}
+
+/// Grows "w" to 256 children then shrinks it back to one, checking the tier
+/// against `grown(len)` after every push and `shrunk(len)` after every remove
+fn check_childs_tiers<const N: usize>(
+    grown: impl Fn(usize) -> &'static str,
+    shrunk: impl Fn(usize) -> &'static str,
+) {
+    let mut art = OxidArtN::<N>::new();
+    art.set(Bytes::from_static(b"w"), Bytes::from_static(b"parent"));
+    let radixes: Vec<u8> = (0..=255u8).map(|i| i.wrapping_mul(73)).collect();
+    for (i, &radix) in radixes.iter().enumerate() {
+        art.set(
+            Bytes::copy_from_slice(&[b'w', radix]),
+            Bytes::from_static(b"v"),
+        );
+        assert_eq!(childs_tier(&art), (grown(i + 1), i + 1));
+    }
+    art.verify_invariants().unwrap();
+    assert!(art.memory_stats().child_heap_bytes > 0);
+
+    for (i, &radix) in radixes.iter().enumerate().take(255) {
+        assert_eq!(
+            art.del(Bytes::copy_from_slice(&[b'w', radix])),
+            Some(Bytes::from_static(b"v"))
+        );
+        let left = 255 - i;
+        assert_eq!(childs_tier(&art), (shrunk(left), left));
+        // Every remaining child is still reachable, in ascending order
+        let keys: Vec<Bytes> = art.iter().map(|(key, _)| key).collect();
+        assert!(keys.is_sorted());
+        assert_eq!(keys.len(), left + 1);
+    }
+    art.verify_invariants().unwrap();
+    // Without inline slot, the last children of "w" and the root stay boxed
+    assert_eq!(art.memory_stats().child_heap_bytes == 0, N > 0);
+}
+
+#[test]
+fn test_childs_promotion_and_demotion() {
+    // Default inline capacity, every tier in turn
+    check_childs_tiers::<{ crate::CHILDS_SIZE }>(
+        |len| match len {
+            ..=4 => "inline",
+            5..=16 => "node16",
+            17..=48 => "node48",
+            _ => "node256",
+        },
+        |len| match len {
+            ..=3 => "inline",
+            4..=12 => "node16",
+            13..=36 => "node48",
+            _ => "node256",
+        },
+    );
+    // 16 inline children: the Node16 tier is skipped
+    check_childs_tiers::<16>(
+        |len| match len {
+            ..=16 => "inline",
+            17..=48 => "node48",
+            _ => "node256",
+        },
+        |len| match len {
+            ..=12 => "inline",
+            13..=36 => "node48",
+            _ => "node256",
+        },
+    );
+    // No inline slot: the first child already grows the node
+    check_childs_tiers::<0>(
+        |len| match len {
+            ..=16 => "node16",
+            17..=48 => "node48",
+            _ => "node256",
+        },
+        |len| match len {
+            ..=12 => "node16",
+            13..=36 => "node48",
+            _ => "node256",
+        },
+    );
+}
+
+#[test]
+fn test_childs_tiers_survive_shrink_and_clone() {
+    let mut art = OxidArt::new();
+    for a in [b'a', b'b', b'c'] {
+        // A Node16, a Node48 and a Node256
+        let count = match a {
+            b'a' => 16,
+            b'b' => 48,
+            _ => 256,
+        };
+        for radix in 0..count {
+            art.set(
+                Bytes::copy_from_slice(&[a, radix as u8]),
+                Bytes::from_static(b"v"),
+            );
+        }
+    }
+    for radix in (0..=255u8).step_by(2) {
+        art.del(Bytes::copy_from_slice(&[b'c', radix]));
+    }
+    art.shrink_to_fit();
+    art.verify_invariants().unwrap();
+
+    let mut clone = art.clone();
+    for (a, count) in [(b'a', 16), (b'b', 48), (b'c', 256)] {
+        for radix in 0..count {
+            let key = Bytes::copy_from_slice(&[a, radix as u8]);
+            let expected = (a != b'c' || radix % 2 == 1).then(|| Bytes::from_static(b"v"));
+            assert_eq!(art.get(key.clone()), expected);
+            assert_eq!(clone.get(key), expected);
+        }
+    }
+}
+
+// ============ Tests Debug ============
+
+#[test]
+fn test_debug_renders_node_layout() {
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"));
+    art.set(Bytes::from_static(b"user:2"), Bytes::from_static(b"bob"));
+    art.set(Bytes::from_static(b"bin\n\xff"), Bytes::new());
+
+    let rendered = format!("{art:?}");
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert_eq!(lines[0], "OxidArt { len: 3, nodes: 5 }");
+    assert!(lines[1].starts_with("#") && lines[1].ends_with(" \"\""));
+    assert!(
+        lines[2].starts_with("  [b] #") && lines[2].ends_with(" \"in\\n\\xff\" value: 0 bytes")
+    );
+    assert!(lines[3].starts_with("  [u] #") && lines[3].ends_with(" \"ser:\""));
+    assert!(lines[4].starts_with("    [1] #") && lines[4].ends_with(" \"\" value: 5 bytes"));
+    assert!(lines[5].starts_with("    [2] #") && lines[5].ends_with(" \"\" value: 3 bytes"));
+    assert_eq!(lines.len(), 6);
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_debug_shows_expirations() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(1000);
+    art.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+    art.set_ttl(
+        Bytes::from_static(b"b"),
+        Duration::from_secs(10),
+        Bytes::from_static(b"2"),
+    );
+    art.set_ttl(
+        Bytes::from_static(b"c"),
+        Duration::from_secs(60),
+        Bytes::from_static(b"3"),
+    );
+    art.set_now(1030);
+
+    let rendered = format!("{art:?}");
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert!(lines[2].ends_with(" \"\" value: 1 bytes"));
+    assert!(lines[3].ends_with(" \"\" value: 1 bytes, expired at 1010"));
+    assert!(lines[4].ends_with(" \"\" value: 1 bytes, expires at 1060"));
+}
+
+// ============ Tests PartialEq ============
+
+#[test]
+fn test_eq_compares_logical_contents() {
+    let pairs: Vec<(Bytes, Bytes)> = (0..500u32)
+        .map(|i| (Bytes::from(format!("key:{i}")), Bytes::from(i.to_string())))
+        .collect();
+
+    // Same contents, different insertion orders and inline capacities
+    let mut forward = OxidArt::new();
+    for (key, val) in &pairs {
+        forward.set(key.clone(), val.clone());
+    }
+    let mut backward = OxidArtN::<4>::new();
+    for (key, val) in pairs.iter().rev() {
+        backward.set(key.clone(), val.clone());
+    }
+    assert_eq!(forward, backward);
+    assert_eq!(forward, OxidArt::from_sorted(forward.iter()));
+
+    // The empty key participates
+    forward.set(Bytes::new(), Bytes::from_static(b"root"));
+    assert_ne!(forward, backward);
+    backward.set(Bytes::new(), Bytes::from_static(b"root"));
+    assert_eq!(forward, backward);
+
+    backward.set(Bytes::from_static(b"key:7"), Bytes::from_static(b"changed"));
+    assert_ne!(forward, backward);
+    backward.set(Bytes::from_static(b"key:7"), Bytes::from_static(b"7"));
+    backward.set(Bytes::from_static(b"key:500"), Bytes::from_static(b"500"));
+    assert_ne!(forward, backward);
+    backward.del(Bytes::from_static(b"key:500"));
+    assert_eq!(forward, backward);
+    assert_eq!(OxidArt::new(), OxidArtN::<4>::new());
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_eq_ignores_expirations() {
+    use std::time::Duration;
+
+    let mut with_ttl = OxidArt::new();
+    with_ttl.set_now(1000);
+    with_ttl.set_ttl(
+        Bytes::from_static(b"a"),
+        Duration::from_secs(60),
+        Bytes::from_static(b"1"),
+    );
+    with_ttl.set_ttl(
+        Bytes::from_static(b"b"),
+        Duration::from_secs(5),
+        Bytes::from_static(b"2"),
+    );
+    let mut plain = OxidArt::new();
+    plain.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+    plain.set(Bytes::from_static(b"b"), Bytes::from_static(b"2"));
+    assert_eq!(with_ttl, plain);
+
+    // "b" expired but is not cleaned up yet
+    with_ttl.set_now(1010);
+    assert_ne!(with_ttl, plain);
+    plain.del(Bytes::from_static(b"b"));
+    assert_eq!(with_ttl, plain);
+}
+
+// ============ Tests cas ============
+
+#[test]
+fn test_cas() {
+    let mut art = OxidArt::new();
+    let key = Bytes::from_static(b"counter");
+
+    assert!(!art.cas(
+        key.clone(),
+        Some(Bytes::from_static(b"0")),
+        Bytes::from_static(b"1")
+    ));
+    assert_eq!(art.len(), 0);
+    assert!(art.cas(key.clone(), None, Bytes::from_static(b"0")));
+    assert!(!art.cas(key.clone(), None, Bytes::from_static(b"x")));
+    assert_eq!(art.get(key.clone()), Some(Bytes::from_static(b"0")));
+
+    // Retry loop: read, compute, swap
+    for _ in 0..10 {
+        loop {
+            let read = art.get(key.clone());
+            let n: u32 = std::str::from_utf8(read.as_ref().unwrap())
+                .unwrap()
+                .parse()
+                .unwrap();
+            if art.cas(key.clone(), read, Bytes::from((n + 1).to_string())) {
+                break;
+            }
+        }
+    }
+    assert_eq!(art.get(key.clone()), Some(Bytes::from_static(b"10")));
+    assert!(!art.cas(
+        key.clone(),
+        Some(Bytes::from_static(b"9")),
+        Bytes::from_static(b"x")
+    ));
+
+    // On a prefix of stored keys and on the empty key
+    art.set(Bytes::from_static(b"count"), Bytes::from_static(b"a"));
+    assert!(art.cas(Bytes::from_static(b"coun"), None, Bytes::from_static(b"b")));
+    assert!(art.cas(Bytes::new(), None, Bytes::from_static(b"root")));
+    assert!(art.cas(
+        Bytes::new(),
+        Some(Bytes::from_static(b"root")),
+        Bytes::new()
+    ));
+    assert_eq!(art.get(Bytes::new()), Some(Bytes::new()));
+    assert_eq!(art.len(), 4);
+    art.verify_invariants().unwrap();
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_cas_expired_counts_as_absent() {
+    use std::time::Duration;
+
+    let mut art = OxidArt::new();
+    art.set_now(1000);
+    let key = Bytes::from_static(b"lease");
+    art.set_ttl(
+        key.clone(),
+        Duration::from_secs(10),
+        Bytes::from_static(b"a"),
+    );
+    assert!(!art.cas(key.clone(), None, Bytes::from_static(b"b")));
+
+    art.set_now(1011);
+    assert!(!art.cas(
+        key.clone(),
+        Some(Bytes::from_static(b"a")),
+        Bytes::from_static(b"b")
+    ));
+    assert!(art.cas(key.clone(), None, Bytes::from_static(b"b")));
+    assert_eq!(art.remaining_ttl(key.clone()), Some(Duration::MAX));
+    assert_eq!(art.len(), 1);
+}
+
+// ============ Tests replace ============
+
+#[test]
+fn test_replace_returns_previous_value() {
+    let mut art = OxidArt::new();
+    assert_eq!(
+        art.replace(Bytes::from_static(b"user:1"), Bytes::from_static(b"a")),
+        None
+    );
+    assert_eq!(
+        art.replace(Bytes::from_static(b"user:1"), Bytes::from_static(b"b")),
+        Some(Bytes::from_static(b"a"))
+    );
+    // Split path: inserted on an intermediate node, nothing was displaced
+    assert_eq!(
+        art.replace(Bytes::from_static(b"user"), Bytes::from_static(b"c")),
+        None
+    );
+    assert_eq!(
+        art.replace(Bytes::from_static(b"user"), Bytes::from_static(b"d")),
+        Some(Bytes::from_static(b"c"))
+    );
+    assert_eq!(art.replace(Bytes::new(), Bytes::from_static(b"root")), None);
+    assert_eq!(
+        art.replace(Bytes::new(), Bytes::new()),
+        Some(Bytes::from_static(b"root"))
+    );
+    assert_eq!(art.len(), 3);
+    assert_eq!(
+        art.get(Bytes::from_static(b"user:1")),
+        Some(Bytes::from_static(b"b"))
+    );
+    art.verify_invariants().unwrap();
+}
+
+// ============ Tests deln_with ============
+
+#[test]
+fn test_deln_with_hands_over_entries() {
+    let mut art = OxidArt::new();
+    let mut expected = std::collections::BTreeMap::new();
+    // Two-byte suffixes, up to 64 children per node: grown child blocks are freed too
+    for i in 0..300u32 {
+        let key = Bytes::from(format!("s:{}", char::from_u32(0x100 + i).unwrap()));
+        art.set(key.clone(), Bytes::from(i.to_string()));
+        expected.insert(key, Bytes::from(i.to_string()));
+    }
+    art.set(Bytes::from_static(b"s:"), Bytes::from_static(b"target"));
+    expected.insert(Bytes::from_static(b"s:"), Bytes::from_static(b"target"));
+    art.set(Bytes::from_static(b"s"), Bytes::from_static(b"kept"));
+    art.set(Bytes::from_static(b"t:1"), Bytes::from_static(b"kept"));
+    assert!(art.memory_stats().grown_child_nodes > 0);
+
+    let mut removed = std::collections::BTreeMap::new();
+    let count = art.deln_with(Bytes::from_static(b"s:"), |key, val| {
+        assert!(removed.insert(key, val).is_none());
+    });
+    assert_eq!(count, 301);
+    assert_eq!(removed, expected);
+    assert_eq!(art.len(), 2);
+    assert_eq!(art.memory_stats().grown_child_nodes, 0);
+    art.verify_invariants().unwrap();
+
+    assert_eq!(
+        art.deln_with(Bytes::from_static(b"s:"), |_, _| unreachable!()),
+        0
+    );
+    let mut rest = Vec::new();
+    assert_eq!(
+        art.deln_with(Bytes::new(), |key, val| rest.push((key, val))),
+        2
+    );
+    rest.sort();
+    assert_eq!(
+        rest,
+        vec![
+            (Bytes::from_static(b"s"), Bytes::from_static(b"kept")),
+            (Bytes::from_static(b"t:1"), Bytes::from_static(b"kept")),
+        ]
+    );
+    assert!(art.is_empty());
+}
+
+// ============ Tests generic values ============
+
+#[test]
+fn test_generic_u64_counters() {
+    let mut art = OxidArt::<u64>::default();
+    for i in 0..200u64 {
+        art.set(Bytes::from(format!("hits:{i}")), i);
+    }
+    art.set(Bytes::from_static(b"misses"), 7);
+    assert_eq!(art.get(Bytes::from_static(b"hits:42")), Some(42));
+    assert!(art.update(Bytes::from_static(b"hits:42"), |hits| *hits += 1));
+    assert_eq!(art.get(Bytes::from_static(b"hits:42")), Some(43));
+    assert_eq!(art.get_or_default(Bytes::from_static(b"hits:new")), 0);
+
+    let hits = art.getn(Bytes::from_static(b"hits:1"));
+    assert_eq!(hits.len(), 111);
+    assert!(hits.iter().all(|(key, val)| *key == format!("hits:{val}")));
+    assert_eq!(
+        art.iter().map(|(_, val)| val).sum::<u64>(),
+        (0..200).sum::<u64>() + 1 + 7
+    );
+
+    assert_eq!(art.del(Bytes::from_static(b"misses")), Some(7));
+    assert_eq!(art.deln(Bytes::from_static(b"hits:")), 201);
+    assert!(art.is_empty());
+    art.verify_invariants().unwrap();
+}
+
+#[test]
+fn test_generic_arc_values() {
+    use std::sync::Arc;
+
+    #[derive(Debug, PartialEq)]
+    struct User {
+        name: &'static str,
+        age: u8,
+    }
+
+    let mut art = OxidArt::<Arc<User>>::default();
+    let alice = Arc::new(User {
+        name: "alice",
+        age: 30,
+    });
+    art.set(Bytes::from_static(b"user:1"), alice.clone());
+    art.set(
+        Bytes::from_static(b"user:2"),
+        Arc::new(User {
+            name: "bob",
+            age: 25,
+        }),
+    );
+    assert!(Arc::ptr_eq(
+        &art.get(Bytes::from_static(b"user:1")).unwrap(),
+        &alice
+    ));
+    assert_eq!(Arc::strong_count(&alice), 2);
+
+    let mut names = Vec::new();
+    art.deln_with(Bytes::from_static(b"user:"), |_, user| {
+        names.push(user.name)
+    });
+    names.sort();
+    assert_eq!(names, vec!["alice", "bob"]);
+    assert_eq!(Arc::strong_count(&alice), 1);
+    assert!(art.is_empty());
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_generic_values_expire() {
+    let mut art = OxidArt::<u64>::default();
+    art.set_now(100);
+    art.set_ttl(
+        Bytes::from_static(b"session:1"),
+        std::time::Duration::from_secs(10),
+        1,
+    );
+    art.set(Bytes::from_static(b"session:2"), 2);
+    assert_eq!(art.get(Bytes::from_static(b"session:1")), Some(1));
+
+    art.set_now(111);
+    assert_eq!(art.get(Bytes::from_static(b"session:1")), None);
+    assert_eq!(
+        art.getn(Bytes::from_static(b"session:")),
+        vec![(Bytes::from_static(b"session:2"), 2)]
+    );
+    art.set_ttl(
+        Bytes::from_static(b"session:3"),
+        std::time::Duration::from_secs(5),
+        3,
+    );
+    art.set_now(200);
+    assert_eq!(art.evict_all_expired(), 1);
+    assert_eq!(art.len(), 1);
+    art.verify_invariants().unwrap();
+}
+
+// ============ Tests prefix_iter ============
+
+#[test]
+fn test_prefix_iter_matches_getn() {
+    let mut art = OxidArt::new();
+    for i in 0..300u32 {
+        art.set(Bytes::from(format!("user:{i}")), Bytes::from(i.to_string()));
+        art.set(Bytes::from(format!("post:{i}")), Bytes::from_static(b"x"));
+    }
+    art.set(Bytes::new(), Bytes::from_static(b"root"));
+    art.set(Bytes::from_static(b"us"), Bytes::from_static(b"short"));
+
+    for prefix in [
+        &b""[..],
+        b"u",
+        b"user:",
+        b"user:1",
+        b"user:29",
+        b"user:299",
+        b"p",
+    ] {
+        let prefix = Bytes::copy_from_slice(prefix);
+        assert_eq!(
+            art.prefix_iter(prefix.clone()).collect::<Vec<_>>(),
+            art.getn(prefix)
+        );
+    }
+    assert_eq!(art.prefix_iter(Bytes::from_static(b"user:3000")).count(), 0);
+    assert_eq!(art.prefix_iter(Bytes::from_static(b"zzz")).count(), 0);
+
+    let first: Vec<Bytes> = art
+        .prefix_iter(Bytes::from_static(b"user:2"))
+        .filter(|(_, val)| val.len() == 3)
+        .map(|(key, _)| key)
+        .take(3)
+        .collect();
+    assert_eq!(
+        first,
+        vec![
+            Bytes::from_static(b"user:200"),
+            Bytes::from_static(b"user:201"),
+            Bytes::from_static(b"user:202"),
+        ]
+    );
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_prefix_iter_skips_expired() {
+    let mut art = OxidArt::new();
+    art.set_now(100);
+    art.set(Bytes::from_static(b"s:1"), Bytes::from_static(b"live"));
+    art.set_ttl(
+        Bytes::from_static(b"s:2"),
+        std::time::Duration::from_secs(5),
+        Bytes::from_static(b"gone"),
+    );
+    art.set_now(200);
+    assert_eq!(
+        art.prefix_iter(Bytes::from_static(b"s:"))
+            .collect::<Vec<_>>(),
+        vec![(Bytes::from_static(b"s:1"), Bytes::from_static(b"live"))]
+    );
+}
+
+// ============ Tests with_capacity ============
+
+#[test]
+fn test_with_capacity_avoids_slab_growth() {
+    let mut art = OxidArt::with_capacity(10_000);
+    let capacity = art.capacity();
+    assert!(capacity >= 10_000);
+    for i in 0..5000u32 {
+        art.set(Bytes::from(format!("key:{i}")), Bytes::from_static(b"v"));
+    }
+    assert_eq!(art.capacity(), capacity);
+    assert_eq!(art.len(), 5000);
+    art.verify_invariants().unwrap();
+
+    let mut small = OxidArt::with_capacity(0);
+    small.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+    assert_eq!(
+        small.get(Bytes::from_static(b"a")),
+        Some(Bytes::from_static(b"1"))
+    );
+    assert!(OxidArt::new().capacity() >= 1024);
+}
+
+// ============ Tests into_iter ============
+
+#[test]
+fn test_into_iter_drains_every_entry() {
+    let mut art = OxidArt::new();
+    let mut expected = std::collections::BTreeMap::new();
+    // Two-byte suffixes, up to 64 children per node, then decimal ones
+    let keys = (0..300u32)
+        .map(|i| format!("k:{}", char::from_u32(0x100 + i).unwrap()))
+        .chain((300..500u32).map(|i| format!("k:{i}")));
+    for (i, key) in keys.enumerate() {
+        let key = Bytes::from(key);
+        art.set(key.clone(), Bytes::from(i.to_string()));
+        expected.insert(key, Bytes::from(i.to_string()));
+    }
+    art.set(Bytes::new(), Bytes::from_static(b"root"));
+    expected.insert(Bytes::new(), Bytes::from_static(b"root"));
+    assert!(art.memory_stats().grown_child_nodes > 0);
+
+    let buffer = art.get(Bytes::from_static(b"k:420")).unwrap();
+    let drained: Vec<(Bytes, Bytes)> = art.into_iter().collect();
+    assert_eq!(drained, expected.into_iter().collect::<Vec<_>>());
+    // The value was moved out, not copied
+    let moved = &drained.iter().find(|(key, _)| key == "k:420").unwrap().1;
+    assert_eq!(moved.as_ptr(), buffer.as_ptr());
+
+    let mut art = OxidArt::new();
+    art.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"));
+    let mut count = 0;
+    for (key, val) in art {
+        assert_eq!(
+            (key, val),
+            (Bytes::from_static(b"a"), Bytes::from_static(b"1"))
+        );
+        count += 1;
+    }
+    assert_eq!(count, 1);
+    assert_eq!(OxidArt::new().into_iter().next(), None);
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_into_iter_drops_expired() {
+    let mut art = OxidArt::new();
+    art.set_now(100);
+    art.set(Bytes::from_static(b"live"), Bytes::from_static(b"1"));
+    art.set_ttl(
+        Bytes::from_static(b"old"),
+        std::time::Duration::from_secs(1),
+        Bytes::from_static(b"2"),
+    );
+    art.set_now(200);
+    assert_eq!(
+        art.into_iter().collect::<Vec<_>>(),
+        vec![(Bytes::from_static(b"live"), Bytes::from_static(b"1"))]
+    );
+}
+
+// ============ Tests snapshot ============
+
+#[test]
+fn test_snapshot_is_unaffected_by_writes() {
+    let mut art = OxidArt::with_max_entries(1000);
+    for i in 0..200u32 {
+        art.set(Bytes::from(format!("user:{i}")), Bytes::from(i.to_string()));
+    }
+    // Same defaults as `OxidArt`
+    let snapshot: crate::Snapshot = art.snapshot();
+    let before = art.iter().collect::<Vec<_>>();
+
+    art.set(
+        Bytes::from_static(b"user:0"),
+        Bytes::from_static(b"changed"),
+    );
+    art.set(Bytes::from_static(b"post:1"), Bytes::from_static(b"new"));
+    art.deln(Bytes::from_static(b"user:1"));
+    assert_eq!(art.len(), 200 - 111 + 1);
+
+    assert_eq!(snapshot.len(), 200);
+    assert_eq!(
+        snapshot.get(Bytes::from_static(b"user:0")),
+        Some(Bytes::from_static(b"0"))
+    );
+    assert!(snapshot.contains_key(Bytes::from_static(b"user:150")));
+    assert!(!snapshot.contains_key(Bytes::from_static(b"post:1")));
+    assert_eq!(snapshot.getn(Bytes::from_static(b"user:1")).len(), 111);
+    assert_eq!(
+        snapshot.prefix_iter(Bytes::from_static(b"user:1")).count(),
+        111
+    );
+    assert_eq!(snapshot.iter().collect::<Vec<_>>(), before);
+
+    // Values are shared with the tree, not copied
+    let live = art.get(Bytes::from_static(b"user:42")).unwrap();
+    let frozen = snapshot.get(Bytes::from_static(b"user:42")).unwrap();
+    assert_eq!(live.as_ptr(), frozen.as_ptr());
+}
+
+#[cfg(feature = "ttl")]
+#[test]
+fn test_snapshot_freezes_the_clock() {
+    let mut art = OxidArt::new();
+    art.set_now(100);
+    art.set_ttl(
+        Bytes::from_static(b"session"),
+        std::time::Duration::from_secs(10),
+        Bytes::from_static(b"token"),
+    );
+    let snapshot = art.snapshot();
+    art.set_now(200);
+    assert_eq!(art.get(Bytes::from_static(b"session")), None);
+    assert_eq!(snapshot.now(), 100);
+    assert_eq!(
+        snapshot.get(Bytes::from_static(b"session")),
+        Some(Bytes::from_static(b"token"))
+    );
+}
+
+// ============ Tests wal ============
+
+#[cfg(feature = "wal")]
+fn wal_path(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("oxidart-{}-{name}.wal", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[cfg(feature = "wal")]
+#[test]
+fn test_wal_replays_writes() {
+    let path = wal_path("replay");
+    let mut tree = OxidArt::open_with_wal(&path).unwrap();
+    assert!(tree.is_empty());
+    for i in 0..100u32 {
+        tree.set(Bytes::from(format!("user:{i}")), Bytes::from(i.to_string()))
+            .unwrap();
+    }
+    tree.set(Bytes::from_static(b"user:7"), Bytes::from_static(b"seven"))
+        .unwrap();
+    assert_eq!(
+        tree.del(Bytes::from_static(b"user:8")).unwrap(),
+        Some(Bytes::from_static(b"8"))
+    );
+    assert_eq!(tree.deln(Bytes::from_static(b"user:9")).unwrap(), 11);
+    let expected: Vec<(Bytes, Bytes)> = tree.iter().collect();
+    drop(tree);
+
+    let tree = OxidArt::open_with_wal(&path).unwrap();
+    assert_eq!(tree.iter().collect::<Vec<_>>(), expected);
+    assert_eq!(
+        tree.get(Bytes::from_static(b"user:7")),
+        Some(Bytes::from_static(b"seven"))
+    );
+    assert_eq!(tree.len(), 88);
+    drop(tree);
+
+    // Replaying twice gives the same tree
+    let tree = OxidArt::open_with_wal(&path).unwrap();
+    assert_eq!(tree.iter().collect::<Vec<_>>(), expected);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "wal")]
+#[test]
+fn test_wal_drops_torn_tail() {
+    use std::io::Write;
+
+    let path = wal_path("torn");
+    let mut tree = OxidArt::open_with_wal(&path).unwrap();
+    tree.set(Bytes::from_static(b"a"), Bytes::from_static(b"1"))
+        .unwrap();
+    tree.set(Bytes::from_static(b"b"), Bytes::from_static(b"2"))
+        .unwrap();
+    drop(tree);
+    let complete_len = std::fs::metadata(&path).unwrap().len();
+
+    // A record cut in the middle of its value
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap();
+    file.write_all(&[14, 0, 0, 0, 1, 1, 0, 0, 0, b'c', 5, 0])
+        .unwrap();
+    drop(file);
+
+    let mut tree = OxidArt::open_with_wal(&path).unwrap();
+    assert_eq!(tree.len(), 2);
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), complete_len);
+    tree.set(Bytes::from_static(b"c"), Bytes::from_static(b"3"))
+        .unwrap();
+    drop(tree);
+
+    let tree = OxidArt::open_with_wal(&path).unwrap();
+    assert_eq!(
+        tree.get(Bytes::from_static(b"c")),
+        Some(Bytes::from_static(b"3"))
+    );
+    assert_eq!(tree.len(), 3);
+    std::fs::remove_file(&path).unwrap();
+
+    std::fs::write(&path, b"not a log").unwrap();
+    let err = OxidArt::open_with_wal(&path).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "wal")]
+#[test]
+fn test_wal_compaction() {
+    let path = wal_path("compact");
+    let mut tree = OxidArt::open_with_wal(&path).unwrap();
+    for round in 0..10u32 {
+        for i in 0..50u32 {
+            tree.set(
+                Bytes::from(format!("k:{i}")),
+                Bytes::from(round.to_string()),
+            )
+            .unwrap();
+        }
+    }
+    tree.deln(Bytes::from_static(b"k:4")).unwrap();
+    let expected: Vec<(Bytes, Bytes)> = tree.iter().collect();
+    let before = std::fs::metadata(&path).unwrap().len();
+
+    // Leftover of a compaction that crashed before its rename
+    let mut leftover = path.clone().into_os_string();
+    leftover.push(".compact");
+    std::fs::write(&leftover, vec![0xAB; before as usize]).unwrap();
+
+    tree.compact_wal().unwrap();
+    assert!(std::fs::metadata(&path).unwrap().len() < before / 5);
+    tree.set(Bytes::from_static(b"k:new"), Bytes::from_static(b"x"))
+        .unwrap();
+    drop(tree);
+
+    let tree = OxidArt::open_with_wal(&path).unwrap();
+    assert_eq!(tree.len(), expected.len() + 1);
+    assert_eq!(
+        tree.get(Bytes::from_static(b"k:new")),
+        Some(Bytes::from_static(b"x"))
+    );
+    let mut tree = tree.into_inner();
+    tree.del(Bytes::from_static(b"k:new"));
+    assert_eq!(tree.iter().collect::<Vec<_>>(), expected);
+    std::fs::remove_file(&path).unwrap();
+}
+
+// ============ Tests depth ============
+
+#[test]
+fn test_depth_tracks_splits_and_recompression() {
+    let mut art = OxidArt::new();
+    assert_eq!((art.node_count(), art.depth()), (1, 1));
+
+    art.set(Bytes::from_static(b"user"), Bytes::from_static(b"1"));
+    assert_eq!((art.node_count(), art.depth()), (2, 2));
+    art.set(Bytes::from_static(b"uso"), Bytes::from_static(b"2"));
+    // Root, "us", then "er" and "o"
+    assert_eq!((art.node_count(), art.depth()), (4, 3));
+    art.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"3"));
+    assert_eq!((art.node_count(), art.depth()), (5, 4));
+
+    // "us" is left with a single child and merged into it
+    art.del(Bytes::from_static(b"uso"));
+    assert_eq!((art.node_count(), art.depth()), (3, 3));
+    art.del(Bytes::from_static(b"user"));
+    assert_eq!((art.node_count(), art.depth()), (2, 2));
+    art.del(Bytes::from_static(b"user:1"));
+    assert_eq!((art.node_count(), art.depth()), (1, 1));
+}
+
 // ============ Tests avec dictionnaire français ============
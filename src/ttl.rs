@@ -0,0 +1,226 @@
+//! Queries and updates of the expiration of stored entries.
+//!
+//! Expirations are absolute timestamps compared against `now`, which only moves
+//! when `set_now`, `advance` or `tick` is called: every remaining duration is
+//! relative to the last update of the clock, not to the system time.
+
+use core::time::Duration;
+
+use bytes::Bytes;
+
+use crate::{NO_EXPIRY, OxidArtN};
+
+impl<const N: usize, V: Clone> OxidArtN<N, V> {
+    /// Returns how long a key has left to live.
+    ///
+    /// The remaining time is `expiration - now` in whole seconds, so a key
+    /// reaching zero is still live until the clock moves past its expiration.
+    /// The tree is only borrowed, an expired entry is not cleaned up.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to look up.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Duration::MAX)` for a key without expiration (as set by `set`).
+    /// - `Some(remaining)` for a live key with an expiration.
+    /// - `None` if the key is absent or expired.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    /// use std::time::Duration;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set_now(1000);
+    /// tree.set_ttl(Bytes::from_static(b"session"), Duration::from_secs(60), Bytes::from_static(b"data"));
+    ///
+    /// tree.advance(15);
+    /// assert_eq!(tree.remaining_ttl(Bytes::from_static(b"session")), Some(Duration::from_secs(45)));
+    /// ```
+    pub fn remaining_ttl(&self, key: Bytes) -> Option<Duration> {
+        let node = self.get_node(self.find_key_node(&key)?);
+        let &(_, expires_at) = node.val.as_ref()?;
+        if expires_at == NO_EXPIRY {
+            return Some(Duration::MAX);
+        }
+        let remaining = expires_at.checked_sub(self.now)?;
+        Some(Duration::from_secs(remaining))
+    }
+
+    /// Returns a lazy iterator over the entries with their remaining time to live, in ascending key order.
+    ///
+    /// Walks the tree like `iter`, expired entries being skipped. The remaining
+    /// time is computed as by `remaining_ttl`, from the last update of the clock.
+    ///
+    /// # Returns
+    ///
+    /// An iterator of `(key, value, remaining)`, `remaining` being `None` for an
+    /// entry without expiration (as set by `set`).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    /// use std::time::Duration;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set_now(1000);
+    /// tree.set(Bytes::from_static(b"config"), Bytes::from_static(b"v1"));
+    /// tree.set_ttl(Bytes::from_static(b"session"), Duration::from_secs(60), Bytes::from_static(b"data"));
+    ///
+    /// for (key, _val, remaining) in tree.iter_with_ttl() {
+    ///     match remaining {
+    ///         Some(ttl) => println!("{key:?} expires in {}s", ttl.as_secs()),
+    ///         None => println!("{key:?} never expires"),
+    ///     }
+    /// }
+    /// ```
+    pub fn iter_with_ttl(&self) -> impl Iterator<Item = (Bytes, V, Option<Duration>)> + '_ {
+        let now = self.now;
+        let mut iter = self.iter();
+        core::iter::from_fn(move || {
+            let (key, node) = iter.next_node()?;
+            let (val, expires_at) = node.val.as_ref()?;
+            let remaining = (*expires_at != NO_EXPIRY)
+                .then(|| Duration::from_secs(expires_at.saturating_sub(now)));
+            Some((key, val.clone(), remaining))
+        })
+    }
+
+    /// Removes the expiration of a key, so that it lives until deleted.
+    ///
+    /// The value is left untouched, like the `PERSIST` command of Redis.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to persist.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the key is live and had an expiration, `false` if it is absent,
+    /// expired or already without expiration.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    /// use std::time::Duration;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set_ttl(Bytes::from_static(b"session"), Duration::from_secs(60), Bytes::from_static(b"data"));
+    ///
+    /// assert!(tree.persist(Bytes::from_static(b"session")));
+    /// assert_eq!(tree.remaining_ttl(Bytes::from_static(b"session")), Some(Duration::MAX));
+    /// ```
+    pub fn persist(&mut self, key: Bytes) -> bool {
+        let Some(expires_at) = self.live_expiration_mut(&key) else {
+            return false;
+        };
+        if *expires_at == NO_EXPIRY {
+            return false;
+        }
+        *expires_at = NO_EXPIRY;
+        if let Some(lru) = self.lru.as_mut() {
+            lru.touch(&key);
+        }
+        true
+    }
+
+    /// Sets or replaces the expiration of an existing key, without changing its value.
+    ///
+    /// The new expiration is `now + ttl`, saturating like `set_ttl`: a TTL too
+    /// large to be represented means the key never expires, and a zero TTL
+    /// deletes the key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to update.
+    /// * `ttl` - Duration after which the key expires, from now.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the key was live, `false` if it is absent or expired (nothing is stored then).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    /// use std::time::Duration;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set_now(1000);
+    /// tree.set(Bytes::from_static(b"token"), Bytes::from_static(b"abc"));
+    ///
+    /// assert!(tree.expire(Bytes::from_static(b"token"), Duration::from_secs(30)));
+    /// assert_eq!(tree.remaining_ttl(Bytes::from_static(b"token")), Some(Duration::from_secs(30)));
+    /// ```
+    pub fn expire(&mut self, key: Bytes, ttl: Duration) -> bool {
+        let now = self.now;
+        let Some(expires_at) = self.live_expiration_mut(&key) else {
+            return false;
+        };
+        if ttl.is_zero() {
+            self.del(key);
+            return true;
+        }
+        *expires_at = now.saturating_add(ttl.as_secs());
+        if let Some(lru) = self.lru.as_mut() {
+            lru.touch(&key);
+        }
+        true
+    }
+
+    /// Deletes every expired entry in a single traversal of the tree.
+    ///
+    /// Expired entries are otherwise only removed lazily, when `get` or a write
+    /// meets them. This is the deterministic full sweep, meant for graceful
+    /// shutdown or before `serialize`: the tree is walked once in post-order,
+    /// freeing and recompressing nodes as it goes, so `len` then only counts
+    /// live entries. An entry expires once `now` is past its expiration, as
+    /// for every read.
+    ///
+    /// This is O(n); prefer letting reads clean up on the hot path.
+    ///
+    /// # Returns
+    ///
+    /// The exact number of deleted entries.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    /// use std::time::Duration;
+    ///
+    /// let mut tree = OxidArt::new();
+    /// tree.set_now(1000);
+    /// tree.set_ttl(Bytes::from_static(b"a"), Duration::from_secs(10), Bytes::from_static(b"1"));
+    /// tree.set(Bytes::from_static(b"b"), Bytes::from_static(b"2"));
+    ///
+    /// tree.advance(60);
+    /// assert_eq!(tree.evict_all_expired(), 1);
+    /// assert_eq!(tree.len(), 1);
+    /// ```
+    pub fn evict_all_expired(&mut self) -> usize {
+        let now = self.now;
+        self.prune_where(&[], |_, node| node.is_expired(now))
+    }
+
+    /// Expiration timestamp of the live value stored under `key`
+    fn live_expiration_mut(&mut self, key: &[u8]) -> Option<&mut u64> {
+        let idx = self.find_key_node(key)?;
+        let now = self.now;
+        let node = self.get_node_mut(idx);
+        if node.is_expired(now) {
+            return None;
+        }
+        node.val.as_mut().map(|(_, expires_at)| expires_at)
+    }
+}
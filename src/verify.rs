@@ -0,0 +1,103 @@
+//! Structural invariant checks, meant for tests and fuzzing.
+
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::OxidArtN;
+use crate::node_childs::ChildAble;
+
+impl<const N: usize, V: Clone> OxidArtN<N, V> {
+    /// Checks the structural invariants of the tree.
+    ///
+    /// Walks every node reachable from the root and verifies that:
+    /// - every child index points to a live node, reached exactly once
+    /// - radixes are unique within a node, every child is found again by its
+    ///   radix, and the grown tiers list them sorted by radix
+    /// - no grown child tier is left empty
+    /// - no non-root node is collapsible (no value and a single child) or a
+    ///   dead leaf (no value and no child)
+    /// - an empty root has no compression
+    /// - no node in the slab is unreachable from the root
+    /// - the `len` counter matches the number of stored values
+    ///
+    /// This is O(n) and intended for tests and fuzzing, to detect the first
+    /// operation that corrupts the tree.
+    ///
+    /// # Returns
+    ///
+    /// `Err` with a description of the first violation found.
+    pub fn verify_invariants(&self) -> Result<(), String> {
+        if !self.map.contains(self.root_idx as usize) {
+            return Err(format!("root {} is not in the slab", self.root_idx));
+        }
+        let root = self.get_node(self.root_idx);
+        if root.val.is_none() && root.childs.is_empty() && !root.compression.is_empty() {
+            return Err("empty root has a compression".to_string());
+        }
+
+        let mut seen_nodes = BTreeSet::new();
+        let mut stack = vec![self.root_idx];
+        seen_nodes.insert(self.root_idx);
+
+        while let Some(node_idx) = stack.pop() {
+            let node = self.get_node(node_idx);
+            let radixes: Vec<(u8, u32)> = node.childs.iter().collect();
+
+            if !node.childs.is_inline() && radixes.is_empty() {
+                return Err(format!("node {node_idx} keeps an empty grown child tier"));
+            }
+            if !radixes.is_sorted_by(|a, b| a.0 < b.0) {
+                return Err(format!(
+                    "children of node {node_idx} are not sorted by radix"
+                ));
+            }
+
+            let mut seen_radixes = BTreeSet::new();
+            for &(radix, child_idx) in &radixes {
+                if !seen_radixes.insert(radix) {
+                    return Err(format!("node {node_idx} has duplicate radix {radix:#04x}"));
+                }
+                if node.childs.find(radix) != Some(child_idx) {
+                    return Err(format!(
+                        "node {node_idx} does not find its child {child_idx} by radix {radix:#04x}"
+                    ));
+                }
+                if !self.map.contains(child_idx as usize) {
+                    return Err(format!(
+                        "node {node_idx} points to missing child {child_idx} (radix {radix:#04x})"
+                    ));
+                }
+                if !seen_nodes.insert(child_idx) {
+                    return Err(format!("node {child_idx} is reachable more than once"));
+                }
+                stack.push(child_idx);
+            }
+
+            if node_idx != self.root_idx && node.val.is_none() {
+                match radixes.len() {
+                    0 => return Err(format!("node {node_idx} has no value and no child")),
+                    1 => return Err(format!("node {node_idx} has no value and a single child")),
+                    _ => {}
+                }
+            }
+        }
+
+        if seen_nodes.len() != self.map.len() {
+            return Err(format!(
+                "{} nodes are unreachable from the root",
+                self.map.len() - seen_nodes.len()
+            ));
+        }
+        let value_count = self.value_count();
+        if self.len != value_count {
+            return Err(format!(
+                "len is {} but {value_count} values are stored",
+                self.len
+            ));
+        }
+        Ok(())
+    }
+}
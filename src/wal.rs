@@ -0,0 +1,345 @@
+//! Write-ahead log for crash recovery, enabled with the `wal` feature.
+//!
+//! # Format
+//!
+//! All integers are little-endian. The log starts with a header:
+//!
+//! | Field   | Size | Content |
+//! |---------|------|---------|
+//! | magic   | 4    | `b"OXWL"` |
+//! | version | 1    | [`WAL_FORMAT_VERSION`], bumped on any incompatible change |
+//!
+//! Then one record per write, in the order they were applied:
+//!
+//! | Field | Size  | Content |
+//! |-------|-------|---------|
+//! | len   | 4     | `u32` length of the rest of the record |
+//! | op    | 1     | `1` set, `2` del, `3` deln |
+//! | key   | 4 + n | `u32` length, then the key (the prefix for deln) |
+//! | value | 4 + n | only for set: `u32` length, then the value bytes |
+//!
+//! A crash can leave the last record torn. Replay stops at the first record
+//! that is incomplete or does not parse, and the log is truncated there, so the
+//! writes acknowledged before it are recovered and the next ones are appended
+//! after them. Replaying the same log always rebuilds the same tree.
+
+use alloc::vec::Vec;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+use bytes::Bytes;
+
+use crate::OxidArt;
+use crate::export::write_chunk;
+
+/// Version of the log format written by `open_with_wal`.
+pub const WAL_FORMAT_VERSION: u8 = 1;
+
+const MAGIC: &[u8; 4] = b"OXWL";
+const HEADER: [u8; 5] = [MAGIC[0], MAGIC[1], MAGIC[2], MAGIC[3], WAL_FORMAT_VERSION];
+const OP_SET: u8 = 1;
+const OP_DEL: u8 = 2;
+const OP_DELN: u8 = 3;
+
+/// One write, as stored in the log
+enum Record {
+    Set(Bytes, Bytes),
+    Del(Bytes),
+    Deln(Bytes),
+}
+
+impl Record {
+    /// Appends the length-prefixed record to `buf`
+    fn encode(&self, buf: &mut Vec<u8>) -> io::Result<()> {
+        let mut body = Vec::new();
+        match self {
+            Record::Set(key, val) => {
+                body.push(OP_SET);
+                write_chunk(&mut body, key)?;
+                write_chunk(&mut body, val)?;
+            }
+            Record::Del(key) => {
+                body.push(OP_DEL);
+                write_chunk(&mut body, key)?;
+            }
+            Record::Deln(prefix) => {
+                body.push(OP_DELN);
+                write_chunk(&mut body, prefix)?;
+            }
+        }
+        write_chunk(buf, &body)
+    }
+
+    /// Parses a record body, `None` if it is malformed
+    fn decode(mut body: Bytes) -> Option<Self> {
+        let op = *body.first()?;
+        let _ = body.split_to(1);
+        let key = take_chunk(&mut body)?;
+        let record = match op {
+            OP_SET => Record::Set(key, take_chunk(&mut body)?),
+            OP_DEL => Record::Del(key),
+            OP_DELN => Record::Deln(key),
+            _ => return None,
+        };
+        body.is_empty().then_some(record)
+    }
+
+    fn apply(self, art: &mut OxidArt) {
+        match self {
+            Record::Set(key, val) => art.set(key, val),
+            Record::Del(key) => {
+                art.del(key);
+            }
+            Record::Deln(prefix) => {
+                art.deln(prefix);
+            }
+        }
+    }
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Syncs the directory holding `path`, so a rename into it survives a power loss
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> io::Result<()> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    File::open(parent)?.sync_all()
+}
+
+/// Directories cannot be opened as files outside unix, the rename is left to the OS
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Splits a `u32` length-prefixed chunk off the front of `buf`, `None` if it is cut short
+fn take_chunk(buf: &mut Bytes) -> Option<Bytes> {
+    let len = u32::from_le_bytes(buf.get(..4)?.try_into().ok()?) as usize;
+    if buf.len() - 4 < len {
+        return None;
+    }
+    let _ = buf.split_to(4);
+    Some(buf.split_to(len))
+}
+
+/// Tree whose writes are logged to a file before being applied, see [`OxidArt::open_with_wal`].
+///
+/// Only `set`, `del` and `deln` are available as writes, so nothing reaches the
+/// tree without going through the log. Reads go through `Deref` to the tree,
+/// plus `get` which does not need `&mut`.
+///
+/// Each record is handed to the OS with a single write before the tree is
+/// updated, so it survives a crash of the process. Call `sync` to also survive
+/// a crash of the machine.
+///
+/// A write that fails halfway is cut back off the log. If even that fails, the
+/// log is poisoned: every later write returns an error, until `compact_wal`
+/// rewrites the log from the tree.
+pub struct WalArt {
+    art: OxidArt,
+    log: File,
+    path: PathBuf,
+    /// Set when a torn record could not be truncated away
+    poisoned: bool,
+}
+
+impl OxidArt {
+    /// Opens the log at `path`, creating it if needed, and rebuilds the tree it records.
+    ///
+    /// Every record of an existing log is replayed in order. A torn or
+    /// malformed tail, left by a crash in the middle of a write, is dropped and
+    /// truncated away (see the module documentation for the format). Entries
+    /// are stored without expiration.
+    ///
+    /// # Errors
+    ///
+    /// Any I/O error opening, reading or truncating the file, and `InvalidData`
+    /// if the file is not an oxidart log or has an unknown format version.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use oxidart::OxidArt;
+    /// use bytes::Bytes;
+    ///
+    /// let mut tree = OxidArt::open_with_wal("tree.wal")?;
+    /// tree.set(Bytes::from_static(b"user:1"), Bytes::from_static(b"alice"))?;
+    /// drop(tree);
+    ///
+    /// let tree = OxidArt::open_with_wal("tree.wal")?;
+    /// assert_eq!(tree.get(Bytes::from_static(b"user:1")), Some(Bytes::from_static(b"alice")));
+    /// ```
+    pub fn open_with_wal(path: impl AsRef<Path>) -> io::Result<WalArt> {
+        let path = path.as_ref().to_path_buf();
+        let mut log = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+        let mut data = Vec::new();
+        log.read_to_end(&mut data)?;
+        let data = Bytes::from(data);
+
+        let mut art = OxidArt::new();
+        if data.len() < HEADER.len() && HEADER.starts_with(&data) {
+            // New log, or a crash while writing its header
+            log.set_len(0)?;
+            log.write_all(&HEADER)?;
+        } else if !data.starts_with(MAGIC) {
+            return Err(invalid_data("not an oxidart write-ahead log"));
+        } else if data[4] != WAL_FORMAT_VERSION {
+            return Err(invalid_data("unsupported write-ahead log format version"));
+        } else {
+            let mut rest = data.slice(HEADER.len()..);
+            let mut valid_len = HEADER.len();
+            while let Some(record) = take_chunk(&mut rest).and_then(Record::decode) {
+                record.apply(&mut art);
+                valid_len = data.len() - rest.len();
+            }
+            if valid_len < data.len() {
+                log.set_len(valid_len as u64)?;
+            }
+        }
+
+        Ok(WalArt {
+            art,
+            log,
+            path,
+            poisoned: false,
+        })
+    }
+}
+
+impl WalArt {
+    /// Logs then applies a `set`, see `OxidArt::set`.
+    ///
+    /// # Errors
+    ///
+    /// Any I/O error writing the record or from a poisoned log, `InvalidInput`
+    /// for a key or value longer than `u32::MAX`. The tree is left unchanged on
+    /// error.
+    pub fn set(&mut self, key: Bytes, val: Bytes) -> io::Result<()> {
+        let record = Record::Set(key, val);
+        self.append(&record)?;
+        record.apply(&mut self.art);
+        Ok(())
+    }
+
+    /// Logs then applies a `del`, returning the removed value, see `OxidArt::del`.
+    ///
+    /// # Errors
+    ///
+    /// Any I/O error writing the record or from a poisoned log, the tree being
+    /// left unchanged.
+    pub fn del(&mut self, key: Bytes) -> io::Result<Option<Bytes>> {
+        self.append(&Record::Del(key.clone()))?;
+        Ok(self.art.del(key))
+    }
+
+    /// Logs then applies a `deln`, returning the number of removed entries, see `OxidArt::deln`.
+    ///
+    /// # Errors
+    ///
+    /// Any I/O error writing the record or from a poisoned log, the tree being
+    /// left unchanged.
+    pub fn deln(&mut self, prefix: Bytes) -> io::Result<usize> {
+        self.append(&Record::Deln(prefix.clone()))?;
+        Ok(self.art.deln(prefix))
+    }
+
+    /// Returns the value of `key`, see `OxidArt::get_slice`.
+    pub fn get(&self, key: Bytes) -> Option<Bytes> {
+        self.art.get_slice(&key)
+    }
+
+    /// Rewrites the log as one `set` record per entry of the current contents.
+    ///
+    /// Overwritten and deleted keys no longer cost replay time or disk space.
+    /// The new log is written next to the current one, synced, then renamed
+    /// over it and the directory synced, so a crash during compaction leaves
+    /// either log intact.
+    ///
+    /// # Errors
+    ///
+    /// Any I/O error writing, syncing or renaming the new log. The current log
+    /// is kept on error. On success, a poisoned log is usable again.
+    pub fn compact_wal(&mut self) -> io::Result<()> {
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".compact");
+        let tmp_path = PathBuf::from(tmp_path);
+
+        let mut buf = HEADER.to_vec();
+        for (key, val) in self.art.iter() {
+            Record::Set(key, val).encode(&mut buf)?;
+        }
+        let mut tmp = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&tmp_path)?;
+        // Drop what a crash in an earlier compaction left behind
+        tmp.set_len(0)?;
+        tmp.write_all(&buf)?;
+        tmp.sync_all()?;
+        fs::rename(&tmp_path, &self.path)?;
+        sync_parent_dir(&self.path)?;
+
+        // The renamed handle is the log now, no reopen that could fail after the swap
+        self.log = tmp;
+        self.poisoned = false;
+        Ok(())
+    }
+
+    /// Flushes the log to the disk, so the logged writes survive a power loss.
+    ///
+    /// # Errors
+    ///
+    /// Any I/O error reported by the file system.
+    pub fn sync(&self) -> io::Result<()> {
+        self.log.sync_data()
+    }
+
+    /// Path of the log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Stops logging and returns the tree.
+    pub fn into_inner(self) -> OxidArt {
+        self.art
+    }
+
+    /// Writes a record to the end of the log in a single call, cutting it back off on error
+    fn append(&mut self, record: &Record) -> io::Result<()> {
+        if self.poisoned {
+            return Err(io::Error::other(
+                "write-ahead log poisoned by a failed rollback",
+            ));
+        }
+        let mut buf = Vec::new();
+        record.encode(&mut buf)?;
+        let len = self.log.metadata()?.len();
+        if let Err(err) = self.log.write_all(&buf) {
+            // A torn record would make replay drop every record appended after it
+            if self.log.set_len(len).is_err() {
+                self.poisoned = true;
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+impl Deref for WalArt {
+    type Target = OxidArt;
+
+    fn deref(&self) -> &OxidArt {
+        &self.art
+    }
+}